@@ -0,0 +1,62 @@
+//!Benchmark of construction of errors with Copy, `&'static str` and `Cow<'static, str>` fields, they are created in ~20 ns
+//!or less without heap, allocations are checked by tests/allocations.rs.
+//!
+//!Run it by `cargo bench --bench construct`. With feature "tracing" ErrorInfo also captures id of current span from
+//!the subscriber: construction takes ~15 ns without subscriber, but inside of span it takes ~45 ns and misses the target
+//!of ~20 ns, group "construct in span" measures this case.
+
+#[macro_use]
+extern crate nes;
+extern crate criterion;
+#[cfg(feature = "tracing")]
+extern crate tracing;
+#[cfg(feature = "tracing")]
+extern crate tracing_subscriber;
+use nes::{ErrorInfo,ErrorInfoTrait};
+
+use criterion::{Criterion,criterion_group,criterion_main};
+use std::borrow::Cow;
+use std::hint::black_box;
+
+define_error!( BenchError,
+    Empty() => "empty",
+    Code(code:u32, retry:bool) => "code {}, retry {}",
+    Name(name:&'static str) => "name {}",
+    Text(text:Cow<'static, str>) => "text {}"
+);
+
+fn code(code:u32) -> result![u32,BenchError] {
+    err!(BenchError::Code, code, false)
+}
+
+fn construct(c:&mut Criterion) {
+    let mut group=c.benchmark_group("construct");
+
+    group.bench_function("create_err!() without fields", |b| b.iter(|| -> BenchError { create_err!(BenchError::Empty) }));
+    group.bench_function("create_err!() with Copy fields", |b| b.iter(|| -> BenchError { create_err!(BenchError::Code, black_box(42), true) }));
+    group.bench_function("create_err!() with &'static str", |b| b.iter(|| -> BenchError { create_err!(BenchError::Name, "config") }));
+    group.bench_function("create_err!() with Cow", |b| b.iter(|| -> BenchError { create_err!(BenchError::Text, "config") }));
+    group.bench_function("err!()", |b| b.iter(|| code(black_box(42))));
+
+    group.finish();
+}
+
+#[cfg(feature = "tracing")]
+fn construct_in_span(c:&mut Criterion) {
+    let subscriber=tracing_subscriber::fmt().with_writer(std::io::sink).finish();
+    let _guard=tracing::subscriber::set_default(subscriber);
+    let _span=tracing::info_span!("bench").entered();
+
+    let mut group=c.benchmark_group("construct in span");
+
+    group.bench_function("create_err!() with Copy fields", |b| b.iter(|| -> BenchError { create_err!(BenchError::Code, black_box(42), true) }));
+    group.bench_function("err!()", |b| b.iter(|| code(black_box(42))));
+
+    group.finish();
+}
+
+#[cfg(not(feature = "tracing"))]
+fn construct_in_span(_c:&mut Criterion) {}
+
+criterion_group!(benches, construct, construct_in_span);
+criterion_main!(benches);
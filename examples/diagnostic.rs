@@ -0,0 +1,55 @@
+//This is an example, how to report errors in user's input(config file) with source snippets
+
+#[macro_use]
+extern crate nes;
+use nes::{ErrorInfo,ErrorInfoTrait};
+use nes::diagnostic::{Diagnostic,Source};
+use std::ops::Range;
+
+define_error!( ConfigError,
+    ParseError(diagnostic:Box<Diagnostic>) => "parse error\n{}"
+);
+
+define_error!( ScriptError,
+    UnexpectedToken(span:Range<usize>, token:String) => "unexpected token \"{2}\" at {1:?}"
+);
+
+fn parse_port(source:Source) -> result![u16,ConfigError] {
+    let line=source.line(2).unwrap_or("").to_string();
+    let value=line.trim_start_matches("port = ");
+    let offset=source.text().find(value).unwrap_or(0);
+
+    match value.parse::<u16>() {
+        Ok(port) => ok!(port),
+        Err(_) => {
+            let diagnostic=Diagnostic::new("expected integer", source)
+                .with_label(offset..offset+value.len(), "this is a string")
+                .with_note("port must be in range 1..65535");
+
+            err!(ConfigError::ParseError, Box::new(diagnostic))
+        }
+    }
+}
+
+fn check_script(source:&Source) -> result![ScriptError] {
+    match source.text().find(';') {
+        Some(offset) => err!(ScriptError::UnexpectedToken, offset..offset+1, ";".to_string()),
+        None => ok!(),
+    }
+}
+
+fn main() {
+    let source=Source::new("server.conf", "host = \"localhost\"\nport = \"80\"\n");
+
+    match parse_port(source) {
+        Ok(port) => println!("port is {}",port),
+        Err(e) => println!("{}",e),
+    }
+
+    //Error has span, so it can be shown with the line of source
+    let script=Source::new("script.txt", "let x = 5 +;");
+
+    if let Err(e)=check_script(&script) {
+        println!("{}",e.with_source(&script));
+    }
+}
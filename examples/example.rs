@@ -1,5 +1,4 @@
 //This is an example, how to work with NES
-#![feature(box_patterns)]
 
 #[macro_use]
 extern crate nes;
@@ -40,7 +39,7 @@ fn read_arg() -> result![String,CommonError] {//Or Result<String,CommonError>
     };
 
     if !file_name.ends_with(".rs") {
-        return err!(CommonError::IncorrectExtension, file_name, ".rs".to_string())
+        return err!(CommonError::IncorrectExtension, file_name, ".rs")
     }
 
     ok!(file_name)
@@ -72,11 +71,14 @@ fn read_file(file_name:String) -> result![Vec<String>,ReadFileError] {//Or Resul
 fn main() {
     match process() {
         Ok(_) => {},
-        Err(CommonError::IncorrectExtension(_,file_name, extension)) => println!("incorrect extension {}",extension),
+        Err(CommonError::IncorrectExtension(_,_, extension)) => println!("incorrect extension {}",extension),
         Err(e) => {
             match e {
-                CommonError::ReadFileError(_, box ReadFileError::ReadFileError(_,ref io_error, ref file)) => println!("can not read file \"{}\"",file),
-                _ => {println!("{}",e)} //or println!("{:?}",e)
+                CommonError::ReadFileError(_, ref read_file_error) => match **read_file_error {
+                    ReadFileError::ReadFileError(_,_, ref file) => println!("can not read file \"{}\"",file),
+                    _ => println!("{}",e),
+                },
+                _ => {println!("{}",e)} //or println!("{:?}",e), or println!("{}",nes::report::SourceReport::new(&e)) to see lines of code
             }
         }
     }
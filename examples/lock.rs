@@ -4,7 +4,7 @@
 extern crate nes;
 use nes::{ErrorInfo,ErrorInfoTrait};
 
-use std::sync::{Mutex,Arc,RwLock};
+use std::sync::{Mutex,Arc};
 use std::thread;
 
 
@@ -41,7 +41,7 @@ fn main_function() -> result![Error] {
 
     println!("Value is {}",*value_guard);
 
-    join_handle.join();
+    let _ = join_handle.join(); //The thread has panicked, so join returns Err
 
     ok!()
 }
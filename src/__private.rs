@@ -0,0 +1,543 @@
+//!Items, that are used by macros. They are not the part of API.
+
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::{Mutex,OnceLock};
+
+use {ErrorInfo,ErrorInfoTrait,ErrorMetadata,ErrorTrail,TrailItem};
+
+pub type BoxDisplay<'a> = Box<dyn fmt::Display + 'a>;
+
+#[cfg(feature = "miette")]
+pub extern crate miette;
+#[cfg(feature = "eyre")]
+pub extern crate eyre;
+#[cfg(feature = "anyhow")]
+pub extern crate anyhow;
+#[cfg(feature = "serde")]
+pub extern crate serde;
+#[cfg(feature = "tracing")]
+pub extern crate tracing;
+#[cfg(feature = "i18n")]
+pub extern crate fluent;
+#[cfg(feature = "i18n")]
+pub extern crate unic_langid;
+#[cfg(feature = "testing")]
+pub extern crate proptest;
+#[cfg(feature = "wasm")]
+pub extern crate wasm_bindgen;
+#[cfg(feature = "wasm")]
+pub extern crate js_sys;
+#[cfg(feature = "defmt")]
+pub extern crate defmt;
+#[cfg(feature = "heapless")]
+pub extern crate heapless;
+#[cfg(feature = "http-client-reqwest")]
+pub extern crate reqwest;
+#[cfg(feature = "http-client-ureq")]
+pub extern crate ureq;
+#[cfg(feature = "sqlx")]
+pub extern crate sqlx;
+#[cfg(feature = "rusqlite")]
+pub extern crate rusqlite;
+#[cfg(feature = "diesel")]
+pub extern crate diesel;
+#[cfg(feature = "serde_json")]
+pub extern crate serde_json;
+#[cfg(feature = "serde_yaml")]
+pub extern crate serde_yaml;
+#[cfg(feature = "toml")]
+pub extern crate toml;
+#[cfg(feature = "clap")]
+pub extern crate clap;
+#[cfg(feature = "stack")]
+pub extern crate libc;
+#[cfg(feature = "symbolize")]
+pub extern crate backtrace;
+
+//stringify!() of raw identifier r#type is "r#type", name of variant or field is "type"
+pub const fn ident_name(ident:&'static str) -> &'static str {
+    match ident.as_bytes() {
+        [b'r', b'#', name @ ..] => match std::str::from_utf8(name) {
+            Ok( name ) => name,
+            Err( _ ) => ident,
+        },
+        _ => ident,
+    }
+}
+
+//ErrorInfo in {:#?} of errors is written by Display
+pub struct DisplayAsDebug<'a, T:'a>(pub &'a T);
+
+impl<'a, T:fmt::Display> fmt::Debug for DisplayAsDebug<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.0, f)
+    }
+}
+
+//Display of errors writes ErrorInfo on its own line before message, line is skipped for ErrorInfo without location, like NoInfo
+pub struct InfoLine<'a, I:'a>(pub &'a I);
+
+impl<'a, I:ErrorInfoTrait> fmt::Display for InfoLine<'a, I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0.file().is_empty() && self.0.line()==0 {
+            return Ok(());
+        }
+
+        writeln!(f, "{}", self.0)
+    }
+}
+
+//lines of doc comment start with space after ///
+pub fn join_docs(lines:&[&str]) -> Option<String> {
+    if lines.is_empty() {
+        return None;
+    }
+
+    let lines:Vec<&str>=lines.iter().map(|line| line.strip_prefix(' ').unwrap_or(line)).collect();
+    Some(lines.join("\n"))
+}
+
+pub fn location<I:ErrorInfoTrait>(error_info:&I) -> ErrorInfo {
+    ErrorInfo {
+        file:error_info.file(),
+        line:error_info.line(),
+        col:error_info.col(),
+        #[cfg(feature = "tracing")]
+        span_id:error_info.span_id().and_then(::std::num::NonZeroU64::new),
+        #[cfg(feature = "tracing")]
+        trace_id:pack_trace_id(error_info.trace_id())
+    }
+}
+
+#[cfg(feature = "tracing")]
+pub fn pack_trace_id(trace_id:Option<u128>) -> [u64;2] {
+    let trace_id=trace_id.unwrap_or(0);
+    [(trace_id >> 64) as u64, trace_id as u64]
+}
+
+#[cfg(feature = "tracing")]
+pub fn unpack_trace_id(trace_id:[u64;2]) -> Option<u128> {
+    match (trace_id[0] as u128) << 64 | trace_id[1] as u128 {
+        0 => None,
+        trace_id => Some(trace_id),
+    }
+}
+
+//ErrorInfo keeps file as &'static str, names of files, that are known only at run time, are leaked once by this interner.
+//It keeps at most max_count names of up to max_length bytes, intern() returns None for other names.
+pub(crate) struct Interner {
+    names:OnceLock<Mutex<HashSet<&'static str>>>,
+    max_count:usize,
+    max_length:usize
+}
+
+impl Interner {
+    pub(crate) const fn new(max_count:usize, max_length:usize) -> Self {
+        Interner {
+            names:OnceLock::new(),
+            max_count,
+            max_length
+        }
+    }
+
+    pub(crate) fn intern(&self, name:&str) -> Option<&'static str> {
+        let mut names=match self.names.get_or_init(|| Mutex::new(HashSet::new())).lock() {
+            Ok( names ) => names,
+            Err( poisoned ) => poisoned.into_inner(),
+        };
+
+        if let Some( interned )=names.get(name) {
+            return Some(interned);
+        }
+
+        if names.len()>=self.max_count || name.len()>self.max_length {
+            return None;
+        }
+
+        let interned:&'static str=Box::leak(name.to_string().into_boxed_str());
+        names.insert(interned);
+
+        Some(interned)
+    }
+}
+
+//Source of error is the first field, that implements Error or is Box<dyn Error>, these traits select it by autoref:
+//(&&SourceField(field)).nes_source() calls SourceViaError if it is implemented, else SourceViaDynError, else SourceViaNone.
+//Method resolution tries &&SourceField, &&&SourceField, then &SourceField as self, so impls are for &SourceField, &&SourceField and SourceField.
+pub struct SourceField<'a, T:'a>(pub &'a T);
+
+pub trait SourceViaError<'a> {
+    fn nes_source(&self) -> Option<&'a (dyn Error + 'static)>;
+}
+
+impl<'a, T:Error + 'static> SourceViaError<'a> for &SourceField<'a, T> {
+    fn nes_source(&self) -> Option<&'a (dyn Error + 'static)> {
+        Some(self.0)
+    }
+}
+
+pub trait SourceViaDynError<'a> {
+    fn nes_source(&self) -> Option<&'a (dyn Error + 'static)>;
+}
+
+impl<'a> SourceViaDynError<'a> for &&SourceField<'a, Box<dyn Error + Send + Sync>> {
+    fn nes_source(&self) -> Option<&'a (dyn Error + 'static)> {
+        Some(&**self.0)
+    }
+}
+
+impl<'a> SourceViaDynError<'a> for &&SourceField<'a, Box<dyn Error + Send>> {
+    fn nes_source(&self) -> Option<&'a (dyn Error + 'static)> {
+        Some(&**self.0)
+    }
+}
+
+impl<'a> SourceViaDynError<'a> for &&SourceField<'a, Box<dyn Error>> {
+    fn nes_source(&self) -> Option<&'a (dyn Error + 'static)> {
+        Some(&**self.0)
+    }
+}
+
+pub trait SourceViaNone {
+    fn nes_source(&self) -> Option<&'static (dyn Error + 'static)> {
+        None
+    }
+}
+
+impl<'a, T:'a> SourceViaNone for SourceField<'a, T> {}
+
+//Same for ErrorInfo trail, fields, that are nes errors, push their trails.
+pub struct TrailField<'a, T:'a>(pub &'a T);
+
+pub trait TrailViaNes {
+    fn push_trail(&self, trail:&mut Vec<TrailItem>);
+}
+
+impl<'a, T:ErrorTrail> TrailViaNes for TrailField<'a, T> {
+    fn push_trail(&self, trail:&mut Vec<TrailItem>) {
+        self.0.push_error_trail(trail)
+    }
+}
+
+pub trait TrailViaNone {
+    fn push_trail(&self, _trail:&mut Vec<TrailItem>) {}
+}
+
+impl<'a, T:'a> TrailViaNone for &TrailField<'a, T> {}
+
+//err!() and try!() create errors by closure, that is called by construct(), it is #[cold] and is not inlined, so code
+//of the happy path stays small and branch of error is predicted as unlikely. Closure calls variant with ErrorInfo
+//of the scope of macro, like error_info!(), and with fields.
+#[cold]
+#[inline(never)]
+pub fn construct<E, F:FnOnce() -> E>(constructor:F) -> E {
+    constructor()
+}
+
+//inspect_err!() passes location of the error to closure, if the error is defined by define_error!(), else location of the call.
+//(&LocationField(error)).nes_location() calls LocationViaNes if error implements ErrorMetadata, else LocationViaNone.
+pub struct LocationField<'a, T:'a>(pub &'a T);
+
+pub trait LocationViaNes {
+    fn nes_location(&self) -> Option<ErrorInfo>;
+}
+
+impl<'a, T:ErrorMetadata> LocationViaNes for LocationField<'a, T> {
+    fn nes_location(&self) -> Option<ErrorInfo> {
+        Some(self.0.location())
+    }
+}
+
+pub trait LocationViaNone {
+    fn nes_location(&self) -> Option<ErrorInfo> {
+        None
+    }
+}
+
+impl<'a, T:'a> LocationViaNone for &LocationField<'a, T> {}
+
+//closure gets types of arguments from F
+pub fn inspect<E, F:FnOnce(&E, &ErrorInfo)>(error:&E, location:&ErrorInfo, inspect:F) {
+    inspect(error, location)
+}
+
+//log_err!() writes error with location of the call by tracing with feature "tracing", else to stderr
+pub fn log_error(error:&dyn fmt::Display, message:Option<fmt::Arguments>, file:&'static str, line:u32, col:u32) {
+    #[cfg(feature = "tracing")]
+    {
+        match message {
+            Some( message ) => tracing::error!(file, line, col, "{}\n{}", message, error),
+            None => tracing::error!(file, line, col, "{}", error),
+        }
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    {
+        match message {
+            Some( message ) => eprintln!("error at {} {}:{}: {}\n{}", file, line, col, message, error),
+            None => eprintln!("error at {} {}:{}:\n{}", file, line, col, error),
+        }
+    }
+}
+
+//assert_err!() panics with pattern, Debug of the error and its trail, if the error is defined by define_error!()
+#[track_caller]
+pub fn assert_err_failed(pattern:&str, error:&dyn fmt::Debug, trail:&[TrailItem]) -> ! {
+    let mut message=format!("assertion failed: error does not match `{}`\nactual error: {:?}", pattern, error);
+
+    if !trail.is_empty() {
+        message.push_str("\nerror trail:");
+
+        for item in trail {
+            message.push_str(&format!("\n    {}::{} {} {}:{}", item.error_name, item.variant_name, item.error_info.file, item.error_info.line, item.error_info.col));
+        }
+    }
+
+    panic!("{}", message)
+}
+
+//assert_err_chain!() looks for the error of type T in the chain of sources, nested errors may be stored in Box, Arc, InlineBox or PoolBox
+pub fn chain_contains<T:Error + 'static, F:Fn(&T) -> bool>(error:&(dyn Error + 'static), matches:F) -> bool {
+    let mut next=Some(error);
+
+    while let Some(error)=next {
+        let value=error.downcast_ref::<T>()
+            .or_else(|| error.downcast_ref::<Box<T>>().map(|error| &**error))
+            .or_else(|| error.downcast_ref::<::std::sync::Arc<T>>().map(|error| &**error))
+            .or_else(|| error.downcast_ref::<::inline::InlineBox<T>>().map(|error| &**error));
+
+        #[cfg(feature = "pool")]
+        let value=value.or_else(|| error.downcast_ref::<::pool::PoolBox<T>>().map(|error| &**error));
+
+        if value.is_some_and(&matches) {
+            return true;
+        }
+
+        next=error.source();
+    }
+
+    false
+}
+
+#[track_caller]
+pub fn assert_err_chain_failed(expected:&str, error:&(dyn Error + 'static)) -> ! {
+    let mut message=format!("assertion failed: error chain does not contain `{}`\nerror chain:", expected);
+    let mut next=Some(error);
+    let mut index=0;
+
+    while let Some(error)=next {
+        message.push_str(&format!("\n    {}: {}", index, error.to_string().replace('\n', "\n       ")));
+        next=error.source();
+        index+=1;
+    }
+
+    panic!("{}", message)
+}
+
+//Arguments of err!(), try!() and create_err!() are passed to fields as is, so they are coerced like arguments of function
+//(Box<[u8; 3]> into Box<[u8]>, Box<T> into Box<dyn Trait>, &Vec<T> into &[T]), and strings are converted by FromArg,
+//so &'static str may be passed to fields of type Cow<'static, str> or String without .to_string().
+//Field<T> gets type of field from call of variant, that is never evaluated, then (&&&field.arg(arg)).nes_arg() calls
+//ArgViaSame, if argument has type of field(its type may be inferred by the field, like x.into()), else ArgViaFromArg,
+//if field implements FromArg, else ArgViaPlain, that returns argument, and compiler coerces it.
+pub struct Field<T>(PhantomData<T>);
+
+impl<T> Field<T> {
+    #[inline(always)]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Field(PhantomData)
+    }
+
+    pub fn value(&self) -> T {
+        unreachable!()
+    }
+
+    #[inline(always)]
+    pub fn arg<A>(&self, arg:A) -> Arg<A, T> {
+        Arg(Cell::new(Some(arg)), PhantomData)
+    }
+}
+
+//returns value of any type for call of variant, that is never evaluated
+pub fn never<T>() -> T {
+    unreachable!()
+}
+
+pub struct Arg<A, T>(Cell<Option<A>>, PhantomData<T>);
+
+impl<A, T> Arg<A, T> {
+    #[inline(always)]
+    fn take(&self) -> A {
+        match self.0.take() {
+            Some( arg ) => arg,
+            None => unreachable!(),
+        }
+    }
+}
+
+pub trait ArgViaSame {
+    type Field;
+    fn nes_arg(&self) -> Self::Field;
+}
+
+impl<T> ArgViaSame for &&Arg<T, T> {
+    type Field = T;
+
+    #[inline(always)]
+    fn nes_arg(&self) -> T { self.take() }
+}
+
+pub trait ArgViaFromArg {
+    type Field;
+    fn nes_arg(&self) -> Self::Field;
+}
+
+impl<A, T:FromArg<A>> ArgViaFromArg for &Arg<A, T> {
+    type Field = T;
+
+    #[inline(always)]
+    fn nes_arg(&self) -> T { T::from_arg(self.take()) }
+}
+
+pub trait ArgViaPlain {
+    type Arg;
+    fn nes_arg(&self) -> Self::Arg;
+}
+
+impl<A, T> ArgViaPlain for Arg<A, T> {
+    type Arg = A;
+
+    #[inline(always)]
+    fn nes_arg(&self) -> A { self.take() }
+}
+
+pub trait FromArg<A> {
+    fn from_arg(arg:A) -> Self;
+}
+
+impl FromArg<&'static str> for Cow<'static, str> {
+    #[inline(always)]
+    fn from_arg(arg:&'static str) -> Self { Cow::Borrowed(arg) }
+}
+
+impl FromArg<String> for Cow<'static, str> {
+    #[inline(always)]
+    fn from_arg(arg:String) -> Self { Cow::Owned(arg) }
+}
+
+impl FromArg<&'static str> for String {
+    #[inline(always)]
+    fn from_arg(arg:&'static str) -> Self { arg.to_string() }
+}
+
+#[cfg(feature = "heapless")]
+impl<const N:usize> FromArg<&'static str> for heapless::String<N> {
+    #[inline(always)]
+    fn from_arg(arg:&'static str) -> Self { ::heapless::truncated(arg) }
+}
+
+//Fields are serialized by Serialize, else as string by Display, else as string by Debug.
+//(&&SerializeField(field)).nes_serialize() returns value, that is serialized, it is selected by autoref like source of error.
+#[cfg(feature = "serde")]
+pub struct SerializeField<'a, T:'a>(pub &'a T);
+
+#[cfg(feature = "serde")]
+pub trait SerializeViaSerialize<'a> {
+    type Value;
+    fn nes_serialize(&self) -> Self::Value;
+}
+
+#[cfg(feature = "serde")]
+impl<'a, T:serde::Serialize> SerializeViaSerialize<'a> for &SerializeField<'a, T> {
+    type Value = &'a T;
+    fn nes_serialize(&self) -> &'a T {
+        self.0
+    }
+}
+
+#[cfg(feature = "serde")]
+pub trait SerializeViaDisplay<'a> {
+    type Value;
+    fn nes_serialize(&self) -> Self::Value;
+}
+
+#[cfg(feature = "serde")]
+impl<'a, T:fmt::Display> SerializeViaDisplay<'a> for &&SerializeField<'a, T> {
+    type Value = SerializeDisplay<'a, T>;
+    fn nes_serialize(&self) -> SerializeDisplay<'a, T> {
+        SerializeDisplay(self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+pub trait SerializeViaDebug<'a> {
+    type Value;
+    fn nes_serialize(&self) -> Self::Value;
+}
+
+#[cfg(feature = "serde")]
+impl<'a, T:fmt::Debug> SerializeViaDebug<'a> for SerializeField<'a, T> {
+    type Value = SerializeDebug<'a, T>;
+    fn nes_serialize(&self) -> SerializeDebug<'a, T> {
+        SerializeDebug(self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+pub struct SerializeDisplay<'a, T:'a>(&'a T);
+
+#[cfg(feature = "serde")]
+impl<'a, T:fmt::Display> serde::Serialize for SerializeDisplay<'a, T> {
+    fn serialize<S:serde::Serializer>(&self, serializer:S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+pub struct SerializeDebug<'a, T:'a>(&'a T);
+
+#[cfg(feature = "serde")]
+impl<'a, T:fmt::Debug> serde::Serialize for SerializeDebug<'a, T> {
+    fn serialize<S:serde::Serializer>(&self, serializer:S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!("{:?}", self.0))
+    }
+}
+
+#[cfg(feature = "serde")]
+pub struct SerializeLocation<'a, I:'a>(pub &'a I);
+
+#[cfg(feature = "serde")]
+impl<'a, I:ErrorInfoTrait> serde::Serialize for SerializeLocation<'a, I> {
+    fn serialize<S:serde::Serializer>(&self, serializer:S) -> Result<S::Ok, S::Error> {
+        use self::serde::ser::SerializeMap;
+
+        let mut map=serializer.serialize_map(None)?;
+        map.serialize_entry("file", self.0.file())?;
+        map.serialize_entry("line", &self.0.line())?;
+        map.serialize_entry("col", &self.0.col())?;
+
+        if let Some(span_id)=self.0.span_id() {
+            map.serialize_entry("span_id", &span_id)?;
+        }
+
+        if let Some(trace_id)=self.0.trace_id() {
+            map.serialize_entry("trace_id", &format_args!("{:032x}", trace_id))?;
+        }
+
+        if let Some(host)=self.0.host() {
+            map.serialize_entry("host", host)?;
+        }
+
+        if let Some(pid)=self.0.pid() {
+            map.serialize_entry("pid", &pid)?;
+        }
+
+        map.end()
+    }
+}
@@ -0,0 +1,196 @@
+//!Aggregate sources: variants, that fail after several attempts, keep all causes.
+//!
+//!When the error means "all of these failed"(all mirrors are unavailable, all addresses refused connection), one source is not enough.
+//!Field of type `Sources<Box<OtherError>>` or `Sources<Box<dyn Error + Send + Sync>>` keeps all causes, Display writes each of them
+//!on its own numbered and indented lines, error trail fans out over trails of all causes, `source()` returns the first cause.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait,ErrorTrail};
+//!use nes::aggregate::Sources;
+//!
+//!define_error!( MirrorError,
+//!    Timeout(url:String) => "mirror {} does not respond"
+//!);
+//!
+//!define_error!( DownloadError,
+//!    AllMirrorsFailed(errors:Sources<Box<MirrorError>>) => "all mirrors failed:{}"
+//!);
+//!
+//!fn fetch(url:&str) -> result![Vec<u8>,MirrorError] {
+//!    err!(MirrorError::Timeout, url.to_string())
+//!}
+//!
+//!fn download(mirrors:&[&str]) -> result![Vec<u8>,DownloadError] {
+//!    let mut errors=Sources::new();
+//!
+//!    for mirror in mirrors {
+//!        match fetch(mirror) {
+//!            Ok( data ) => return Ok(data),
+//!            Err( error ) => errors.push(Box::new(error)),
+//!        }
+//!    }
+//!
+//!    err!(DownloadError::AllMirrorsFailed, errors)
+//!}
+//!
+//!fn main() {
+//!    let error=download(&["a.example.com", "b.example.com"]).unwrap_err();
+//!    let text=error.to_string();
+//!
+//!    assert!(text.contains("all mirrors failed:\n  [1] "));
+//!    assert!(text.contains("\n      mirror a.example.com does not respond\n  [2] "));
+//!    assert!(text.ends_with("\n      mirror b.example.com does not respond"));
+//!    assert_eq!(error.error_trail().len(), 3);
+//!}
+//! ```
+//!
+//!Output:
+//!
+//! ```text
+//!main/src/main.rs 36:4
+//!all mirrors failed:
+//!  [1] main/src/main.rs 23:4
+//!      mirror a.example.com does not respond
+//!  [2] main/src/main.rs 23:4
+//!      mirror b.example.com does not respond
+//! ```
+
+use std::error::Error;
+use std::fmt::{self,Write};
+use std::iter::FromIterator;
+use std::ops::Deref;
+
+use {ErrorTrail,TrailItem};
+
+///Cause of aggregate error, it is implemented for `Box<E>` and boxed `dyn Error`.
+pub trait AsError {
+    fn as_error(&self) -> &(dyn Error + 'static);
+}
+
+impl<E:Error + 'static> AsError for Box<E> {
+    fn as_error(&self) -> &(dyn Error + 'static) { &**self }
+}
+
+impl AsError for Box<dyn Error + Send + Sync> {
+    fn as_error(&self) -> &(dyn Error + 'static) { &**self }
+}
+
+impl AsError for Box<dyn Error + Send> {
+    fn as_error(&self) -> &(dyn Error + 'static) { &**self }
+}
+
+impl AsError for Box<dyn Error> {
+    fn as_error(&self) -> &(dyn Error + 'static) { &**self }
+}
+
+///All causes of the error, that is "all of these failed".
+#[derive(Clone, PartialEq)]
+pub struct Sources<E>(Vec<E>);
+
+impl<E> Sources<E> {
+    pub fn new() -> Self {
+        Sources(Vec::new())
+    }
+
+    pub fn push(&mut self, error:E) {
+        self.0.push(error)
+    }
+
+    pub fn into_vec(self) -> Vec<E> {
+        self.0
+    }
+}
+
+impl<E:AsError> Sources<E> {
+    ///Returns causes as `dyn Error`.
+    pub fn causes(&self) -> impl Iterator<Item = &(dyn Error + 'static)> {
+        self.0.iter().map(|error| error.as_error())
+    }
+}
+
+impl<E> Default for Sources<E> {
+    fn default() -> Self {
+        Sources::new()
+    }
+}
+
+impl<E> Deref for Sources<E> {
+    type Target = [E];
+
+    fn deref(&self) -> &[E] { &self.0 }
+}
+
+impl<E> From<Vec<E>> for Sources<E> {
+    fn from(errors:Vec<E>) -> Self {
+        Sources(errors)
+    }
+}
+
+impl<E> FromIterator<E> for Sources<E> {
+    fn from_iter<I:IntoIterator<Item = E>>(iter:I) -> Self {
+        Sources(iter.into_iter().collect())
+    }
+}
+
+impl<'a, E> IntoIterator for &'a Sources<E> {
+    type Item = &'a E;
+    type IntoIter = std::slice::Iter<'a, E>;
+
+    fn into_iter(self) -> Self::IntoIter { self.0.iter() }
+}
+
+impl<E:fmt::Display> fmt::Display for Sources<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, error) in self.0.iter().enumerate() {
+            write!(f, "\n  [{}] ", index+1)?;
+            write!(Indented { f, indent:"      " }, "{}", error)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<E:fmt::Debug> fmt::Debug for Sources<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.0.iter()).finish()
+    }
+}
+
+impl<E:AsError + fmt::Debug + fmt::Display> Error for Sources<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.0.first().map(|error| error.as_error())
+    }
+}
+
+impl<E:ErrorTrail> ErrorTrail for Sources<E> {
+    fn push_error_trail(&self, trail:&mut Vec<TrailItem>) {
+        for error in self.0.iter() {
+            error.push_error_trail(trail);
+        }
+    }
+}
+
+//writes text with indent after each line break, so nested errors with several lines stay under their number
+pub(crate) struct Indented<'a, 'b:'a> {
+    pub(crate) f:&'a mut fmt::Formatter<'b>,
+    pub(crate) indent:&'static str
+}
+
+impl<'a, 'b> Write for Indented<'a, 'b> {
+    fn write_str(&mut self, text:&str) -> fmt::Result {
+        for (index, line) in text.split('\n').enumerate() {
+            if index>0 {
+                self.f.write_char('\n')?;
+                self.f.write_str(self.indent)?;
+            }
+
+            self.f.write_str(line)?;
+        }
+
+        Ok(())
+    }
+}
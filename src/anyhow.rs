@@ -0,0 +1,53 @@
+//!Interop with anyhow(feature "anyhow"), for applications, that move from anyhow to nes step by step.
+//!
+//!Errors of nes are converted into anyhow::Error by `?` like any std::error::Error. Back, field of type `anyhow::Error`
+//!is the source of variant, like `Box<dyn Error>`, so the whole chain of anyhow(with its context) is kept in source().
+//!`impl_from_error!(anyhow::Error => AppError::Other)` lets `?` wrap anyhow::Error into the variant in code, that returns nes errors.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!extern crate anyhow;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use anyhow::Context;
+//!use std::error::Error;
+//!
+//!define_error!( AppError,
+//!    Legacy(error:anyhow::Error) => "legacy code failed: {}"
+//!);
+//!
+//!impl_from_error!(anyhow::Error => AppError::Legacy);
+//!
+//!fn legacy_load() -> anyhow::Result<u16> {
+//!    let port="http".parse::<u16>().context("bad port in config")?;
+//!    Ok(port)
+//!}
+//!
+//!fn load() -> result![u16,AppError] {
+//!    let port=legacy_load()?;
+//!    ok!(port)
+//!}
+//!
+//!fn main() {
+//!    let error=load().unwrap_err();
+//!    assert!(error.to_string().ends_with("legacy code failed: bad port in config"));
+//!
+//!    let source=error.source().unwrap();
+//!    assert_eq!(source.to_string(), "bad port in config");
+//!    assert_eq!(source.source().unwrap().to_string(), "invalid digit found in string");
+//!}
+//! ```
+
+use std::error::Error;
+
+use super::__private::anyhow;
+use __private::{SourceField,SourceViaDynError};
+
+impl<'a> SourceViaDynError<'a> for &&SourceField<'a, anyhow::Error> {
+    fn nes_source(&self) -> Option<&'a (dyn Error + 'static)> {
+        let error:&'a (dyn Error + Send + Sync + 'static)=self.0.as_ref();
+        Some(error)
+    }
+}
@@ -0,0 +1,267 @@
+//!Reporting in background thread, so slow reporters(files, syslog, network) never block threads, that handle requests.
+//!
+//!`BackgroundReporter` wraps the reporter, captures reported errors into `CapturedError` and puts them into bounded queue,
+//!its thread passes them to the wrapped reporter. If the queue is full, the error is dropped without capture and counted
+//!by `dropped()`, so burst of errors does not grow memory and does not slow down the service. `flush(timeout)` and `nes::flush_reporters(timeout)`
+//!wait, until the thread reports queued errors. When the reporter is dropped, the thread reports errors, that are left in the queue, and stops.
+//!
+//!`CapturedError` keeps text of the error, its metadata, context, sections and trail, so the reporter renders it like the original
+//!error, only typed context is not captured.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use nes::background::BackgroundReporter;
+//!use nes::reporter::{Reporter,ReportedError};
+//!use std::sync::atomic::{AtomicUsize,Ordering};
+//!use std::time::Duration;
+//!
+//!define_error!( HandlerError,
+//!    NotFound(path:String) => "page {} is not found"
+//!);
+//!
+//!static SENT:AtomicUsize=AtomicUsize::new(0);
+//!
+//!struct SlowReporter;
+//!
+//!impl Reporter for SlowReporter {
+//!    fn report(&self, error:&dyn ReportedError) {
+//!        std::thread::sleep(Duration::from_millis(10));
+//!        assert!(error.to_string().ends_with("page /index.html is not found"));
+//!        SENT.fetch_add(1, Ordering::Relaxed);
+//!    }
+//!}
+//!
+//!fn main() {
+//!    let reporter=BackgroundReporter::new(SlowReporter, 4);
+//!
+//!    for _ in 0..100 {
+//!        let error:HandlerError=create_err!(HandlerError::NotFound, "/index.html".to_string());
+//!        reporter.report(&error);
+//!    }
+//!
+//!    let dropped=reporter.dropped();
+//!    assert!(dropped>0);
+//!
+//!    drop(reporter);
+//!    assert_eq!(SENT.load(Ordering::Relaxed) as u64, 100-dropped);
+//!}
+//! ```
+
+use std::any::{Any,TypeId};
+use std::fmt;
+use std::panic::{self,AssertUnwindSafe};
+use std::sync::{Arc,Condvar,Mutex};
+use std::sync::atomic::{AtomicU64,Ordering};
+use std::sync::mpsc::{self,SyncSender,TrySendError};
+use std::thread::{self,JoinHandle};
+use std::time::{Duration,Instant};
+
+use {ErrorInfo,ErrorMetadata,ErrorTrail,Severity,TrailItem};
+use context::ContextItem;
+use reporter::{Reporter,ReportedError,Message,lock,reporting};
+use section::Section;
+
+///Reported error, that is captured with its text, metadata and trail, so it may be reported later by other thread.
+#[derive(Clone)]
+pub struct CapturedError {
+    error_name:&'static str,
+    variant_name:&'static str,
+    location:ErrorInfo,
+    code:Option<&'static str>,
+    help:Option<&'static str>,
+    url:Option<&'static str>,
+    severity:Severity,
+    note:Option<&'static str>,
+    warning:Option<&'static str>,
+    message:String,
+    text:String,
+    context:Vec<ContextItem>,
+    sections:Vec<Section>,
+    trail:Vec<(&'static str, &'static str, ErrorInfo)>
+}
+
+impl CapturedError {
+    pub fn capture(error:&dyn ReportedError) -> Self {
+        CapturedError {
+            error_name:error.error_name(),
+            variant_name:error.variant_name(),
+            location:error.location(),
+            code:error.code(),
+            help:error.help(),
+            url:error.url(),
+            severity:error.severity(),
+            note:error.note(),
+            warning:error.warning(),
+            message:Message(error).to_string(),
+            text:error.to_string(),
+            context:error.context().to_vec(),
+            sections:error.sections().to_vec(),
+            trail:error.error_trail().into_iter().map(|item| (item.error_name, item.variant_name, item.error_info)).collect()
+        }
+    }
+}
+
+impl fmt::Display for CapturedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl fmt::Debug for CapturedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CapturedError")
+            .field("error", &self.error_name)
+            .field("variant", &self.variant_name)
+            .field("message", &self.message)
+            .finish()
+    }
+}
+
+impl ErrorTrail for CapturedError {
+    fn push_error_trail(&self, trail:&mut Vec<TrailItem>) {
+        for &(error_name, variant_name, error_info) in self.trail.iter() {
+            trail.push(TrailItem { error_name, variant_name, error_info });
+        }
+    }
+}
+
+impl ErrorMetadata for CapturedError {
+    fn error_name(&self) -> &'static str { self.error_name }
+    fn variant_name(&self) -> &'static str { self.variant_name }
+    fn location(&self) -> ErrorInfo { self.location }
+    fn code(&self) -> Option<&'static str> { self.code }
+    fn help(&self) -> Option<&'static str> { self.help }
+    fn url(&self) -> Option<&'static str> { self.url }
+    fn severity(&self) -> Severity { self.severity }
+    fn fmt_message(&self, f: &mut fmt::Formatter) -> fmt::Result { f.write_str(&self.message) }
+    fn context(&self) -> &[ContextItem] { &self.context }
+    fn note(&self) -> Option<&'static str> { self.note }
+    fn warning(&self) -> Option<&'static str> { self.warning }
+    fn sections(&self) -> &[Section] { &self.sections }
+    fn typed_context(&self, _type_id:TypeId) -> Option<&(dyn Any + Send + Sync)> { None }
+}
+
+///Reporter, that passes errors to the wrapped reporter in background thread through bounded queue.
+pub struct BackgroundReporter {
+    reporter:Arc<dyn Reporter>,
+    sender:Option<SyncSender<CapturedError>>,
+    thread:Option<JoinHandle<()>>,
+    //count of errors, that are queued and are not reported yet, flush() waits until it becomes 0
+    pending:Arc<(Mutex<usize>, Condvar)>,
+    capacity:usize,
+    dropped:AtomicU64
+}
+
+impl BackgroundReporter {
+    ///Starts thread, that reports errors by the reporter, capacity is max count of errors in the queue.
+    pub fn new<R:Reporter + 'static>(reporter:R, capacity:usize) -> Self {
+        let reporter:Arc<dyn Reporter>=Arc::new(reporter);
+        let pending=Arc::new((Mutex::new(0), Condvar::new()));
+        let (sender, receiver)=mpsc::sync_channel::<CapturedError>(capacity);
+
+        let thread={
+            let reporter=reporter.clone();
+            let pending=pending.clone();
+
+            //thread is marked as reporting, so its panic is not reported to itself by the panic hook
+            thread::Builder::new().name("nes-reporter".to_string()).spawn(move || reporting(|| {
+                for error in receiver {
+                    //panic of the reporter does not stop the thread, else queued errors would be never reported
+                    let _=panic::catch_unwind(AssertUnwindSafe(|| reporter.report(&error)));
+
+                    let mut count=lock(&pending.0);
+                    *count-=1;
+                    pending.1.notify_all();
+                }
+            })).ok()
+        };
+
+        BackgroundReporter {
+            reporter,
+            sender:thread.as_ref().map(|_| sender),
+            thread,
+            pending,
+            //error, that the thread reports, is pending too, so queue of capacity 0 passes one error to the waiting thread
+            capacity:std::cmp::max(capacity, 1),
+            dropped:AtomicU64::new(0)
+        }
+    }
+
+    ///Returns count of errors, that are dropped, because the queue was full.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    ///Returns count of errors, that are queued and are not reported yet.
+    pub fn pending(&self) -> usize {
+        *lock(&self.pending.0)
+    }
+
+    fn drop_error(&self) {
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl Reporter for BackgroundReporter {
+    fn report(&self, error:&dyn ReportedError) {
+        //thread may be not started, if system is out of threads
+        let sender=match self.sender {
+            Some( ref sender ) => sender,
+            None => return self.drop_error(),
+        };
+
+        //error is counted before sending, else thread may report it before it is counted. If the queue is full,
+        //the error is dropped before capture, so burst of errors does not format errors, that are thrown away
+        {
+            let mut pending=lock(&self.pending.0);
+
+            if *pending>=self.capacity {
+                drop(pending);
+                return self.drop_error();
+            }
+
+            *pending+=1;
+        }
+
+        match sender.try_send(CapturedError::capture(error)) {
+            Ok( () ) => {},
+            Err( TrySendError::Full(_) ) | Err( TrySendError::Disconnected(_) ) => {
+                *lock(&self.pending.0)-=1;
+                self.drop_error();
+            },
+        }
+    }
+
+    ///Waits, until the thread reports all queued errors, then flushes the wrapped reporter.
+    fn flush(&self, timeout:Duration) -> bool {
+        let deadline=Instant::now()+timeout;
+        let (ref count, ref condvar)=*self.pending;
+
+        let count=match condvar.wait_timeout_while(lock(count), timeout, |count| *count>0) {
+            Ok( (count, _) ) => count,
+            Err( poisoned ) => poisoned.into_inner().0,
+        };
+
+        if *count>0 {
+            return false;
+        }
+
+        drop(count);
+        self.reporter.flush(deadline.saturating_duration_since(Instant::now()))
+    }
+}
+
+impl Drop for BackgroundReporter {
+    fn drop(&mut self) {
+        //thread reports errors, that are left in the queue, and stops, when the queue is closed
+        self.sender=None;
+
+        if let Some( thread )=self.thread.take() {
+            let _=thread.join();
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//!Errors of command line arguments, that are parsed by clap(feature "clap").
+//!
+//!`CliFailure` is created from clap::Error by From, so try!() wraps it into variant with location, like other errors, and main()
+//!of binary may return one `result![CliError]`. It keeps kind of error, argument, that is wrong, and usage, Display writes
+//!message of clap without styles and usage after it. Clap adds usage not to all errors, `with_usage(&mut command)` adds it from command. Help and version are not errors, `nes::clap::parse()` prints them and exits,
+//!like `Parser::parse()`, other errors are returned.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!extern crate clap;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use nes::clap::CliFailure;
+//!use clap::{Arg,Command,value_parser};
+//!
+//!define_error!( CliError,
+//!    Arguments(failure:CliFailure) => "bad arguments: {}"
+//!);
+//!
+//!fn port(args:&[&str]) -> result![u16,CliError] {
+//!    let command=Command::new("server").arg(Arg::new("port").long("port").value_parser(value_parser!(u16)));
+//!    let matches=try!( command.try_get_matches_from(args), CliError::Arguments );
+//!    ok!(matches.get_one::<u16>("port").copied().unwrap_or(80))
+//!}
+//!
+//!fn main() {
+//!    assert_eq!(port(&["server", "--port", "8080"]).unwrap(), 8080);
+//!
+//!    let error=port(&["server", "--port", "http"]).unwrap_err();
+//!    let CliError::Arguments(_, ref failure)=error;
+//!    assert_eq!(failure.argument(), Some("--port <port>"));
+//!    assert!(error.to_string().ends_with("bad arguments: invalid value 'http' for '--port <port>': invalid digit found in string"));
+//!
+//!    let error=port(&["server", "--verbose"]).unwrap_err();
+//!    assert!(error.to_string().ends_with("bad arguments: unexpected argument '--verbose' found\n\nUsage: server [OPTIONS]"));
+//!}
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+use super::__private::clap::{self,Command,Parser};
+use super::__private::clap::error::{ContextKind,ErrorKind};
+
+///Failure of parsing of command line arguments.
+pub struct CliFailure {
+    message:String,
+    argument:Option<String>,
+    usage:Option<String>,
+    error:clap::Error
+}
+
+impl CliFailure {
+    pub fn new(error:clap::Error) -> Self {
+        let rendered=error.render().to_string();
+        let message=rendered.lines().next().unwrap_or("");
+        let message=message.strip_prefix("error: ").unwrap_or(message).to_string();
+
+        CliFailure {
+            message,
+            argument:error.get(ContextKind::InvalidArg).map(|argument| argument.to_string()),
+            usage:error.get(ContextKind::Usage).map(|usage| usage.to_string()),
+            error
+        }
+    }
+
+    ///Sets usage of the command, if clap has not added it to the error.
+    pub fn with_usage(mut self, command:&mut Command) -> Self {
+        if self.usage.is_none() {
+            self.usage=Some(command.render_usage().to_string());
+        }
+
+        self
+    }
+
+    pub fn kind(&self) -> ErrorKind { self.error.kind() }
+    ///Returns message of clap without styles and usage.
+    pub fn message(&self) -> &str { &self.message }
+    ///Returns argument, that is wrong, like `--port <PORT>`.
+    pub fn argument(&self) -> Option<&str> { self.argument.as_deref() }
+    pub fn usage(&self) -> Option<&str> { self.usage.as_deref() }
+    pub fn exit_code(&self) -> i32 { self.error.exit_code() }
+    pub fn clap_error(&self) -> &clap::Error { &self.error }
+}
+
+impl From<clap::Error> for CliFailure {
+    fn from(error:clap::Error) -> Self {
+        CliFailure::new(error)
+    }
+}
+
+impl fmt::Display for CliFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+
+        if let Some( ref usage )=self.usage {
+            write!(f, "\n\n{}", usage)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for CliFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CliFailure")
+            .field("kind", &self.error.kind())
+            .field("message", &self.message)
+            .field("argument", &self.argument)
+            .field("usage", &self.usage)
+            .finish()
+    }
+}
+
+impl Error for CliFailure {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+///Parses arguments of process, prints help or version and exits, if they are requested, else returns failure.
+pub fn parse<P:Parser>() -> Result<P, CliFailure> {
+    match P::try_parse() {
+        Ok( parsed ) => Ok(parsed),
+        Err( error ) => match error.kind() {
+            ErrorKind::DisplayHelp | ErrorKind::DisplayVersion | ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand => error.exit(),
+            _ => Err(CliFailure::new(error).with_usage(&mut P::command())),
+        },
+    }
+}
@@ -0,0 +1,330 @@
+//!Typed access to keys of toml config files(feature "config").
+//!
+//!`Config` parses the file once and returns values by full key path, like `server.tls.cert`. When the key is missing, has value
+//!of other type or value, that does not fit into the type, `ConfigFailure` keeps the key path, name of the file, expected type and
+//!`Diagnostic`, that points to the value(or to the table, where the key is missing), so user sees not only what is wrong, but where.
+//!Failure is stored in variant like other errors, try!() adds location in the rust code.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use nes::config::{Config,ConfigFailure};
+//!use nes::diagnostic::Source;
+//!
+//!define_error!( ServerError,
+//!    Config(failure:ConfigFailure) => "bad config: {}"
+//!);
+//!
+//!#[derive(Debug)]
+//!struct Settings {
+//!    port:u16,
+//!    cert:String
+//!}
+//!
+//!fn load(source:&Source) -> result![Settings,ServerError] {
+//!    let config=try!( Config::parse(source), ServerError::Config );
+//!
+//!    ok!(Settings {
+//!        port:try!( config.get("server.port"), ServerError::Config ),
+//!        cert:try!( config.get("server.tls.cert"), ServerError::Config ),
+//!    })
+//!}
+//!
+//!fn main() {
+//!    let source=Source::new("server.toml", "[server]\nport = 8080\n\n[server.tls]\ncert = \"server.pem\"\n");
+//!    let settings=load(&source).unwrap();
+//!    assert_eq!((settings.port, settings.cert.as_str()), (8080, "server.pem"));
+//!
+//!    let source=Source::new("server.toml", "[server]\nport = \"http\"\n");
+//!    let error=load(&source).unwrap_err();
+//!    let ServerError::Config(_, ref failure)=error;
+//!    assert_eq!((failure.key(), failure.file(), failure.expected()), ("server.port", "server.toml", Some("u16")));
+//!    assert!(error.to_string().ends_with("bad config: error: invalid type of `server.port`: expected u16, found string\n --> server.toml:2:8\n  |\n2 | port = \"http\"\n  |        ^^^^^^ expected u16"));
+//!
+//!    let source=Source::new("server.toml", "[server]\nport = 8080\n\n[server.tls]\nkey = \"server.key\"\n");
+//!    let error=load(&source).unwrap_err();
+//!    assert!(error.to_string().contains("error: missing key `server.tls.cert` of type string\n --> server.toml:4:1"));
+//!}
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+use std::path::PathBuf;
+
+use super::__private::toml;
+use super::__private::toml::de::{DeTable,DeValue};
+use super::__private::toml::Spanned;
+
+use diagnostic::{Diagnostic,Source};
+
+///Parsed toml file, that returns values by key path.
+pub struct Config<'a> {
+    source:&'a Source,
+    root:Spanned<DeTable<'a>>
+}
+
+impl<'a> Config<'a> {
+    ///Parses text of the source, syntax error is returned as failure with diagnostic.
+    pub fn parse(source:&'a Source) -> Result<Self, ConfigFailure> {
+        match DeTable::parse(source.text()) {
+            Ok( root ) => Ok(Config { source, root }),
+            Err( error ) => Err(ConfigFailure::syntax(&error, source)),
+        }
+    }
+
+    pub fn source(&self) -> &Source { self.source }
+
+    ///Returns value of the key, key path is separated by dots, like `server.tls.cert`.
+    pub fn get<T:FromConfig>(&self, key:&str) -> Result<T, ConfigFailure> {
+        match self.get_opt(key)? {
+            Some( value ) => Ok(value),
+            None => {
+                let (table, name)=self.parent(key)?;
+                let message=format!("missing key `{}` of type {}", key, T::EXPECTED);
+                let diagnostic=match table {
+                    Some( span ) => Diagnostic::new(message, self.source.clone()).with_label(span, format!("`{}` is not found in this table", name)),
+                    None => Diagnostic::new(message, self.source.clone()),
+                };
+
+                Err(ConfigFailure::new(ConfigFailureKind::MissingKey, key, Some(T::EXPECTED), None, diagnostic))
+            }
+        }
+    }
+
+    ///Returns None, if the key is missing, value of other type is still the failure.
+    pub fn get_opt<T:FromConfig>(&self, key:&str) -> Result<Option<T>, ConfigFailure> {
+        let value=match self.find(key)? {
+            Some( value ) => value,
+            None => return Ok(None),
+        };
+
+        match T::from_value(value.get_ref()) {
+            Ok( value ) => Ok(Some(value)),
+            Err( Invalid::Type ) => {
+                let found=value.get_ref().type_str();
+                let message=format!("invalid type of `{}`: expected {}, found {}", key, T::EXPECTED, found);
+                Err(self.invalid(ConfigFailureKind::InvalidType, key, T::EXPECTED, Some(found), message, value.span()))
+            }
+            Err( Invalid::Value(reason) ) => {
+                let message=format!("invalid value of `{}`: {}", key, reason);
+                Err(self.invalid(ConfigFailureKind::InvalidValue, key, T::EXPECTED, None, message, value.span()))
+            }
+        }
+    }
+
+    ///Returns default, if the key is missing.
+    pub fn get_or<T:FromConfig>(&self, key:&str, default:T) -> Result<T, ConfigFailure> {
+        Ok(self.get_opt(key)?.unwrap_or(default))
+    }
+
+    fn invalid(&self, kind:ConfigFailureKind, key:&str, expected:&'static str, found:Option<&'static str>, message:String, span:Range<usize>) -> ConfigFailure {
+        let diagnostic=Diagnostic::new(message, self.source.clone()).with_label(span, format!("expected {}", expected));
+        ConfigFailure::new(kind, key, Some(expected), found, diagnostic)
+    }
+
+    //walks tables of the key path, value, that is not a table, in the middle of the path is the failure
+    fn find(&self, key:&str) -> Result<Option<&Spanned<DeValue<'a>>>, ConfigFailure> {
+        let mut table=self.root.get_ref();
+        let mut path_end=0;
+        let mut names=key.split('.').peekable();
+
+        while let Some( name )=names.next() {
+            path_end+=if path_end==0 { name.len() } else { name.len()+1 };
+
+            let value=match lookup(table, name) {
+                Some( value ) => value,
+                None => return Ok(None),
+            };
+
+            if names.peek().is_none() {
+                return Ok(Some(value));
+            }
+
+            table=match *value.get_ref() {
+                DeValue::Table( ref table ) => table,
+                ref other => {
+                    let path=&key[..path_end];
+                    let message=format!("invalid type of `{}`: expected table, found {}", path, other.type_str());
+                    return Err(self.invalid(ConfigFailureKind::InvalidType, path, "table", Some(other.type_str()), message, value.span()));
+                }
+            };
+        }
+
+        Ok(None)
+    }
+
+    //returns span of the table, that must contain the key, and name of the key in it
+    fn parent<'k>(&self, key:&'k str) -> Result<(Option<Range<usize>>, &'k str), ConfigFailure> {
+        match key.rfind('.') {
+            Some( dot ) => match self.find(&key[..dot])? {
+                Some( table ) => Ok((Some(table.span()), &key[dot+1..])),
+                None => Ok((None, key)),
+            },
+            None => Ok((None, key)),
+        }
+    }
+}
+
+fn lookup<'t, 'a>(table:&'t DeTable<'a>, name:&str) -> Option<&'t Spanned<DeValue<'a>>> {
+    table.iter()
+        .find(|&(key, _)| key.get_ref().as_ref()==name)
+        .map(|(_, value)| value)
+}
+
+///Why value can not be converted, it is returned by `FromConfig::from_value`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Invalid {
+    ///Value has other type, like string instead of integer.
+    Type,
+    ///Value has right type, but is wrong, like integer, that does not fit into u16. String is the reason.
+    Value(String)
+}
+
+///Type of value of config, it is implemented for strings, paths, bools, numbers and can be implemented for types of application.
+pub trait FromConfig: Sized {
+    ///Name of the type, that is shown to user, like `u16` or `string`.
+    const EXPECTED:&'static str;
+
+    fn from_value(value:&DeValue) -> Result<Self, Invalid>;
+}
+
+impl FromConfig for String {
+    const EXPECTED:&'static str = "string";
+
+    fn from_value(value:&DeValue) -> Result<Self, Invalid> {
+        value.as_str().map(|value| value.to_string()).ok_or(Invalid::Type)
+    }
+}
+
+impl FromConfig for PathBuf {
+    const EXPECTED:&'static str = "path";
+
+    fn from_value(value:&DeValue) -> Result<Self, Invalid> {
+        value.as_str().map(PathBuf::from).ok_or(Invalid::Type)
+    }
+}
+
+impl FromConfig for bool {
+    const EXPECTED:&'static str = "boolean";
+
+    fn from_value(value:&DeValue) -> Result<Self, Invalid> {
+        value.as_bool().ok_or(Invalid::Type)
+    }
+}
+
+impl FromConfig for f64 {
+    const EXPECTED:&'static str = "float";
+
+    fn from_value(value:&DeValue) -> Result<Self, Invalid> {
+        match *value {
+            DeValue::Float( ref float ) => float.as_str().parse().map_err(|_| Invalid::Value(format!("{} is not a number", float.as_str()))),
+            DeValue::Integer(..) => i64::from_value(value).map(|value| value as f64),
+            _ => Err(Invalid::Type),
+        }
+    }
+}
+
+impl FromConfig for i64 {
+    const EXPECTED:&'static str = "i64";
+
+    fn from_value(value:&DeValue) -> Result<Self, Invalid> {
+        let integer=value.as_integer().ok_or(Invalid::Type)?;
+        i64::from_str_radix(integer.as_str(), integer.radix()).map_err(|_| Invalid::Value(format!("{} does not fit in i64", integer.as_str())))
+    }
+}
+
+macro_rules! impl_from_config_for_integer {
+    ($($integer:ident)*) => {$(
+        impl FromConfig for $integer {
+            const EXPECTED:&'static str = stringify!($integer);
+
+            fn from_value(value:&DeValue) -> Result<Self, Invalid> {
+                let integer=i64::from_value(value)?;
+                ::std::convert::TryFrom::try_from(integer).map_err(|_| Invalid::Value(format!("{} does not fit in {}", integer, stringify!($integer))))
+            }
+        }
+    )*};
+}
+
+impl_from_config_for_integer!(i8 i16 i32 isize u8 u16 u32 u64 usize);
+
+///Kind of failure of config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFailureKind {
+    ///File is not valid toml.
+    Syntax,
+    MissingKey,
+    ///Value has other type, like string instead of integer.
+    InvalidType,
+    ///Value has right type, but is wrong, like integer, that does not fit into u16.
+    InvalidValue
+}
+
+///Failure of config with key path, file, expected type and diagnostic, that points to the problem in the file.
+pub struct ConfigFailure {
+    kind:ConfigFailureKind,
+    key:String,
+    expected:Option<&'static str>,
+    found:Option<&'static str>,
+    diagnostic:Box<Diagnostic>,
+    error:Option<Box<toml::de::Error>>
+}
+
+impl ConfigFailure {
+    pub fn new(kind:ConfigFailureKind, key:&str, expected:Option<&'static str>, found:Option<&'static str>, diagnostic:Diagnostic) -> Self {
+        ConfigFailure {
+            kind,
+            key:key.to_string(),
+            expected,
+            found,
+            diagnostic:Box::new(diagnostic),
+            error:None
+        }
+    }
+
+    fn syntax(error:&toml::de::Error, source:&Source) -> Self {
+        ConfigFailure {
+            error:Some(Box::new(error.clone())),
+            ..ConfigFailure::new(ConfigFailureKind::Syntax, "", None, None, Diagnostic::from_parse_error(error, source.clone()))
+        }
+    }
+
+    pub fn kind(&self) -> ConfigFailureKind { self.kind }
+    ///Returns full key path, like `server.tls.cert`, it is empty for syntax errors.
+    pub fn key(&self) -> &str { &self.key }
+    ///Returns name of the config file.
+    pub fn file(&self) -> &str { self.diagnostic.source().name() }
+    pub fn expected(&self) -> Option<&'static str> { self.expected }
+    ///Returns type of the value, that is found instead of expected.
+    pub fn found(&self) -> Option<&'static str> { self.found }
+    pub fn diagnostic(&self) -> &Diagnostic { &self.diagnostic }
+}
+
+impl fmt::Display for ConfigFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.diagnostic)
+    }
+}
+
+impl fmt::Debug for ConfigFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ConfigFailure")
+            .field("kind", &self.kind)
+            .field("key", &self.key)
+            .field("file", &self.file())
+            .field("expected", &self.expected)
+            .field("found", &self.found)
+            .field("message", &self.diagnostic.message())
+            .finish()
+    }
+}
+
+impl Error for ConfigFailure {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.error.as_ref().map(|error| &**error as &(dyn Error + 'static))
+    }
+}
@@ -0,0 +1,327 @@
+//!Key-value context of errors. Chain of locations does not tell, which request or which user has failed,
+//!so any nes error may get typed key-value pairs by `error.add_context("request_id", id)`.
+//!
+//!`add_context` wraps error into `WithContext`, that derefs to the error, so it can be matched like `match *error { ... }`.
+//!Display shows the context after the error, Logfmt writes it as additional keys, with feature "serde" it is serialized as
+//!`{"context":{"request_id":42},"error":{...}}`.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use nes::context::{AddContext,WithContext};
+//!
+//!define_error!( HandlerError,
+//!    NotFound(path:String) => "page {} is not found"
+//!);
+//!
+//!fn handle(request_id:u64, path:&str) -> Result<(), WithContext<HandlerError>> {
+//!    let error:HandlerError=create_err!(HandlerError::NotFound, path.to_string());
+//!
+//!    Err(error.add_context("request_id", request_id).add_context("user", "bob"))
+//!}
+//!
+//!fn main() {
+//!    let error=handle(42, "/index.html").unwrap_err();
+//!    assert_eq!(error.get_context("request_id"), Some(&nes::context::ContextValue::U64(42)));
+//!    println!("{}",error);
+//!}
+//! ```
+//!
+//!Output:
+//!
+//! ```text
+//!main/src/main.rs 11:27
+//!page /index.html is not found
+//!context: request_id=42 user="bob"
+//! ```
+//!
+//!Middleware may attach strongly-typed values instead of string keys by `add_typed_context(value)`, one value of each type is kept,
+//!`get_typed_context::<T>()` returns it back. Typed values are not written by Display, they are for code, that handles the error.
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use nes::context::{AddContext,WithContext};
+//!
+//!define_error!( HandlerError,
+//!    NotFound(path:String) => "page {} is not found"
+//!);
+//!
+//!#[derive(Debug, PartialEq)]
+//!struct RequestId(u64);
+//!
+//!fn handle(path:&str) -> Result<(), WithContext<HandlerError>> {
+//!    let error:HandlerError=create_err!(HandlerError::NotFound, path.to_string());
+//!    Err(error.add_typed_context(RequestId(42)))
+//!}
+//!
+//!fn main() {
+//!    let error=handle("/index.html").unwrap_err();
+//!    assert_eq!(error.get_typed_context::<RequestId>(), Some(&RequestId(42)));
+//!    assert_eq!(nes::context::get_typed_context::<u32, _>(&error), None);
+//!}
+//! ```
+
+use std::any::{Any,TypeId};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::ops::{Deref,DerefMut};
+
+use {ErrorInfo,ErrorMetadata,ErrorTrail,Severity,TrailItem};
+use section::Section;
+
+///Typed value of context.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContextValue {
+    String(String),
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    Bool(bool)
+}
+
+impl fmt::Display for ContextValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ContextValue::String(ref value) => write!(f, "{:?}", value),
+            ContextValue::I64(value) => write!(f, "{}", value),
+            ContextValue::U64(value) => write!(f, "{}", value),
+            ContextValue::F64(value) => write!(f, "{}", value),
+            ContextValue::Bool(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+macro_rules! impl_from_value{
+    ( $variant:ident, $target:ty; $( $t:ty ),* ) => {
+        $(
+            impl From<$t> for ContextValue {
+                fn from(value:$t) -> Self {
+                    ContextValue::$variant(value as $target)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_value!(I64, i64; i8, i16, i32, i64, isize);
+impl_from_value!(U64, u64; u8, u16, u32, u64, usize);
+impl_from_value!(F64, f64; f32, f64);
+
+impl From<bool> for ContextValue {
+    fn from(value:bool) -> Self {
+        ContextValue::Bool(value)
+    }
+}
+
+impl From<String> for ContextValue {
+    fn from(value:String) -> Self {
+        ContextValue::String(value)
+    }
+}
+
+impl<'a> From<&'a str> for ContextValue {
+    fn from(value:&'a str) -> Self {
+        ContextValue::String(value.to_string())
+    }
+}
+
+impl<'a> From<&'a String> for ContextValue {
+    fn from(value:&'a String) -> Self {
+        ContextValue::String(value.clone())
+    }
+}
+
+///Key and value of context.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextItem {
+    pub key:&'static str,
+    pub value:ContextValue
+}
+
+///Error with key-value context, it is created by `add_context`.
+pub struct WithContext<E> {
+    error:E,
+    context:Vec<ContextItem>,
+    typed:HashMap<TypeId, Box<dyn Any + Send + Sync>>
+}
+
+impl<E> WithContext<E> {
+    pub fn new(error:E) -> Self {
+        WithContext {
+            error,
+            context:Vec::new(),
+            typed:HashMap::new()
+        }
+    }
+
+    ///Adds key-value pair, value of existing key is replaced.
+    pub fn add_context<V:Into<ContextValue>>(mut self, key:&'static str, value:V) -> Self {
+        let value=value.into();
+
+        match self.context.iter_mut().find(|item| item.key==key) {
+            Some( item ) => item.value=value,
+            None => self.context.push(ContextItem { key, value }),
+        }
+
+        self
+    }
+
+    pub fn get_context(&self, key:&str) -> Option<&ContextValue> {
+        self.context.iter().find(|item| item.key==key).map(|item| &item.value)
+    }
+
+    ///Adds typed value, value of the same type is replaced.
+    pub fn add_typed_context<T:Any + Send + Sync>(mut self, value:T) -> Self {
+        self.typed.insert(TypeId::of::<T>(), Box::new(value));
+        self
+    }
+
+    pub fn get_typed_context<T:Any>(&self) -> Option<&T> {
+        self.typed.get(&TypeId::of::<T>()).and_then(|value| value.downcast_ref::<T>())
+    }
+
+    pub fn context(&self) -> &[ContextItem] { &self.context }
+    pub fn error(&self) -> &E { &self.error }
+    pub fn into_error(self) -> E { self.error }
+}
+
+impl<E> Deref for WithContext<E> {
+    type Target = E;
+
+    fn deref(&self) -> &E { &self.error }
+}
+
+impl<E> DerefMut for WithContext<E> {
+    fn deref_mut(&mut self) -> &mut E { &mut self.error }
+}
+
+impl<E:fmt::Display> fmt::Display for WithContext<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.error)?;
+
+        if !self.context.is_empty() {
+            write!(f, "\ncontext:")?;
+        }
+
+        for item in self.context.iter() {
+            write!(f, " {}={}", item.key, item.value)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<E:fmt::Debug> fmt::Debug for WithContext<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.error)?;
+
+        for item in self.context.iter() {
+            write!(f, "\n{}:{:?}", item.key, item.value)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<E:Error> Error for WithContext<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.error.source()
+    }
+}
+
+impl<E:ErrorTrail> ErrorTrail for WithContext<E> {
+    fn push_error_trail(&self, trail:&mut Vec<TrailItem>) {
+        self.error.push_error_trail(trail)
+    }
+}
+
+impl<E:ErrorMetadata> ErrorMetadata for WithContext<E> {
+    fn error_name(&self) -> &'static str { self.error.error_name() }
+    fn variant_name(&self) -> &'static str { self.error.variant_name() }
+    fn location(&self) -> ErrorInfo { self.error.location() }
+    fn code(&self) -> Option<&'static str> { self.error.code() }
+    fn help(&self) -> Option<&'static str> { self.error.help() }
+    fn url(&self) -> Option<&'static str> { self.error.url() }
+    fn severity(&self) -> Severity { self.error.severity() }
+    fn fmt_message(&self, f: &mut fmt::Formatter) -> fmt::Result { self.error.fmt_message(f) }
+    fn context(&self) -> &[ContextItem] { &self.context }
+
+    fn typed_context(&self, type_id:TypeId) -> Option<&(dyn Any + Send + Sync)> {
+        match self.typed.get(&type_id) {
+            Some( value ) => Some(&**value),
+            None => self.error.typed_context(type_id),
+        }
+    }
+
+    fn note(&self) -> Option<&'static str> { self.error.note() }
+    fn warning(&self) -> Option<&'static str> { self.error.warning() }
+    fn sections(&self) -> &[Section] { self.error.sections() }
+}
+
+///Adds key-value and typed context to nes errors.
+pub trait AddContext: Sized {
+    fn add_context<V:Into<ContextValue>>(self, key:&'static str, value:V) -> WithContext<Self>;
+    fn add_typed_context<T:Any + Send + Sync>(self, value:T) -> WithContext<Self>;
+}
+
+impl<E:ErrorMetadata> AddContext for E {
+    fn add_context<V:Into<ContextValue>>(self, key:&'static str, value:V) -> WithContext<Self> {
+        WithContext::new(self).add_context(key, value)
+    }
+
+    fn add_typed_context<T:Any + Send + Sync>(self, value:T) -> WithContext<Self> {
+        WithContext::new(self).add_typed_context(value)
+    }
+}
+
+///Returns typed context of any nes error, for example, of `&dyn ReportedError` in reporters.
+pub fn get_typed_context<T:Any, E:ErrorMetadata + ?Sized>(error:&E) -> Option<&T> {
+    error.typed_context(TypeId::of::<T>()).and_then(|value| (value as &dyn Any).downcast_ref::<T>())
+}
+
+#[cfg(feature = "serde")]
+mod serialize {
+    use super::{ContextValue,WithContext};
+    use __private::serde::ser::{Serialize,SerializeMap,Serializer};
+
+    impl Serialize for ContextValue {
+        fn serialize<S:Serializer>(&self, serializer:S) -> Result<S::Ok, S::Error> {
+            match *self {
+                ContextValue::String(ref value) => serializer.serialize_str(value),
+                ContextValue::I64(value) => serializer.serialize_i64(value),
+                ContextValue::U64(value) => serializer.serialize_u64(value),
+                ContextValue::F64(value) => serializer.serialize_f64(value),
+                ContextValue::Bool(value) => serializer.serialize_bool(value),
+            }
+        }
+    }
+
+    struct Context<'a>(&'a [super::ContextItem]);
+
+    impl<'a> Serialize for Context<'a> {
+        fn serialize<S:Serializer>(&self, serializer:S) -> Result<S::Ok, S::Error> {
+            let mut map=serializer.serialize_map(Some(self.0.len()))?;
+
+            for item in self.0.iter() {
+                map.serialize_entry(item.key, &item.value)?;
+            }
+
+            map.end()
+        }
+    }
+
+    impl<E:Serialize> Serialize for WithContext<E> {
+        fn serialize<S:Serializer>(&self, serializer:S) -> Result<S::Ok, S::Error> {
+            let mut map=serializer.serialize_map(Some(2))?;
+            map.serialize_entry("context", &Context(&self.context))?;
+            map.serialize_entry("error", &self.error)?;
+            map.end()
+        }
+    }
+}
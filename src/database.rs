@@ -0,0 +1,204 @@
+//!Errors of databases with statement and connection(feature "database").
+//!
+//!Error of database driver says "duplicate key value" or "database is locked", but not which query has failed and where.
+//!`try_query!(result, statement, connection, DbError::Query)` works like try!(), it wraps the error of driver into `QueryFailure`
+//!with the statement, name of connection and code of error(SQLSTATE of sqlx, extended code of rusqlite) and passes it
+//!to the first field of variant, the error of driver is kept as source.
+//!
+//!Statement is redacted: string and number literals are replaced with `?`, so values, that are formatted into SQL,
+//!do not get into logs. Errors of sqlx(feature "sqlx"), rusqlite(feature "rusqlite") and diesel(feature "diesel") implement
+//!`DatabaseError`, these features enable "database" and the driver. Code of diesel is SQLSTATE of its kind of error,
+//!like "23505" for unique violation. Errors of other drivers implement `DatabaseError` themselves.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use nes::database::{DatabaseError,QueryFailure};
+//!use std::error::Error;
+//!
+//!//error of some driver
+//!#[derive(Debug)]
+//!struct DriverError;
+//!
+//!impl std::fmt::Display for DriverError {
+//!    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { write!(f, "duplicate key value") }
+//!}
+//!
+//!impl Error for DriverError {}
+//!
+//!impl DatabaseError for DriverError {
+//!    fn code(&self) -> Option<String> { Some("23505".to_string()) }
+//!}
+//!
+//!define_error!( DbError,
+//!    Query(failure:QueryFailure) => "can not save user: {}"
+//!);
+//!
+//!fn save(name:&str) -> result![DbError] {
+//!    let statement=format!("INSERT INTO users (name, age) VALUES ('{}', 42)", name);
+//!    let result:Result<u64, DriverError>=Err(DriverError);
+//!    try_query!(result, &statement, "users-primary", DbError::Query);
+//!    ok!()
+//!}
+//!
+//!fn main() {
+//!    let error=save("bob").unwrap_err();
+//!    assert!(error.to_string().ends_with(
+//!        "can not save user: query `INSERT INTO users (name, age) VALUES (?, ?)` on users-primary has failed(code 23505): duplicate key value"
+//!    ));
+//!    assert_eq!(error.source().unwrap().source().unwrap().to_string(), "duplicate key value");
+//!}
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+#[cfg(feature = "rusqlite")]
+use super::__private::rusqlite;
+#[cfg(feature = "sqlx")]
+use super::__private::sqlx;
+#[cfg(feature = "diesel")]
+use super::__private::diesel;
+
+///Error of database driver, code of error is taken from it, if it has one.
+pub trait DatabaseError: Error + Send + Sync + 'static {
+    fn code(&self) -> Option<String> { None }
+}
+
+#[cfg(feature = "sqlx")]
+impl DatabaseError for sqlx::Error {
+    fn code(&self) -> Option<String> {
+        self.as_database_error().and_then(|error| error.code()).map(|code| code.into_owned())
+    }
+}
+
+#[cfg(feature = "rusqlite")]
+impl DatabaseError for rusqlite::Error {
+    fn code(&self) -> Option<String> {
+        self.sqlite_error().map(|error| error.extended_code.to_string())
+    }
+}
+
+#[cfg(feature = "diesel")]
+impl DatabaseError for diesel::result::Error {
+    fn code(&self) -> Option<String> {
+        use self::diesel::result::DatabaseErrorKind;
+
+        let code=match *self {
+            diesel::result::Error::DatabaseError(ref kind, _) => match *kind {
+                DatabaseErrorKind::UniqueViolation => "23505",
+                DatabaseErrorKind::ForeignKeyViolation => "23503",
+                DatabaseErrorKind::NotNullViolation => "23502",
+                DatabaseErrorKind::CheckViolation => "23514",
+                DatabaseErrorKind::SerializationFailure => "40001",
+                DatabaseErrorKind::ReadOnlyTransaction => "25006",
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        Some(code.to_string())
+    }
+}
+
+///Failed query: statement, name of connection, code and error of driver.
+pub struct QueryFailure {
+    statement:String,
+    connection:String,
+    code:Option<String>,
+    error:Box<dyn Error + Send + Sync>
+}
+
+impl QueryFailure {
+    ///Creates failure with redacted statement, it is called by try_query!().
+    pub fn new<E:DatabaseError>(statement:&str, connection:&str, error:E) -> Self {
+        Self::with_raw_statement(&redact(statement), connection, error)
+    }
+
+    ///Creates failure with statement as is, when the statement has no secrets, for example, it has only placeholders.
+    pub fn with_raw_statement<E:DatabaseError>(statement:&str, connection:&str, error:E) -> Self {
+        QueryFailure {
+            statement:statement.to_string(),
+            connection:connection.to_string(),
+            code:error.code(),
+            error:Box::new(error)
+        }
+    }
+
+    pub fn statement(&self) -> &str { &self.statement }
+    pub fn connection(&self) -> &str { &self.connection }
+    pub fn code(&self) -> Option<&str> { self.code.as_deref() }
+    pub fn error(&self) -> &(dyn Error + Send + Sync + 'static) { &*self.error }
+}
+
+impl fmt::Display for QueryFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "query `{}` on {} has failed", self.statement, self.connection)?;
+
+        if let Some( ref code )=self.code {
+            write!(f, "(code {})", code)?;
+        }
+
+        write!(f, ": {}", self.error)
+    }
+}
+
+impl fmt::Debug for QueryFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("QueryFailure")
+            .field("statement", &self.statement)
+            .field("connection", &self.connection)
+            .field("code", &self.code)
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+impl Error for QueryFailure {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&*self.error)
+    }
+}
+
+///Replaces string literals('...') and number literals of SQL with `?`, placeholders($1, ?1, :name) and identifiers are kept.
+pub fn redact(statement:&str) -> String {
+    let mut redacted=String::with_capacity(statement.len());
+    let mut chars=statement.chars().peekable();
+    //literal may start only after operator, space or bracket, so digits of identifiers(table2) and placeholders($1) are kept
+    let mut previous=' ';
+
+    while let Some( c )=chars.next() {
+        if c=='\'' {
+            //'' inside of literal is escaped quote
+            loop {
+                match chars.next() {
+                    Some( '\'' ) if chars.peek()==Some(&'\'') => { chars.next(); },
+                    Some( '\'' ) | None => break,
+                    Some( _ ) => {},
+                }
+            }
+
+            redacted.push('?');
+            previous='?';
+        }else if c.is_ascii_digit() && !(previous.is_alphanumeric() || previous=='_' || previous=='$' || previous=='?' || previous==':') {
+            while let Some( &next )=chars.peek() {
+                if next.is_ascii_alphanumeric() || next=='.' {
+                    chars.next();
+                }else{
+                    break;
+                }
+            }
+
+            redacted.push('?');
+            previous='?';
+        }else{
+            redacted.push(c);
+            previous=c;
+        }
+    }
+
+    redacted
+}
@@ -0,0 +1,74 @@
+//!Fallible cleanup of `defer_err!()`, that is not lost, if the block has already failed.
+//!
+//!`defer_err!(cleanup => { ... })` runs the block and then the cleanup(flush of file, rollback of transaction), it evaluates to
+//!`Result<T, WithSections<E>>`. If the block has failed, the error of cleanup is attached to it as related section with location
+//!of defer_err!(), so the secondary error is written after the primary one instead of being silently dropped. If only the cleanup
+//!has failed, its error is converted into the error of block by From, like by `?`.
+//!
+//!The block is the body of closure, like the body of error_scope!(), the cleanup is evaluated after it, so it may use
+//!the same variables. Cleanup is not run, if the block panics.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use nes::section::WithSections;
+//!
+//!define_error!( ImportError,
+//!    BadRow(row:usize) => "bad row {}",
+//!    Rollback(message:&'static str) => "rollback failed: {}"
+//!);
+//!
+//!impl From<&'static str> for ImportError {
+//!    fn from(message:&'static str) -> Self {
+//!        create_err!(ImportError::Rollback, message)
+//!    }
+//!}
+//!
+//!fn rollback() -> Result<(), &'static str> {
+//!    Err("connection is closed")
+//!}
+//!
+//!fn import(rows:&[&str]) -> Result<usize, WithSections<ImportError>> {
+//!    defer_err!(rollback() => {
+//!        for (row, text) in rows.iter().enumerate() {
+//!            if text.is_empty() {
+//!                return err!(ImportError::BadRow, row);
+//!            }
+//!        }
+//!
+//!        ok!(rows.len())
+//!    })
+//!}
+//!
+//!fn main() {
+//!    let error=import(&["a", ""]).unwrap_err();
+//!    let text=error.to_string();
+//!    assert!(text.contains("bad row 1\nrelated: cleanup failed: connection is closed\n  at "));
+//!
+//!    let error=import(&["a"]).unwrap_err();
+//!    assert!(error.to_string().ends_with("rollback failed: connection is closed"));
+//!}
+//! ```
+
+use std::fmt;
+
+use ErrorInfo;
+use section::WithSections;
+
+///Completes defer_err!(): attaches error of cleanup to error of block or converts it into error of block.
+pub fn finish<T, E, U, C>(result:Result<T, E>, cleanup:Result<U, C>, error_info:ErrorInfo) -> Result<T, WithSections<E>>
+    where E:From<C>, C:fmt::Display
+{
+    match (result, cleanup) {
+        (Ok( ok ), Ok( _ )) => Ok(ok),
+        (Ok( _ ), Err( cleanup_error )) => Err(WithSections::new(E::from(cleanup_error))),
+        (Err( error ), Ok( _ )) => Err(WithSections::new(error)),
+        (Err( error ), Err( cleanup_error )) => {
+            let text=format!("cleanup failed: {}", cleanup_error);
+            Err(WithSections::new(error).with_related(error_info, text))
+        },
+    }
+}
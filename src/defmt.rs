@@ -0,0 +1,71 @@
+//!Logging of errors by defmt on microcontrollers(feature "defmt").
+//!
+//!define_error!() implements `defmt::Format` for errors, so they are written by `defmt::error!("{}", error)` over RTT
+//!without heap and formatting of strings on the device. Path of file is not sent, location is written as id of file(hash of path,
+//!the same, that privacy mode writes) and line:col, host maps ids back to paths by `nes::privacy::LocationTable`,
+//!that is generated by build script. Message of variant is written by `defmt::Display2Format`, that streams it without heap.
+//!
+//! ```text
+//!ERROR 5f0c2a6e9b1d47e3 42:13 SensorError::Timeout sensor 3 does not respond
+//! ```
+//!
+//! # Example
+//!
+//! ```ignore
+//!#[macro_use]
+//!extern crate nes;
+//!extern crate defmt;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!
+//!define_error!( SensorError,
+//!    Timeout(sensor:u8) => "sensor {} does not respond"
+//!);
+//!
+//!fn read(sensor:u8) -> result![u16,SensorError] {
+//!    err!(SensorError::Timeout, sensor)
+//!}
+//!
+//!fn poll() {
+//!    if let Err(error)=read(3) {
+//!        defmt::error!("{}", error);
+//!    }
+//!}
+//! ```
+
+use std::fmt;
+
+use super::__private::defmt::{self,Display2Format,Format,Formatter};
+
+use {ErrorInfo,ErrorInfoTrait,ErrorMetadata};
+use privacy;
+
+///Returns id of file of location, that is hash of its path. In privacy mode file is already the hash, so it is parsed.
+pub fn file_id(file:&str) -> u64 {
+    if privacy::is_enabled() && file.len()==16 {
+        if let Ok( id )=u64::from_str_radix(file, 16) {
+            return id;
+        }
+    }
+
+    privacy::file_id(file)
+}
+
+impl Format for ErrorInfo {
+    fn format(&self, f:Formatter) {
+        defmt::write!(f, "{=u64:016x} {=u32}:{=u32}", file_id(self.file()), self.line(), self.col())
+    }
+}
+
+///Writes the error: location, names of error and variant and message. It is called by impls of Format of define_error!().
+pub fn format_error<E:ErrorMetadata>(f:Formatter, error:&E) {
+    defmt::write!(f, "{} {=str}::{=str} {}", error.location(), error.error_name(), error.variant_name(), Display2Format(&Message(error)))
+}
+
+//message of variant without ErrorInfo
+struct Message<'a, E:'a>(&'a E);
+
+impl<'a, E:ErrorMetadata> fmt::Display for Message<'a, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt_message(f)
+    }
+}
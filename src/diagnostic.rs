@@ -0,0 +1,454 @@
+//!Diagnostic is a second rendering engine for errors, that are found in user's input(config files, scripts, source code of compiler).
+//!ErrorInfo tells where the error has been occurred in the rust code, but user wants to know where is the problem in his file.
+//!Diagnostic stores the source buffer and byte-span labels and renders them with caret underlines, like rustc does.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use nes::diagnostic::{Diagnostic,Source};
+//!
+//!define_error!( ConfigError,
+//!    ParseError(diagnostic:Box<Diagnostic>) => "parse error\n{}"
+//!);
+//!
+//!fn main() {
+//!    let source=Source::new("server.conf", "host = \"localhost\"\nport = \"80\"\n");
+//!    let diagnostic=Diagnostic::new("expected integer", source)
+//!        .with_label(25..29, "this is a string")
+//!        .with_note("port must be in range 1..65535");
+//!
+//!    let error:ConfigError=create_err!(ConfigError::ParseError, Box::new(diagnostic));
+//!    println!("{}",error);
+//!}
+//! ```
+//!
+//!Output:
+//!
+//! ```text
+//!diagnostic/examples/diagnostic.rs 24:13
+//!parse error
+//!error: expected integer
+//! --> server.conf:2:8
+//!  |
+//!2 | port = "80"
+//!  |        ^^^^ this is a string
+//!  |
+//!  = note: port must be in range 1..65535
+//! ```
+//!
+//!Errors of parsers of config files(serde_json, serde_yaml and toml with features of the same names) are converted into Diagnostic
+//!by `Diagnostic::from_parse_error(&error, source)`, so the report points to line and column in the config file.
+//!
+//! ```
+//!# #[cfg(feature = "serde_json")]
+//!# mod test {
+//!extern crate serde_json;
+//!use nes::diagnostic::{Diagnostic,Source};
+//!use std::collections::HashMap;
+//!
+//!# pub fn main() {
+//!let text="{\n  \"port\": \"80\"\n}";
+//!let error=serde_json::from_str::<HashMap<String, u16>>(text).unwrap_err();
+//!let diagnostic=Diagnostic::from_parse_error(&error, Source::new("server.json", text));
+//!
+//!assert_eq!(diagnostic.to_string(),
+//!    "error: invalid type: string \"80\", expected u16\n --> server.json:2:14\n  |\n2 |   \"port\": \"80\"\n  |              ^");
+//!# }
+//!# }
+//!# fn main() {
+//!# #[cfg(feature = "serde_json")]
+//!# test::main();
+//!# }
+//! ```
+
+use std::fmt;
+use std::ops::Range;
+
+#[cfg(feature = "serde_json")]
+use super::__private::serde_json;
+#[cfg(feature = "serde_yaml")]
+use super::__private::serde_yaml;
+#[cfg(feature = "toml")]
+use super::__private::toml;
+
+///Source buffer(text of file) with its name, that is shown in the report.
+#[derive(Debug, Clone)]
+pub struct Source {
+    name:String,
+    text:String
+}
+
+impl Source {
+    pub fn new<N:Into<String>, T:Into<String>>(name:N, text:T) -> Self {
+        Source {
+            name:name.into(),
+            text:text.into()
+        }
+    }
+
+    pub fn name(&self) -> &str { &self.name }
+    pub fn text(&self) -> &str { &self.text }
+
+    ///Returns line and column(both start from 1) of byte offset. Column is counted in chars.
+    ///Offset, that is out of text, points to the end of text.
+    pub fn line_col(&self, offset:usize) -> (usize, usize) {
+        let offset=self.clamp(offset);
+        let before=&self.text[..offset];
+        let line_start=match before.rfind('\n') {
+            Some(pos) => pos+1,
+            None => 0,
+        };
+
+        (before.matches('\n').count()+1, before[line_start..].chars().count()+1)
+    }
+
+    ///Returns byte offset of line and column(both start from 1, column is counted in chars), it is reverse of line_col().
+    ///Line or column, that is out of text, points to the end of line or text.
+    pub fn offset(&self, line:usize, col:usize) -> usize {
+        let mut line_start=0;
+
+        for _ in 1..line {
+            match self.text[line_start..].find('\n') {
+                Some( pos ) => line_start+=pos+1,
+                None => return self.text.len(),
+            }
+        }
+
+        let line_end=match self.text[line_start..].find('\n') {
+            Some( pos ) => line_start+pos,
+            None => self.text.len(),
+        };
+
+        match self.text[line_start..line_end].char_indices().nth(col.saturating_sub(1)) {
+            Some( (pos, _) ) => line_start+pos,
+            None => line_end,
+        }
+    }
+
+    ///Returns text of line(starts from 1) without line break.
+    pub fn line(&self, line:usize) -> Option<&str> {
+        if line==0 {
+            return None;
+        }
+
+        self.text.split('\n').nth(line-1).map(|text| text.trim_end_matches('\r'))
+    }
+
+    fn clamp(&self, offset:usize) -> usize {
+        let mut offset=std::cmp::min(offset, self.text.len());
+
+        while !self.text.is_char_boundary(offset) {
+            offset-=1;
+        }
+
+        offset
+    }
+}
+
+///Message, that points to span of the source.
+#[derive(Debug, Clone)]
+pub struct Label {
+    span:Range<usize>,
+    message:String
+}
+
+impl Label {
+    pub fn new<M:Into<String>>(span:Range<usize>, message:M) -> Self {
+        Label {
+            span,
+            message:message.into()
+        }
+    }
+
+    pub fn span(&self) -> Range<usize> { self.span.clone() }
+    pub fn message(&self) -> &str { &self.message }
+}
+
+///Diagnostic is the message with labeled spans of the source, that is rendered by Display with caret underlines.
+///You can store it in your error as field(in Box, like other errors).
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    message:String,
+    source:Source,
+    labels:Vec<Label>,
+    notes:Vec<String>
+}
+
+impl Diagnostic {
+    pub fn new<M:Into<String>>(message:M, source:Source) -> Self {
+        Diagnostic {
+            message:message.into(),
+            source,
+            labels:Vec::new(),
+            notes:Vec::new()
+        }
+    }
+
+    ///Adds label, span is range of bytes in the source.
+    pub fn with_label<M:Into<String>>(mut self, span:Range<usize>, message:M) -> Self {
+        self.labels.push(Label::new(span, message));
+        self
+    }
+
+    ///Adds note, that is shown after the source snippet.
+    pub fn with_note<N:Into<String>>(mut self, note:N) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    ///Creates diagnostic from error of parser(serde_json, serde_yaml, toml), so report points to the problem in the file,
+    ///not only to the rust code, that has called the parser.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    ///let text=std::fs::read_to_string(path)?;
+    ///let config:Config=match serde_json::from_str(&text) {
+    ///    Ok( config ) => config,
+    ///    Err( error ) => return err!(ConfigError::Parse, Box::new(Diagnostic::from_parse_error(&error, Source::new(path, text)))),
+    ///};
+    /// ```
+    pub fn from_parse_error<E:ParseError + ?Sized>(error:&E, source:Source) -> Self {
+        let span=error.span(&source);
+        let diagnostic=Diagnostic::new(error.message(), source);
+
+        match span {
+            Some( span ) => diagnostic.with_label(span, ""),
+            None => diagnostic,
+        }
+    }
+
+    pub fn message(&self) -> &str { &self.message }
+    pub fn source(&self) -> &Source { &self.source }
+    pub fn labels(&self) -> &[Label] { &self.labels }
+    pub fn notes(&self) -> &[String] { &self.notes }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "error: {}", self.message)?;
+
+        let labels:Vec<&Label>=self.labels.iter().collect();
+        let width=fmt_snippet(f, &self.source, labels)?;
+
+        if !self.notes.is_empty() {
+            write!(f, "\n{} |", " ".repeat(width))?;
+        }
+
+        for note in self.notes.iter() {
+            write!(f, "\n{} = note: {}", " ".repeat(width), note)?;
+        }
+
+        Ok(())
+    }
+}
+
+///Error of parser, that knows where the problem is in the source. It is implemented for errors of serde_json, serde_yaml and toml
+///with features "serde_json", "serde_yaml" and "toml".
+pub trait ParseError: fmt::Display {
+    ///Returns span of the problem in bytes of the source.
+    fn span(&self, source:&Source) -> Option<Range<usize>>;
+
+    ///Returns message without location, location is shown by the snippet.
+    fn message(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl ParseError for serde_json::Error {
+    fn span(&self, source:&Source) -> Option<Range<usize>> {
+        //errors of io and of data structure(not of text) have line 0
+        if self.line()==0 {
+            return None;
+        }
+
+        let offset=source.offset(self.line(), self.column());
+        Some(offset..offset)
+    }
+
+    fn message(&self) -> String {
+        without_position(self.to_string())
+    }
+}
+
+#[cfg(feature = "serde_yaml")]
+impl ParseError for serde_yaml::Error {
+    fn span(&self, _source:&Source) -> Option<Range<usize>> {
+        self.location().map(|location| location.index()..location.index())
+    }
+
+    fn message(&self) -> String {
+        without_position(self.to_string())
+    }
+}
+
+#[cfg(feature = "toml")]
+impl ParseError for toml::de::Error {
+    fn span(&self, _source:&Source) -> Option<Range<usize>> {
+        toml::de::Error::span(self)
+    }
+
+    fn message(&self) -> String {
+        toml::de::Error::message(self).to_string()
+    }
+}
+
+//serde_json and serde_yaml append " at line 2 column 8" to message
+#[cfg(any(feature = "serde_json", feature = "serde_yaml"))]
+fn without_position(message:String) -> String {
+    match message.rfind(" at line ") {
+        Some( pos ) if message[pos..].contains(" column ") => message[..pos].to_string(),
+        _ => message,
+    }
+}
+
+///This trait is implemented by define_error!() for all errors. Variant has span, if it has field `span:Range<usize>` or `offset:usize`.
+pub trait Spanned {
+    fn span(&self) -> Option<Range<usize>>;
+}
+
+///Error with attached source, Display shows the error and the line of the source, where span of the error is.
+///Note, that all fields must be used in message of variant, so you need to show span or offset, for example, as `{1:?}`.
+///It is created by method `with_source` of errors, that are defined by define_error!().
+///
+/// # Example
+///
+/// ```
+///#[macro_use]
+///extern crate nes;
+///use nes::{ErrorInfo,ErrorInfoTrait};
+///use nes::diagnostic::Source;
+///use std::ops::Range;
+///
+///define_error!( ParseError,
+///    UnexpectedToken(span:Range<usize>, token:String) => "unexpected token \"{2}\" at {1:?}",
+///    UnexpectedEnd(offset:usize) => "unexpected end of file at {}"
+///);
+///
+///fn main() {
+///    let source=Source::new("script.txt", "let x = 5 +;");
+///    let error:ParseError=create_err!(ParseError::UnexpectedToken, 11..12, ";".to_string());
+///
+///    println!("{}",error.with_source(&source));
+///}
+/// ```
+///
+///Output:
+///
+/// ```text
+///main/src/main.rs 13:26
+///unexpected token ";" at 11..12
+/// --> script.txt:1:12
+///  |
+///1 | let x = 5 +;
+///  |            ^
+/// ```
+pub struct WithSource<'a, E:'a> {
+    error:&'a E,
+    source:&'a Source
+}
+
+impl<'a, E:Spanned> WithSource<'a, E> {
+    pub fn new(error:&'a E, source:&'a Source) -> Self {
+        WithSource {
+            error,
+            source
+        }
+    }
+}
+
+impl<'a, E:Spanned+fmt::Display> fmt::Display for WithSource<'a, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.error)?;
+
+        if let Some(span)=self.error.span() {
+            let label=Label::new(span, "");
+            fmt_snippet(f, self.source, vec![&label])?;
+        }
+
+        Ok(())
+    }
+}
+
+//writes location of first label and lines of source with underlined labels, returns width of gutter
+fn fmt_snippet(f: &mut fmt::Formatter, source:&Source, mut labels:Vec<&Label>) -> Result<usize, fmt::Error> {
+    labels.sort_by_key(|label| label.span.start);
+
+    let (line, col)=match labels.first() {
+        Some(label) => source.line_col(label.span.start),
+        None => (1, 1),
+    };
+
+    let width=labels.iter()
+        .map(|label| source.line_col(label.span.start).0)
+        .max()
+        .unwrap_or(0)
+        .to_string()
+        .len();
+    let gutter=" ".repeat(width);
+
+    write!(f, "\n{}--> {}:{}:{}", gutter, source.name, line, col)?;
+
+    if !labels.is_empty() {
+        write!(f, "\n{} |", gutter)?;
+    }
+
+    let mut last_line=0;
+
+    for label in labels {
+        let (line, col)=source.line_col(label.span.start);
+        let text=source.line(line).unwrap_or("");
+        let line_length=text.chars().count()+1;
+        //span may start at '\r' of line, that ends with "\r\n", it is trimmed from the text of line
+        let col=std::cmp::min(col, line_length);
+
+        if line!=last_line {
+            write!(f, "\n{:>width$} | {}", line, text, width=width)?;
+            last_line=line;
+        }
+
+        //multiline span is underlined to the end of its first line
+        let end=std::cmp::max(source.clamp(label.span.end), source.clamp(label.span.start));
+        let (end_line, end_col)=source.line_col(end);
+        let length=if end_line==line {
+            end_col.saturating_sub(col)
+        }else{
+            line_length.saturating_sub(col)
+        };
+
+        write!(f, "\n{} | {}{}", gutter, " ".repeat(col-1), "^".repeat(std::cmp::max(length, 1)))?;
+
+        if !label.message.is_empty() {
+            write!(f, " {}", label.message)?;
+        }
+    }
+
+    Ok(width)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Diagnostic,Source};
+
+    #[test]
+    fn span_at_crlf() {
+        let diagnostic=Diagnostic::new("unexpected end of line", Source::new("a.cfg", "ab\r\ncd\r\n")).with_label(3..5, "here");
+        let text=diagnostic.to_string();
+
+        assert!(text.contains("1 | ab\n"), "{}", text);
+        assert!(text.contains("  |   ^ here"), "{}", text);
+    }
+
+    #[test]
+    fn span_at_end_of_line() {
+        let diagnostic=Diagnostic::new("expected ';'", Source::new("a.rs", "let x=1\nlet y=2\n")).with_label(7..8, "here");
+        let text=diagnostic.to_string();
+
+        assert!(text.contains("1 | let x=1\n"), "{}", text);
+        assert!(text.contains("  |        ^ here"), "{}", text);
+    }
+}
@@ -0,0 +1,174 @@
+//!Errors as documents of Elastic Common Schema(feature "ecs").
+//!
+//!`Ecs` writes the error as one line of JSON with fields of ECS, so Filebeat or Elastic Agent ship it to Elasticsearch
+//!and Kibana shows it without custom ingest processor:
+//!
+//! ```text
+//!{"@timestamp":"2019-03-02T14:05:09.123Z","log.level":"error","message":"can not fetch /api/users",
+//! "ecs.version":"8.11.0","error.type":"LoadError::FetchFailed","error.message":"can not fetch /api/users",
+//! "error.stack_trace":"app/src/api.rs 42:9\ncan not fetch /api/users","error.code":"E0102",
+//! "log.origin.file.name":"app/src/api.rs","log.origin.file.line":42,"span.id":"00000000000004d2","labels":{"user":"42"}}
+//! ```
+//!
+//!`error.stack_trace` is the full chain of the error with locations, like Display. `error.code`, `span.id` and `trace.id`
+//!are written, if the error has them, key-value context of the error is written as `labels`.
+//!`EcsReporter` writes reported errors as such lines to stderr or other writer.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use nes::ecs::{Ecs,EcsReporter};
+//!
+//!define_error!( LoadError,
+//!    #[nes(code = "E0102")]
+//!    FetchFailed(url:String) => "can not fetch {}"
+//!);
+//!
+//!fn main() {
+//!    nes::add_reporter(EcsReporter::stderr());
+//!
+//!    let error:LoadError=create_err!(LoadError::FetchFailed, "/api/users".to_string());
+//!    let document=Ecs::new(&error).to_string();
+//!
+//!    assert!(document.contains("\"error.type\":\"LoadError::FetchFailed\""));
+//!    assert!(document.contains("\"error.code\":\"E0102\""));
+//!    assert!(document.contains("\"log.origin.file.line\":"));
+//!    error.report();
+//!}
+//! ```
+
+use std::fmt;
+use std::io::{self,Write};
+use std::sync::Mutex;
+use std::time::{Duration,SystemTime};
+
+use {ErrorInfoTrait,Severity};
+use context::ContextValue;
+use reporter::{Reporter,ReportedError,JsonString,Message,Timestamp};
+
+///Version of ECS, that is written to `ecs.version`.
+pub const ECS_VERSION:&str = "8.11.0";
+
+///Document of ECS with the error, Display writes it as one line of JSON.
+pub struct Ecs<'a> {
+    error:&'a dyn ReportedError,
+    timestamp:SystemTime
+}
+
+impl<'a> Ecs<'a> {
+    ///Creates document with current time as `@timestamp`.
+    pub fn new(error:&'a dyn ReportedError) -> Self {
+        Ecs {
+            error,
+            timestamp:SystemTime::now()
+        }
+    }
+
+    pub fn timestamp(mut self, timestamp:SystemTime) -> Self {
+        self.timestamp=timestamp;
+        self
+    }
+}
+
+impl<'a> fmt::Display for Ecs<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let error=self.error;
+        let level=match error.severity() {
+            Severity::Error => "error",
+            Severity::Warning => "warn",
+            Severity::Advice => "info",
+        };
+
+        let message=Message(error).to_string();
+        let location=error.location();
+
+        write!(
+            f, "{{\"@timestamp\":\"{}\",\"log.level\":\"{}\",\"message\":{},\"ecs.version\":\"{}\"",
+            Timestamp(self.timestamp), level, JsonString(&message), ECS_VERSION
+        )?;
+
+        write!(
+            f, ",\"error.type\":\"{}::{}\",\"error.message\":{},\"error.stack_trace\":{}",
+            error.error_name(), error.variant_name(), JsonString(&message), JsonString(&error.to_string())
+        )?;
+
+        if let Some( code )=error.code() {
+            write!(f, ",\"error.code\":{}", JsonString(code))?;
+        }
+
+        write!(f, ",\"log.origin.file.name\":{},\"log.origin.file.line\":{}", JsonString(location.file()), location.line())?;
+
+        if let Some( span_id )=location.span_id() {
+            write!(f, ",\"span.id\":\"{:016x}\"", span_id)?;
+        }
+
+        if let Some( trace_id )=location.trace_id() {
+            write!(f, ",\"trace.id\":\"{:032x}\"", trace_id)?;
+        }
+
+        //labels of ECS are keywords, so all values are strings
+        for (index, item) in error.context().iter().enumerate() {
+            let value=match item.value {
+                ContextValue::String(ref value) => value.clone(),
+                ref value => value.to_string(),
+            };
+
+            write!(f, "{}{}:{}", if index==0 { ",\"labels\":{" } else { "," }, JsonString(item.key), JsonString(&value))?;
+        }
+
+        if !error.context().is_empty() {
+            write!(f, "}}")?;
+        }
+
+        write!(f, "}}")
+    }
+}
+
+///Reporter, that writes reported errors as documents of ECS, one line for each error.
+pub struct EcsReporter<W:Write + Send = io::Stderr> {
+    writer:Mutex<W>
+}
+
+impl EcsReporter {
+    pub fn stderr() -> Self {
+        EcsReporter::new(io::stderr())
+    }
+}
+
+impl<W:Write + Send> EcsReporter<W> {
+    pub fn new(writer:W) -> Self {
+        EcsReporter {
+            writer:Mutex::new(writer)
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        match self.writer.into_inner() {
+            Ok( writer ) => writer,
+            Err( poisoned ) => poisoned.into_inner(),
+        }
+    }
+}
+
+impl<W:Write + Send> Reporter for EcsReporter<W> {
+    fn report(&self, error:&dyn ReportedError) {
+        let line=format!("{}\n", Ecs::new(error));
+
+        let mut writer=match self.writer.lock() {
+            Ok( writer ) => writer,
+            Err( poisoned ) => poisoned.into_inner(),
+        };
+
+        let _=writer.write_all(line.as_bytes());
+    }
+
+    fn flush(&self, _timeout:Duration) -> bool {
+        match self.writer.lock() {
+            Ok( mut writer ) => writer.flush().is_ok(),
+            Err( poisoned ) => poisoned.into_inner().flush().is_ok(),
+        }
+    }
+}
@@ -0,0 +1,120 @@
+//!Interop with eyre(feature "eyre").
+//!
+//!Errors, that are defined by define_error!(), implement std::error::Error, so in application, that uses eyre at main(),
+//!`?` converts them into eyre::Report, and the chain is kept by source().
+//!
+//!Report, that is created by `report(error)`, also has the section with ErrorInfo trail(locations of all nes errors in the chain),
+//!if Handler of this module is installed.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!extern crate eyre;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!
+//!define_error!( CommonError,
+//!    NoArguments() => "no arguments"
+//!);
+//!
+//!fn process() -> result![CommonError] {
+//!    err!(CommonError::NoArguments)
+//!}
+//!
+//!fn main() {
+//!    nes::eyre::install().unwrap();
+//!
+//!    if let Err(e)=process() {
+//!        println!("{:?}",nes::eyre::report(e));
+//!    }
+//!}
+//! ```
+//!
+//!Output:
+//!
+//! ```text
+//!main/src/main.rs 11:5
+//!no arguments
+//!
+//!Error trail:
+//!   0: CommonError::NoArguments main/src/main.rs 11:5
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+use super::__private::eyre::{self,EyreHandler,InstallError,Report};
+use {ErrorTrail,TrailItem};
+
+///Handler of eyre::Report, that shows the chain of errors and ErrorInfo trail.
+pub struct Handler {
+    trail:Vec<TrailItem>
+}
+
+impl Handler {
+    pub fn new() -> Self {
+        Handler {
+            trail:Vec::new()
+        }
+    }
+
+    pub fn trail(&self) -> &[TrailItem] { &self.trail }
+}
+
+impl Default for Handler {
+    fn default() -> Self {
+        Handler::new()
+    }
+}
+
+impl EyreHandler for Handler {
+    fn debug(&self, error: &(dyn Error + 'static), f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            return fmt::Debug::fmt(error, f);
+        }
+
+        write!(f, "{}", error)?;
+
+        let mut source=error.source();
+
+        if source.is_some() {
+            write!(f, "\n\nCaused by:")?;
+        }
+
+        let mut index=0;
+
+        while let Some(cause)=source {
+            write!(f, "\n{:>4}: {}", index, cause.to_string().replace('\n', "\n      "))?;
+            source=cause.source();
+            index+=1;
+        }
+
+        if !self.trail.is_empty() {
+            write!(f, "\n\nError trail:")?;
+        }
+
+        for (index, item) in self.trail.iter().enumerate() {
+            write!(f, "\n{:>4}: {}", index, item)?;
+        }
+
+        Ok(())
+    }
+}
+
+///Installs Handler as eyre hook, call it at start of main().
+pub fn install() -> Result<(), InstallError> {
+    eyre::set_hook(Box::new(|_| Box::new(Handler::new())))
+}
+
+///Converts the error into eyre::Report and attaches the ErrorInfo trail, if Handler is installed.
+pub fn report<E:Error + ErrorTrail + Send + Sync + 'static>(error:E) -> Report {
+    let trail=error.error_trail();
+    let mut report=Report::new(error);
+
+    if let Some(handler)=report.handler_mut().downcast_mut::<Handler>() {
+        handler.trail=trail;
+    }
+
+    report
+}
@@ -0,0 +1,323 @@
+//!Errors as messages of GELF for Graylog(feature "gelf").
+//!
+//!`Gelf` writes the error as GELF 1.1 message: `short_message` is the message of the error, `full_message` is the full chain
+//!with locations, like Display, `level` is syslog level of severity(Error is 3, Warning is 4, Advice is 6). Names of error
+//!and variant, location, code, ids of span and trace and key-value context of the error are additional fields.
+//!Characters of keys of context, that GELF does not allow, are replaced with `_`, keys `id` and keys of the fields above
+//!are skipped, so JSON has no duplicate keys:
+//!
+//! ```text
+//!{"version":"1.1","host":"worker-7","short_message":"can not fetch /api/users",
+//! "full_message":"app/src/api.rs 42:9\ncan not fetch /api/users","timestamp":1551535509.123,"level":3,
+//! "_error":"LoadError","_variant":"FetchFailed","_file":"app/src/api.rs","_line":42,"_col":9,"_code":"E0102","_user":"42"}
+//! ```
+//!
+//!`GelfReporter` sends reported errors to Graylog by UDP(messages, that are larger than one datagram, are chunked) or by TCP
+//!(messages are terminated by zero byte), or writes them to any writer line by line, for example, to the file, that is read
+//!by Graylog Sidecar. TCP connection and writing have timeouts of one second, so stalled Graylog does not block threads,
+//!that report errors. After failure the connection is restored by next report, but not earlier than after delay, that grows
+//!from one second to one minute, errors are dropped meanwhile.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use nes::context::AddContext;
+//!use nes::gelf::{Gelf,GelfReporter};
+//!
+//!define_error!( LoadError,
+//!    #[nes(code = "E0102")]
+//!    FetchFailed(url:String) => "can not fetch {}"
+//!);
+//!
+//!fn main() {
+//!    let graylog=std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+//!    nes::add_reporter(GelfReporter::udp(graylog.local_addr().unwrap()).unwrap());
+//!
+//!    let error:LoadError=create_err!(LoadError::FetchFailed, "/api/users".to_string());
+//!    let message=Gelf::new(&error).to_string();
+//!    assert!(message.contains("\"short_message\":\"can not fetch /api/users\""));
+//!    assert!(message.contains("\"level\":3"));
+//!    assert!(message.contains("\"_code\":\"E0102\""));
+//!
+//!    error.report();
+//!
+//!    let mut datagram=[0; 8192];
+//!    let size=graylog.recv(&mut datagram).unwrap();
+//!    assert!(std::str::from_utf8(&datagram[..size]).unwrap().contains("\"_variant\":\"FetchFailed\""));
+//!
+//!    let error=error.add_context("user id", 42).add_context("file", "users.json");
+//!    let message=Gelf::new(&error).to_string();
+//!    assert!(message.contains("\"_user_id\":\"42\""));
+//!    assert_eq!(message.matches("\"_file\"").count(), 1);
+//!}
+//! ```
+
+use std::fmt;
+use std::io::{self,Write};
+use std::net::{SocketAddr,TcpStream,ToSocketAddrs,UdpSocket};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64,Ordering};
+use std::time::{Duration,Instant,SystemTime,UNIX_EPOCH};
+
+use {ErrorInfoTrait,Severity};
+use context::ContextValue;
+use host;
+use reporter::{Reporter,ReportedError,JsonString,Message};
+
+//size of UDP datagram with header of chunk, it fits into MTU of most networks
+const CHUNK_SIZE:usize = 1420;
+const CHUNK_HEADER_SIZE:usize = 12;
+//Graylog drops messages with more chunks
+const MAX_CHUNKS:usize = 128;
+//timeout of connection and of writing of one message by TCP
+const TCP_TIMEOUT:Duration = Duration::from_secs(1);
+const MIN_RECONNECT_DELAY:Duration = Duration::from_secs(1);
+const MAX_RECONNECT_DELAY:Duration = Duration::from_secs(60);
+//additional fields, that are written for each error, keys of context are not written with these names
+const RESERVED_KEYS:[&str; 9] = ["id", "error", "variant", "file", "line", "col", "code", "span_id", "trace_id"];
+
+///Message of GELF with the error, Display writes it as one line of JSON.
+pub struct Gelf<'a> {
+    error:&'a dyn ReportedError,
+    timestamp:SystemTime
+}
+
+impl<'a> Gelf<'a> {
+    ///Creates message with current time as timestamp.
+    pub fn new(error:&'a dyn ReportedError) -> Self {
+        Gelf {
+            error,
+            timestamp:SystemTime::now()
+        }
+    }
+
+    pub fn timestamp(mut self, timestamp:SystemTime) -> Self {
+        self.timestamp=timestamp;
+        self
+    }
+}
+
+///Returns syslog level of severity, that is `level` of GELF.
+pub fn level(severity:Severity) -> u8 {
+    match severity {
+        Severity::Error => 3,
+        Severity::Warning => 4,
+        Severity::Advice => 6,
+    }
+}
+
+impl<'a> fmt::Display for Gelf<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let error=self.error;
+        let location=error.location();
+        let timestamp=self.timestamp.duration_since(UNIX_EPOCH).unwrap_or_default();
+
+        write!(
+            f, "{{\"version\":\"1.1\",\"host\":{},\"short_message\":{},\"full_message\":{},\"timestamp\":{}.{:03},\"level\":{}",
+            JsonString(location.host().unwrap_or_else(host::hostname)), JsonString(&Message(error).to_string()),
+            JsonString(&error.to_string()), timestamp.as_secs(), timestamp.subsec_millis(), level(error.severity())
+        )?;
+
+        write!(
+            f, ",\"_error\":{},\"_variant\":{},\"_file\":{},\"_line\":{},\"_col\":{}",
+            JsonString(error.error_name()), JsonString(error.variant_name()), JsonString(location.file()), location.line(), location.col()
+        )?;
+
+        if let Some( code )=error.code() {
+            write!(f, ",\"_code\":{}", JsonString(code))?;
+        }
+
+        if let Some( span_id )=location.span_id() {
+            write!(f, ",\"_span_id\":\"{:016x}\"", span_id)?;
+        }
+
+        if let Some( trace_id )=location.trace_id() {
+            write!(f, ",\"_trace_id\":\"{:032x}\"", trace_id)?;
+        }
+
+        for item in error.context() {
+            //GELF allows only keys like ^[\w\.\-]*$
+            let key:String=item.key.chars().map(|c| if c.is_ascii_alphanumeric() || c=='_' || c=='.' || c=='-' { c } else { '_' }).collect();
+
+            if RESERVED_KEYS.contains(&key.as_str()) {
+                continue;
+            }
+
+            let value=match item.value {
+                ContextValue::String(ref value) => value.clone(),
+                ref value => value.to_string(),
+            };
+
+            write!(f, ",{}:{}", JsonString(&format!("_{}", key)), JsonString(&value))?;
+        }
+
+        write!(f, "}}")
+    }
+}
+
+enum Transport {
+    Udp {
+        socket:UdpSocket,
+        address:SocketAddr
+    },
+    Tcp {
+        address:SocketAddr,
+        stream:Option<TcpStream>,
+        //connection is not restored before this time, delay grows after each failure
+        reconnect_at:Instant,
+        reconnect_delay:Duration
+    },
+    Writer(Box<dyn Write + Send>)
+}
+
+///Reporter, that sends reported errors as messages of GELF by UDP or TCP or writes them to the writer.
+pub struct GelfReporter {
+    transport:Mutex<Transport>
+}
+
+impl GelfReporter {
+    ///Creates reporter, that sends messages to Graylog input "GELF UDP", large messages are chunked.
+    pub fn udp<A:ToSocketAddrs>(address:A) -> io::Result<Self> {
+        let address=resolve(address)?;
+        let socket=UdpSocket::bind(if address.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" })?;
+
+        Ok(GelfReporter::with_transport(Transport::Udp { socket, address }))
+    }
+
+    ///Creates reporter, that sends messages to Graylog input "GELF TCP", messages are terminated by zero byte.
+    pub fn tcp<A:ToSocketAddrs>(address:A) -> io::Result<Self> {
+        let address=resolve(address)?;
+        let stream=connect(address)?;
+
+        Ok(GelfReporter::with_transport(Transport::Tcp {
+            address,
+            stream:Some(stream),
+            reconnect_at:Instant::now(),
+            reconnect_delay:MIN_RECONNECT_DELAY
+        }))
+    }
+
+    ///Creates reporter, that writes messages to the writer, one line for each message.
+    pub fn new<W:Write + Send + 'static>(writer:W) -> Self {
+        GelfReporter::with_transport(Transport::Writer(Box::new(writer)))
+    }
+
+    fn with_transport(transport:Transport) -> Self {
+        GelfReporter {
+            transport:Mutex::new(transport)
+        }
+    }
+}
+
+impl Reporter for GelfReporter {
+    fn report(&self, error:&dyn ReportedError) {
+        let message=Gelf::new(error).to_string();
+
+        let mut transport=match self.transport.lock() {
+            Ok( transport ) => transport,
+            Err( poisoned ) => poisoned.into_inner(),
+        };
+
+        match *transport {
+            Transport::Udp { ref socket, address } => {
+                let _=send_udp(socket, address, message.as_bytes());
+            },
+            Transport::Tcp { address, ref mut stream, ref mut reconnect_at, ref mut reconnect_delay } => {
+                //connection is restored by next report after delay
+                if stream.is_none() {
+                    if Instant::now()<*reconnect_at {
+                        return;
+                    }
+
+                    *stream=connect(address).ok();
+                }
+
+                let sent=match *stream {
+                    Some( ref mut stream ) => stream.write_all(message.as_bytes()).and_then(|_| stream.write_all(&[0])),
+                    None => Err(io::Error::from(io::ErrorKind::NotConnected)),
+                };
+
+                match sent {
+                    Ok( () ) => *reconnect_delay=MIN_RECONNECT_DELAY,
+                    Err( _ ) => {
+                        *stream=None;
+                        *reconnect_at=Instant::now()+*reconnect_delay;
+                        *reconnect_delay=std::cmp::min(*reconnect_delay*2, MAX_RECONNECT_DELAY);
+                    },
+                }
+            },
+            Transport::Writer(ref mut writer) => {
+                let _=writer.write_all(message.as_bytes()).and_then(|_| writer.write_all(b"\n"));
+            },
+        }
+    }
+
+    fn flush(&self, _timeout:Duration) -> bool {
+        let mut transport=match self.transport.lock() {
+            Ok( transport ) => transport,
+            Err( poisoned ) => poisoned.into_inner(),
+        };
+
+        match *transport {
+            Transport::Udp { .. } => true,
+            Transport::Tcp { ref mut stream, .. } => stream.as_mut().is_none_or(|stream| stream.flush().is_ok()),
+            Transport::Writer(ref mut writer) => writer.flush().is_ok(),
+        }
+    }
+}
+
+fn connect(address:SocketAddr) -> io::Result<TcpStream> {
+    let stream=TcpStream::connect_timeout(&address, TCP_TIMEOUT)?;
+    stream.set_write_timeout(Some(TCP_TIMEOUT))?;
+    Ok(stream)
+}
+
+fn resolve<A:ToSocketAddrs>(address:A) -> io::Result<SocketAddr> {
+    match address.to_socket_addrs()?.next() {
+        Some( address ) => Ok(address),
+        None => Err(io::Error::new(io::ErrorKind::InvalidInput, "address of Graylog is not resolved")),
+    }
+}
+
+//message, that does not fit into one datagram, is sent by chunks with the same id, sequence number and count of chunks
+fn send_udp(socket:&UdpSocket, address:SocketAddr, message:&[u8]) -> io::Result<()> {
+    if message.len()<=CHUNK_SIZE {
+        return socket.send_to(message, address).map(|_| ());
+    }
+
+    let payload_size=CHUNK_SIZE-CHUNK_HEADER_SIZE;
+    let count=message.len().div_ceil(payload_size);
+
+    if count>MAX_CHUNKS {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "message of GELF is too large"));
+    }
+
+    let id=message_id();
+    let mut chunk=Vec::with_capacity(CHUNK_SIZE);
+
+    for (index, payload) in message.chunks(payload_size).enumerate() {
+        chunk.clear();
+        chunk.extend_from_slice(&[0x1e, 0x0f]);
+        chunk.extend_from_slice(&id);
+        chunk.push(index as u8);
+        chunk.push(count as u8);
+        chunk.extend_from_slice(payload);
+
+        socket.send_to(&chunk, address)?;
+    }
+
+    Ok(())
+}
+
+//id of chunked message is unique for process and differs between processes, that send to the same Graylog
+fn message_id() -> [u8; 8] {
+    static COUNTER:AtomicU64 = AtomicU64::new(0);
+
+    let nanos=SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().subsec_nanos() as u64;
+    let id=COUNTER.fetch_add(1, Ordering::Relaxed) ^ (nanos << 32) ^ (std::process::id() as u64);
+
+    id.to_be_bytes()
+}
@@ -0,0 +1,69 @@
+//!Fields of fixed size, that are stored in the error without allocation(feature "heapless").
+//!
+//!define_error!() does not box anything itself, variants own their fields, so errors without heap are defined with fields of
+//!fixed size: numbers, fixed-size arrays, `heapless::String<N>` and `heapless::Vec<T, N>`, nested errors are stored by value
+//!or in `nes::inline::InlineBox`. `&'static str` is passed to fields of type `heapless::String<N>` in err!(), try!() and create_err!()
+//!like to String, because errors must not fail to be created, longer text is truncated at boundary of char and ends with `…`
+//!(3 bytes of UTF-8) to fit into N bytes, so truncation is visible in messages and logs.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!extern crate heapless;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!
+//!define_error!( SensorError,
+//!    Timeout(sensor:u8, name:heapless::String<8>) => "sensor {} \"{}\" does not respond",
+//!    BadFrame(frame:[u8;4]) => "bad frame {:02x?}"
+//!);
+//!
+//!fn read(sensor:u8) -> result![u16,SensorError] {
+//!    err!(SensorError::Timeout, sensor, "thermometer")
+//!}
+//!
+//!fn main() {
+//!    let error=read(3).unwrap_err();
+//!    assert!(error.to_string().ends_with("sensor 3 \"therm…\" does not respond"));
+//!
+//!    let error:SensorError=create_err!(SensorError::BadFrame, [0xde, 0xad, 0xbe, 0xef]);
+//!    assert!(error.to_string().ends_with("bad frame [de, ad, be, ef]"));
+//!}
+//! ```
+
+use __private::heapless::String;
+
+///Marker of truncated text.
+pub const TRUNCATION_MARKER:&str = "…";
+
+///Returns string with at most N bytes of text. Longer text is truncated at boundary of char and ends with `TRUNCATION_MARKER`,
+///if N is less than length of the marker, the string is filled by dots.
+pub fn truncated<const N:usize>(text:&str) -> String<N> {
+    let mut string=String::new();
+
+    if text.len()<=N {
+        //can not fail, length is at most N
+        let _=string.push_str(text);
+        return string;
+    }
+
+    if N<TRUNCATION_MARKER.len() {
+        for _ in 0..N {
+            let _=string.push('.');
+        }
+
+        return string;
+    }
+
+    let mut end=N-TRUNCATION_MARKER.len();
+
+    while !text.is_char_boundary(end) {
+        end-=1;
+    }
+
+    //can not fail, length is at most N
+    let _=string.push_str(&text[..end]);
+    let _=string.push_str(TRUNCATION_MARKER);
+    string
+}
@@ -0,0 +1,91 @@
+//!ErrorInfo with name of host and id of process, for fleet deployments, that aggregate errors from many machines.
+//!
+//!Name of host and pid are got once and cached. They are shown by Display and, with feature "serde", serialized with location.
+//!Use this ErrorInfo instead of standard:
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::ErrorInfoTrait;
+//!use nes::host::ErrorInfo;
+//!
+//!define_error!( WorkerError,
+//!    QueueIsFull(size:usize) => "queue is full, size is {}"
+//!);
+//!
+//!fn main() {
+//!    let mut error:WorkerError=create_err!(WorkerError::QueueIsFull, 1024);
+//!    assert_eq!(error.get_error_info().pid(), Some(std::process::id()));
+//!    println!("{}",error);
+//!}
+//! ```
+//!
+//!Output:
+//!
+//! ```text
+//!main/src/main.rs 13:31 worker-7[4242]
+//!queue is full, size is 1024
+//! ```
+
+use std::fmt;
+use std::fs;
+use std::sync::OnceLock;
+
+use {report,ErrorInfoTrait};
+
+///ErrorInfo, that stores location, name of host and pid.
+#[derive(Clone, Copy)]
+pub struct ErrorInfo {
+    file:&'static str,
+    line:u32,
+    col:u32,
+    host:&'static str,
+    pid:u32
+}
+
+impl ErrorInfoTrait for ErrorInfo {
+    fn new(file:&'static str, line:u32, col:u32 ) -> Self{
+        ErrorInfo {
+            file,
+            line,
+            col,
+            host:hostname(),
+            pid:std::process::id()
+        }
+    }
+
+    fn file(&self) -> &'static str { self.file }
+    fn line(&self) -> u32 { self.line }
+    fn col(&self) -> u32 { self.col }
+    fn host(&self) -> Option<&'static str> { Some(self.host) }
+    fn pid(&self) -> Option<u32> { Some(self.pid) }
+}
+
+impl fmt::Display for ErrorInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        report::fmt_location(f, self.file, self.line, self.col)?;
+
+        if report::is_snapshot_mode() {
+            write!(f, " HOST[PID]")?;
+        }else{
+            write!(f, " {}[{}]", self.host, self.pid)?;
+        }
+
+        report::fmt_source_line(f, self.file, self.line, self.col)
+    }
+}
+
+///Returns name of host, it is read once from HOSTNAME or COMPUTERNAME variables or from /proc and /etc, else it is "unknown".
+pub fn hostname() -> &'static str {
+    static HOSTNAME:OnceLock<String> = OnceLock::new();
+
+    HOSTNAME.get_or_init(|| {
+        let from_env=["HOSTNAME", "COMPUTERNAME"].iter().filter_map(|name| std::env::var(name).ok());
+        let from_files=["/proc/sys/kernel/hostname", "/etc/hostname"].iter().filter_map(|path| fs::read_to_string(path).ok());
+
+        from_env.chain(from_files)
+            .map(|name| name.trim().to_string())
+            .find(|name| !name.is_empty())
+            .unwrap_or_else(|| "unknown".to_string())
+    })
+}
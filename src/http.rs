@@ -0,0 +1,150 @@
+//!Errors of HTTP clients with method, URL and status code(feature "http-client").
+//!
+//!Error of HTTP client often says only "status code 404" or "connection refused", `.http_context(method, url)` of results
+//!wraps it into `HttpFailure` with method, URL and status code, the error of client is kept as source. HttpFailure is stored
+//!in variant like other errors, so try!() and impl_from_error!() wrap it with location.
+//!Status code is taken from errors of reqwest(feature "http-client-reqwest", after `error_for_status()`) and ureq(feature
+//!"http-client-ureq"), these features enable "http-client" and the client. Other clients implement `HttpClientError`.
+//!
+//! ```ignore
+//!let body=try!( ureq::get(&url).call().http_context("GET", &url), ApiError::Request );
+//! ```
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use nes::http::{HttpClientError,HttpFailure,HttpResultExt};
+//!use std::error::Error;
+//!
+//!//error of some client
+//!#[derive(Debug)]
+//!struct ClientError(u16);
+//!
+//!impl std::fmt::Display for ClientError {
+//!    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { write!(f, "http status: {}", self.0) }
+//!}
+//!
+//!impl Error for ClientError {}
+//!
+//!impl HttpClientError for ClientError {
+//!    fn status(&self) -> Option<u16> { Some(self.0) }
+//!}
+//!
+//!define_error!( ApiError,
+//!    Request(failure:HttpFailure) => "request failed: {}"
+//!);
+//!
+//!fn get(url:&str) -> result![String,ApiError] {
+//!    let result:Result<String, ClientError>=Err(ClientError(404));
+//!    let body=try!( result.http_context("GET", url), ApiError::Request );
+//!    ok!(body)
+//!}
+//!
+//!fn main() {
+//!    let error=get("https://example.com/users/7").unwrap_err();
+//!    assert!(error.to_string().ends_with("request failed: GET https://example.com/users/7 (status 404): http status: 404"));
+//!
+//!    let failure=error.source().unwrap().downcast_ref::<HttpFailure>().unwrap();
+//!    assert_eq!(failure.status(), Some(404));
+//!    assert_eq!(failure.source().unwrap().to_string(), "http status: 404");
+//!}
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+#[cfg(feature = "http-client-reqwest")]
+use super::__private::reqwest;
+#[cfg(feature = "http-client-ureq")]
+use super::__private::ureq;
+
+///Error of HTTP client, status code is taken from it, if it has one.
+pub trait HttpClientError: Error + Send + Sync + 'static {
+    fn status(&self) -> Option<u16> { None }
+}
+
+#[cfg(feature = "http-client-reqwest")]
+impl HttpClientError for reqwest::Error {
+    fn status(&self) -> Option<u16> {
+        reqwest::Error::status(self).map(|status| status.as_u16())
+    }
+}
+
+#[cfg(feature = "http-client-ureq")]
+impl HttpClientError for ureq::Error {
+    fn status(&self) -> Option<u16> {
+        match *self {
+            ureq::Error::StatusCode( status ) => Some(status),
+            _ => None,
+        }
+    }
+}
+
+impl HttpClientError for std::io::Error {}
+
+///Failed request: method, URL, status code, if server has responded, and error of HTTP client.
+pub struct HttpFailure {
+    method:String,
+    url:String,
+    status:Option<u16>,
+    error:Box<dyn Error + Send + Sync>
+}
+
+impl HttpFailure {
+    pub fn new<E:HttpClientError>(method:&str, url:&str, error:E) -> Self {
+        HttpFailure {
+            method:method.to_string(),
+            url:url.to_string(),
+            status:error.status(),
+            error:Box::new(error)
+        }
+    }
+
+    pub fn method(&self) -> &str { &self.method }
+    pub fn url(&self) -> &str { &self.url }
+    pub fn status(&self) -> Option<u16> { self.status }
+    pub fn error(&self) -> &(dyn Error + Send + Sync + 'static) { &*self.error }
+}
+
+impl fmt::Display for HttpFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.method, self.url)?;
+
+        if let Some( status )=self.status {
+            write!(f, " (status {})", status)?;
+        }
+
+        write!(f, ": {}", self.error)
+    }
+}
+
+impl fmt::Debug for HttpFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("HttpFailure")
+            .field("method", &self.method)
+            .field("url", &self.url)
+            .field("status", &self.status)
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+impl Error for HttpFailure {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&*self.error)
+    }
+}
+
+///Adds method and URL to errors of HTTP clients.
+pub trait HttpResultExt<T> {
+    fn http_context(self, method:&str, url:&str) -> Result<T, HttpFailure>;
+}
+
+impl<T, E:HttpClientError> HttpResultExt<T> for Result<T, E> {
+    fn http_context(self, method:&str, url:&str) -> Result<T, HttpFailure> {
+        self.map_err(|error| HttpFailure::new(method, url, error))
+    }
+}
@@ -0,0 +1,267 @@
+//!Localization of messages by Fluent catalogs(feature "i18n").
+//!
+//!Display and logs keep the canonical English message of define_error!(), but user-facing messages may be translated:
+//!`error.localized_message(&lang)` looks for the message of variant in the catalog of language and formats it with fields of variant.
+//!
+//!Key of message is `ErrorName-VariantName`, or it is set by `#[nes(fluent = "key")]`. Fields are passed as variables with their names,
+//!numbers are passed as numbers, nested nes errors are passed as their localized messages, other fields as strings by Display or Debug.
+//!If the catalog or the message is not found, canonical message(without ErrorInfo) is returned.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use nes::i18n::LanguageIdentifier;
+//!
+//!define_error!( CommonError,
+//!    IncorrectExtension(file_name:String, extension:String) => "file \"{}\" has incorrect extension \"{}\"",
+//!    #[nes(fluent = "too-many-files")]
+//!    TooManyFiles(count:usize) => "too many files: {}"
+//!);
+//!
+//!fn main() {
+//!    let de:LanguageIdentifier="de".parse().unwrap();
+//!
+//!    nes::i18n::add_catalog(de.clone(), r#"
+//!CommonError-IncorrectExtension = Datei "{ $file_name }" hat falsche Erweiterung "{ $extension }"
+//!too-many-files = Zu viele Dateien: { $count }
+//!"#).unwrap();
+//!
+//!    let error:CommonError=create_err!(CommonError::TooManyFiles, 12);
+//!    assert_eq!(error.localized_message(&de), "Zu viele Dateien: 12");
+//!    assert_eq!(error.localized_message(&"fr".parse().unwrap()), "too many files: 12");
+//!}
+//! ```
+//!
+//!Language of user may be selected at runtime: `set_locale()` sets global locale, that is used by `error.user_message()`
+//!and by Display of `Localized` wrapper, `Localized::with_locale()` selects language for one render.
+//!Without locale and without translation canonical message is used.
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use nes::i18n::{Localized,LanguageIdentifier};
+//!
+//!define_error!( LoginError,
+//!    WrongPassword(login:String) => "wrong password for {}"
+//!);
+//!
+//!fn main() {
+//!    let uk:LanguageIdentifier="uk".parse().unwrap();
+//!    nes::i18n::add_catalog(uk.clone(), "LoginError-WrongPassword = Невірний пароль для { $login }").unwrap();
+//!
+//!    let error:LoginError=create_err!(LoginError::WrongPassword, "admin".to_string());
+//!    assert_eq!(error.user_message(), "wrong password for admin");
+//!
+//!    nes::i18n::set_locale(Some(uk));
+//!    assert_eq!(error.user_message(), "Невірний пароль для admin");
+//!    assert_eq!(Localized::new(&error).with_locale(&"en".parse().unwrap()).to_string(), "wrong password for admin");
+//!}
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::sync::{Arc,RwLock};
+
+use super::__private::fluent::concurrent::FluentBundle;
+use super::__private::fluent::{FluentResource,FluentValue};
+pub use super::__private::fluent::FluentArgs;
+pub use super::__private::unic_langid::LanguageIdentifier;
+
+use ErrorMetadata;
+
+///This trait is implemented by define_error!() with feature "i18n".
+pub trait Localize {
+    ///Returns translated message of variant without ErrorInfo.
+    fn localized_message(&self, lang:&LanguageIdentifier) -> String;
+}
+
+impl<T:Localize + ?Sized> Localize for Box<T> {
+    fn localized_message(&self, lang:&LanguageIdentifier) -> String { (**self).localized_message(lang) }
+}
+
+impl<T:Localize + ?Sized> Localize for Arc<T> {
+    fn localized_message(&self, lang:&LanguageIdentifier) -> String { (**self).localized_message(lang) }
+}
+
+static CATALOGS:RwLock<Vec<FluentBundle<FluentResource>>> = RwLock::new(Vec::new());
+
+///Adds messages of Fluent catalog(.ftl) to catalog of language. Unicode isolation marks are not inserted around variables.
+pub fn add_catalog(lang:LanguageIdentifier, source:&str) -> Result<(), CatalogError> {
+    let resource=match FluentResource::try_new(source.to_string()) {
+        Ok( resource ) => resource,
+        Err((_, errors)) => return Err(CatalogError(errors.iter().map(|e| e.to_string()).collect())),
+    };
+
+    let mut catalogs=match CATALOGS.write() {
+        Ok( catalogs ) => catalogs,
+        Err( poisoned ) => poisoned.into_inner(),
+    };
+
+    let index=match catalogs.iter().position(|bundle| bundle.locales.first()==Some(&lang)) {
+        Some( index ) => index,
+        None => {
+            let mut bundle=FluentBundle::new_concurrent(vec![lang]);
+            bundle.set_use_isolating(false);
+            catalogs.push(bundle);
+            catalogs.len()-1
+        }
+    };
+
+    catalogs[index].add_resource(resource).map_err(|errors| CatalogError(errors.iter().map(|e| e.to_string()).collect()))
+}
+
+static LOCALE:RwLock<Option<LanguageIdentifier>> = RwLock::new(None);
+
+///Sets global locale, that is used by user_message() of errors and by Localized, None means canonical messages.
+pub fn set_locale(lang:Option<LanguageIdentifier>) {
+    match LOCALE.write() {
+        Ok( mut locale ) => *locale=lang,
+        Err( poisoned ) => *poisoned.into_inner()=lang,
+    }
+}
+
+///Returns global locale.
+pub fn locale() -> Option<LanguageIdentifier> {
+    match LOCALE.read() {
+        Ok( locale ) => locale.clone(),
+        Err( poisoned ) => poisoned.into_inner().clone(),
+    }
+}
+
+///Returns message of error in global locale, or canonical message, if locale is not set.
+pub fn user_message<E:Localize + ErrorMetadata>(error:&E) -> String {
+    match locale() {
+        Some( lang ) => error.localized_message(&lang),
+        None => canonical_message(error),
+    }
+}
+
+///Display of this wrapper writes message of error in global locale or in locale of this render.
+pub struct Localized<'a, E:'a> {
+    error:&'a E,
+    lang:Option<&'a LanguageIdentifier>
+}
+
+impl<'a, E:Localize + ErrorMetadata> Localized<'a, E> {
+    pub fn new(error:&'a E) -> Self {
+        Localized {
+            error,
+            lang:None
+        }
+    }
+
+    ///Selects locale for this render instead of global locale.
+    pub fn with_locale(mut self, lang:&'a LanguageIdentifier) -> Self {
+        self.lang=Some(lang);
+        self
+    }
+}
+
+impl<'a, E:Localize + ErrorMetadata> fmt::Display for Localized<'a, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.lang {
+            Some( lang ) => write!(f, "{}", self.error.localized_message(lang)),
+            None => write!(f, "{}", user_message(self.error)),
+        }
+    }
+}
+
+///Returns message of catalog, that is formatted with args, or None, if catalog or message is not found.
+///Catalog of language is looked up by full identifier, then by language only(de-AT uses catalog of de).
+pub fn format_message(lang:&LanguageIdentifier, key:&str, args:&FluentArgs) -> Option<String> {
+    let catalogs=match CATALOGS.read() {
+        Ok( catalogs ) => catalogs,
+        Err( poisoned ) => poisoned.into_inner(),
+    };
+
+    let bundle=catalogs.iter().find(|bundle| bundle.locales.first()==Some(lang))
+        .or_else(|| catalogs.iter().find(|bundle| bundle.locales.first().map(|locale| locale.language)==Some(lang.language)))?;
+
+    let pattern=bundle.get_message(key)?.value()?;
+    let mut errors=Vec::new();
+
+    Some(bundle.format_pattern(pattern, Some(args), &mut errors).into_owned())
+}
+
+///Returns canonical message of variant without ErrorInfo, it is used, if translation is not found.
+pub fn canonical_message<E:ErrorMetadata>(error:&E) -> String {
+    struct Message<'a, E:'a>(&'a E);
+
+    impl<'a, E:ErrorMetadata> fmt::Display for Message<'a, E> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            self.0.fmt_message(f)
+        }
+    }
+
+    Message(error).to_string()
+}
+
+///Errors of parsing of Fluent catalog.
+#[derive(Debug, Clone)]
+pub struct CatalogError(Vec<String>);
+
+impl CatalogError {
+    pub fn errors(&self) -> &[String] { &self.0 }
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "can not add Fluent catalog: {}", self.0.join(", "))
+    }
+}
+
+impl Error for CatalogError {}
+
+//Fields are passed to Fluent by autoref: (&&&LocalizeField(field)).fluent_value(lang) calls
+//ValueViaLocalize for nes errors, else ValueViaFluent for numbers and strings, else ValueViaDisplay, else ValueViaDebug.
+//Method resolution tries &&LocalizeField, &&&LocalizeField, &LocalizeField, then LocalizeField as Self.
+#[doc(hidden)]
+pub struct LocalizeField<'a, T:'a>(pub &'a T);
+
+#[doc(hidden)]
+pub trait ValueViaLocalize {
+    fn fluent_value(&self, lang:&LanguageIdentifier) -> FluentValue<'static>;
+}
+
+impl<'a, T:Localize> ValueViaLocalize for &&LocalizeField<'a, T> {
+    fn fluent_value(&self, lang:&LanguageIdentifier) -> FluentValue<'static> {
+        FluentValue::from(self.0.localized_message(lang))
+    }
+}
+
+#[doc(hidden)]
+pub trait ValueViaFluent {
+    fn fluent_value(&self, lang:&LanguageIdentifier) -> FluentValue<'static>;
+}
+
+impl<'a, T:Clone + Into<FluentValue<'static>>> ValueViaFluent for &&&LocalizeField<'a, T> {
+    fn fluent_value(&self, _lang:&LanguageIdentifier) -> FluentValue<'static> {
+        self.0.clone().into()
+    }
+}
+
+#[doc(hidden)]
+pub trait ValueViaDisplay {
+    fn fluent_value(&self, lang:&LanguageIdentifier) -> FluentValue<'static>;
+}
+
+impl<'a, T:fmt::Display> ValueViaDisplay for &LocalizeField<'a, T> {
+    fn fluent_value(&self, _lang:&LanguageIdentifier) -> FluentValue<'static> {
+        FluentValue::from(self.0.to_string())
+    }
+}
+
+#[doc(hidden)]
+pub trait ValueViaDebug {
+    fn fluent_value(&self, lang:&LanguageIdentifier) -> FluentValue<'static>;
+}
+
+impl<'a, T:fmt::Debug> ValueViaDebug for LocalizeField<'a, T> {
+    fn fluent_value(&self, _lang:&LanguageIdentifier) -> FluentValue<'static> {
+        FluentValue::from(format!("{:?}", self.0))
+    }
+}
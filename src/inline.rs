@@ -0,0 +1,326 @@
+//!Fields, that are stored inline if they are small, else in Box, and fields, that are always stored inline.
+//!
+//!Nested errors are pushed in Box to keep results small, but small payloads(std::io::Error is one pointer) may be stored
+//!in the error itself without allocation on hot error paths. `InlineBox<T, WORDS>` stores value inline, if it fits into WORDS words
+//!and its align is not larger than align of usize, else it allocates Box, so large and recursive errors stay boxed and size
+//!of InlineBox is always WORDS words. Default threshold is 2 words(16 bytes on 64-bit targets).
+//!
+//!try!() and impl_from_error!() create the field by From, like Box.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use nes::inline::InlineBox;
+//!
+//!define_error!( ReadFileError,
+//!    IOError(io_error:InlineBox<std::io::Error>) => "IO Error: {}",
+//!    Nested(error:InlineBox<ReadFileError>) => "{}"
+//!);
+//!
+//!fn open(file:&str) -> result![std::fs::File,ReadFileError] {
+//!    ok!(try!(std::fs::File::open(file), ReadFileError::IOError))
+//!}
+//!
+//!fn main() {
+//!    match open("no_file.rs").unwrap_err() {
+//!        ReadFileError::IOError(_, io_error) => {
+//!            assert!(io_error.is_inline());
+//!            assert_eq!(io_error.kind(), std::io::ErrorKind::NotFound);
+//!        },
+//!        _ => unreachable!(),
+//!    }
+//!}
+//! ```
+//!
+//!Real-time code must not allocate at all. `StaticBox<T, WORDS>` always stores value inline, if it does not fit into WORDS words
+//!(default is 8 words, 64 bytes on 64-bit targets) or needs larger align, the build fails, so errors with StaticBox fields and
+//!fields of fixed size never touch the heap. Together with option `#[nes(max_size = N)]` size of the whole error is bounded.
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use nes::inline::StaticBox;
+//!
+//!define_error!( SensorError,
+//!    Timeout(sensor:u8) => "sensor {} does not respond"
+//!);
+//!
+//!define_error!( #[nes(max_size = 256)] ControlError,
+//!    SensorError(error:StaticBox<SensorError, 16>) => "{}"
+//!);
+//!
+//!impl_from_error!(SensorError => ControlError);
+//!
+//!fn read(sensor:u8) -> result![u16,SensorError] {
+//!    err!(SensorError::Timeout, sensor)
+//!}
+//!
+//!fn step() -> result![ControlError] {
+//!    read(3)?;
+//!    ok!()
+//!}
+//!
+//!fn main() {
+//!    assert!(step().unwrap_err().to_string().ends_with("sensor 3 does not respond"));
+//!}
+//! ```
+//!
+//!Too large value is rejected at compile time:
+//!
+//! ```compile_fail
+//!use nes::inline::StaticBox;
+//!
+//!let value:StaticBox<[u8;256]>=StaticBox::new([0;256]);
+//! ```
+
+use std::any::{Any,TypeId};
+use std::error::Error;
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem::{self,ManuallyDrop,MaybeUninit};
+use std::ops::{Deref,DerefMut};
+use std::ptr;
+
+use {ErrorInfo,ErrorMetadata,ErrorTrail,Severity,TrailItem};
+use context::ContextItem;
+use section::Section;
+
+///Value, that is stored inline, if it fits into WORDS words, else in Box.
+pub struct InlineBox<T, const WORDS:usize = 2> {
+    storage:Storage<WORDS>,
+    marker:PhantomData<T>
+}
+
+union Storage<const WORDS:usize> {
+    inline:MaybeUninit<[usize;WORDS]>,
+    boxed:*mut ()
+}
+
+impl<T, const WORDS:usize> InlineBox<T, WORDS> {
+    const INLINE:bool = mem::size_of::<T>() <= mem::size_of::<[usize;WORDS]>() && mem::align_of::<T>() <= mem::align_of::<usize>();
+
+    pub fn new(value:T) -> Self {
+        let storage=if Self::INLINE {
+            let mut storage=Storage { inline:MaybeUninit::uninit() };
+            //SAFETY: T fits into storage and its align is not larger than align of storage
+            unsafe { ptr::write(ptr::addr_of_mut!(storage.inline) as *mut T, value) };
+            storage
+        }else{
+            Storage { boxed:Box::into_raw(Box::new(value)) as *mut () }
+        };
+
+        InlineBox {
+            storage,
+            marker:PhantomData
+        }
+    }
+
+    ///Returns true, if value is stored inline without allocation.
+    pub fn is_inline(&self) -> bool {
+        Self::INLINE
+    }
+
+    pub fn into_inner(self) -> T {
+        let mut this=ManuallyDrop::new(self);
+
+        //SAFETY: value is initialized and is not dropped, because this is ManuallyDrop
+        unsafe {
+            if Self::INLINE {
+                ptr::read(this.as_mut_ptr())
+            }else{
+                *Box::from_raw(this.as_mut_ptr())
+            }
+        }
+    }
+
+    fn as_ptr(&self) -> *const T {
+        if Self::INLINE {
+            ptr::addr_of!(self.storage.inline) as *const T
+        }else{
+            //SAFETY: boxed is initialized, if T is not inline
+            unsafe { self.storage.boxed as *const T }
+        }
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut T {
+        if Self::INLINE {
+            ptr::addr_of_mut!(self.storage.inline) as *mut T
+        }else{
+            //SAFETY: boxed is initialized, if T is not inline
+            unsafe { self.storage.boxed as *mut T }
+        }
+    }
+}
+
+impl<T, const WORDS:usize> Drop for InlineBox<T, WORDS> {
+    fn drop(&mut self) {
+        //SAFETY: value is initialized and it is dropped once
+        unsafe {
+            if Self::INLINE {
+                ptr::drop_in_place(self.as_mut_ptr())
+            }else{
+                drop(Box::from_raw(self.as_mut_ptr()))
+            }
+        }
+    }
+}
+
+//SAFETY: InlineBox owns T like Box
+unsafe impl<T:Send, const WORDS:usize> Send for InlineBox<T, WORDS> {}
+unsafe impl<T:Sync, const WORDS:usize> Sync for InlineBox<T, WORDS> {}
+
+impl<T, const WORDS:usize> Deref for InlineBox<T, WORDS> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        //SAFETY: value is initialized while InlineBox exists
+        unsafe { &*self.as_ptr() }
+    }
+}
+
+impl<T, const WORDS:usize> DerefMut for InlineBox<T, WORDS> {
+    fn deref_mut(&mut self) -> &mut T {
+        //SAFETY: value is initialized while InlineBox exists
+        unsafe { &mut *self.as_mut_ptr() }
+    }
+}
+
+///Value, that is always stored inline, it must fit into WORDS words and its align must not be larger than align of usize.
+pub struct StaticBox<T, const WORDS:usize = 8> {
+    storage:MaybeUninit<[usize;WORDS]>,
+    marker:PhantomData<T>
+}
+
+impl<T, const WORDS:usize> StaticBox<T, WORDS> {
+    const FITS:() = assert!(
+        mem::size_of::<T>() <= mem::size_of::<[usize;WORDS]>() && mem::align_of::<T>() <= mem::align_of::<usize>(),
+        "value does not fit into StaticBox, increase WORDS"
+    );
+
+    pub fn new(value:T) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::FITS;
+
+        let mut storage=MaybeUninit::uninit();
+        //SAFETY: T fits into storage and its align is not larger than align of storage, it is checked at compile time
+        unsafe { ptr::write(storage.as_mut_ptr() as *mut T, value) };
+
+        StaticBox {
+            storage,
+            marker:PhantomData
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        let this=ManuallyDrop::new(self);
+
+        //SAFETY: value is initialized and is not dropped, because this is ManuallyDrop
+        unsafe { ptr::read(this.storage.as_ptr() as *const T) }
+    }
+}
+
+impl<T, const WORDS:usize> Drop for StaticBox<T, WORDS> {
+    fn drop(&mut self) {
+        //SAFETY: value is initialized and it is dropped once
+        unsafe { ptr::drop_in_place(self.storage.as_mut_ptr() as *mut T) }
+    }
+}
+
+//SAFETY: StaticBox owns T
+unsafe impl<T:Send, const WORDS:usize> Send for StaticBox<T, WORDS> {}
+unsafe impl<T:Sync, const WORDS:usize> Sync for StaticBox<T, WORDS> {}
+
+impl<T, const WORDS:usize> Deref for StaticBox<T, WORDS> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        //SAFETY: value is initialized while StaticBox exists
+        unsafe { &*(self.storage.as_ptr() as *const T) }
+    }
+}
+
+impl<T, const WORDS:usize> DerefMut for StaticBox<T, WORDS> {
+    fn deref_mut(&mut self) -> &mut T {
+        //SAFETY: value is initialized while StaticBox exists
+        unsafe { &mut *(self.storage.as_mut_ptr() as *mut T) }
+    }
+}
+
+//traits of InlineBox and StaticBox, that are forwarded to value
+macro_rules! impl_value_traits{
+    ( $name:ident ) => {
+        impl<T, const WORDS:usize> From<T> for $name<T, WORDS> {
+            fn from(value:T) -> Self {
+                $name::new(value)
+            }
+        }
+
+        impl<T:Clone, const WORDS:usize> Clone for $name<T, WORDS> {
+            fn clone(&self) -> Self {
+                $name::new((**self).clone())
+            }
+        }
+
+        impl<T:PartialEq, const WORDS:usize> PartialEq for $name<T, WORDS> {
+            fn eq(&self, other:&Self) -> bool {
+                **self==**other
+            }
+        }
+
+        impl<T:Eq, const WORDS:usize> Eq for $name<T, WORDS> {}
+
+        impl<T:fmt::Display, const WORDS:usize> fmt::Display for $name<T, WORDS> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                (**self).fmt(f)
+            }
+        }
+
+        impl<T:fmt::Debug, const WORDS:usize> fmt::Debug for $name<T, WORDS> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                (**self).fmt(f)
+            }
+        }
+
+        impl<T:Error, const WORDS:usize> Error for $name<T, WORDS> {
+            fn source(&self) -> Option<&(dyn Error + 'static)> {
+                (**self).source()
+            }
+        }
+
+        impl<T:ErrorTrail, const WORDS:usize> ErrorTrail for $name<T, WORDS> {
+            fn push_error_trail(&self, trail:&mut Vec<TrailItem>) {
+                (**self).push_error_trail(trail)
+            }
+        }
+
+        impl<T:ErrorMetadata, const WORDS:usize> ErrorMetadata for $name<T, WORDS> {
+            fn error_name(&self) -> &'static str { (**self).error_name() }
+            fn variant_name(&self) -> &'static str { (**self).variant_name() }
+            fn location(&self) -> ErrorInfo { (**self).location() }
+            fn code(&self) -> Option<&'static str> { (**self).code() }
+            fn help(&self) -> Option<&'static str> { (**self).help() }
+            fn url(&self) -> Option<&'static str> { (**self).url() }
+            fn severity(&self) -> Severity { (**self).severity() }
+            fn fmt_message(&self, f: &mut fmt::Formatter) -> fmt::Result { (**self).fmt_message(f) }
+            fn context(&self) -> &[ContextItem] { (**self).context() }
+            fn note(&self) -> Option<&'static str> { (**self).note() }
+            fn warning(&self) -> Option<&'static str> { (**self).warning() }
+            fn sections(&self) -> &[Section] { (**self).sections() }
+            fn typed_context(&self, type_id:TypeId) -> Option<&(dyn Any + Send + Sync)> { (**self).typed_context(type_id) }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<T:super::__private::serde::Serialize, const WORDS:usize> super::__private::serde::Serialize for $name<T, WORDS> {
+            fn serialize<S:super::__private::serde::Serializer>(&self, serializer:S) -> Result<S::Ok, S::Error> {
+                (**self).serialize(serializer)
+            }
+        }
+    };
+}
+
+impl_value_traits!(InlineBox);
+impl_value_traits!(StaticBox);
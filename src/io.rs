@@ -0,0 +1,252 @@
+//!Reader and writer, that return located errors, so each io operation does not need try!().
+//!
+//!`TrackedReader` and `TrackedWriter` wrap any Read and Write, their methods return `IoError`, that has ErrorInfo of the call,
+//!name of the operation, number of bytes, that were requested(or written before the failure), and the error of std::io.
+//!Name of the stream(usually path of file) is given at creation and is written in messages.
+//!
+//! # Example
+//!
+//! ```
+//!extern crate nes;
+//!use nes::ErrorInfoTrait;
+//!use nes::io::{TrackedReader,Operation};
+//!
+//!fn main() {
+//!    let mut reader=TrackedReader::new(&b"abc"[..], "header.bin");
+//!    let mut header=[0u8;8];
+//!
+//!    let line=line!(); let error=reader.read_exact(&mut header).unwrap_err();
+//!    assert_eq!(error.operation(), Operation::ReadExact);
+//!    assert_eq!(error.bytes(), 8);
+//!    assert_eq!(error.error_info().line(), line);
+//!    assert!(error.to_string().ends_with("can not read exactly 8 bytes from header.bin: failed to fill whole buffer"));
+//!}
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self,Read,Write};
+
+use {ErrorInfo,ErrorMetadata,ErrorTrail,Severity,TrailItem,caller_error_info};
+use reporter::{ErrorInfoDyn,NesError};
+
+///Operation, that has failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Read,
+    ReadExact,
+    ReadToEnd,
+    ReadToString,
+    Write,
+    WriteAll,
+    Flush
+}
+
+impl Operation {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Operation::Read => "Read",
+            Operation::ReadExact => "ReadExact",
+            Operation::ReadToEnd => "ReadToEnd",
+            Operation::ReadToString => "ReadToString",
+            Operation::Write => "Write",
+            Operation::WriteAll => "WriteAll",
+            Operation::Flush => "Flush",
+        }
+    }
+}
+
+///Error of TrackedReader and TrackedWriter.
+pub struct IoError {
+    error_info:ErrorInfo,
+    operation:Operation,
+    bytes:usize,
+    stream:String,
+    error:io::Error
+}
+
+impl IoError {
+    pub fn error_info(&self) -> &ErrorInfo { &self.error_info }
+    pub fn operation(&self) -> Operation { self.operation }
+    ///Returns number of bytes, that were requested, for write_all and read_to_end it is number of bytes, that were transferred before the failure.
+    pub fn bytes(&self) -> usize { self.bytes }
+    pub fn stream(&self) -> &str { &self.stream }
+    pub fn io_error(&self) -> &io::Error { &self.error }
+    pub fn into_io_error(self) -> io::Error { self.error }
+}
+
+impl fmt::Display for IoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", self.error_info)?;
+        self.fmt_message(f)
+    }
+}
+
+impl fmt::Debug for IoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}\nIoError::{} bytes:{:?} stream:{:?} error:{:?} ", self.error_info, self.operation.name(), self.bytes, self.stream, self.error)
+    }
+}
+
+impl Error for IoError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl ErrorTrail for IoError {
+    fn push_error_trail(&self, trail:&mut Vec<TrailItem>) {
+        trail.push(TrailItem {
+            error_name:"IoError",
+            variant_name:self.operation.name(),
+            error_info:self.error_info
+        });
+    }
+}
+
+impl ErrorMetadata for IoError {
+    fn error_name(&self) -> &'static str { "IoError" }
+    fn variant_name(&self) -> &'static str { self.operation.name() }
+    fn location(&self) -> ErrorInfo { self.error_info }
+    fn code(&self) -> Option<&'static str> { None }
+    fn help(&self) -> Option<&'static str> { None }
+    fn url(&self) -> Option<&'static str> { None }
+    fn severity(&self) -> Severity { Severity::Error }
+
+    fn fmt_message(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.operation {
+            Operation::Read => write!(f, "can not read {} bytes from {}: {}", self.bytes, self.stream, self.error),
+            Operation::ReadExact => write!(f, "can not read exactly {} bytes from {}: {}", self.bytes, self.stream, self.error),
+            Operation::ReadToEnd | Operation::ReadToString => write!(f, "can not read {} after {} bytes: {}", self.stream, self.bytes, self.error),
+            Operation::Write => write!(f, "can not write {} bytes to {}: {}", self.bytes, self.stream, self.error),
+            Operation::WriteAll => write!(f, "can not write all bytes to {}, {} bytes are written: {}", self.stream, self.bytes, self.error),
+            Operation::Flush => write!(f, "can not flush {}: {}", self.stream, self.error),
+        }
+    }
+}
+
+impl NesError for IoError {
+    fn error_info(&self) -> &dyn ErrorInfoDyn { &self.error_info }
+}
+
+//ErrorInfo is location of the caller of method, so it must be called directly by methods, not by closures
+#[track_caller]
+fn io_error(operation:Operation, bytes:usize, stream:&str, error:io::Error) -> IoError {
+    IoError {
+        error_info:caller_error_info(),
+        operation,
+        bytes,
+        stream:stream.to_string(),
+        error
+    }
+}
+
+///Reader, that returns IoError with location of the call.
+pub struct TrackedReader<R> {
+    inner:R,
+    stream:String
+}
+
+impl<R:Read> TrackedReader<R> {
+    pub fn new<S:Into<String>>(inner:R, stream:S) -> Self {
+        TrackedReader {
+            inner,
+            stream:stream.into()
+        }
+    }
+
+    #[track_caller]
+    pub fn read(&mut self, buf:&mut [u8]) -> Result<usize, IoError> {
+        match self.inner.read(buf) {
+            Ok( ok ) => Ok(ok),
+            Err( error ) => Err(io_error(Operation::Read, buf.len(), &self.stream, error)),
+        }
+    }
+
+    #[track_caller]
+    pub fn read_exact(&mut self, buf:&mut [u8]) -> Result<(), IoError> {
+        match self.inner.read_exact(buf) {
+            Ok( ok ) => Ok(ok),
+            Err( error ) => Err(io_error(Operation::ReadExact, buf.len(), &self.stream, error)),
+        }
+    }
+
+    #[track_caller]
+    pub fn read_to_end(&mut self, buf:&mut Vec<u8>) -> Result<usize, IoError> {
+        let start=buf.len();
+        match self.inner.read_to_end(buf) {
+            Ok( ok ) => Ok(ok),
+            Err( error ) => Err(io_error(Operation::ReadToEnd, buf.len()-start, &self.stream, error)),
+        }
+    }
+
+    #[track_caller]
+    pub fn read_to_string(&mut self, buf:&mut String) -> Result<usize, IoError> {
+        let start=buf.len();
+        match self.inner.read_to_string(buf) {
+            Ok( ok ) => Ok(ok),
+            Err( error ) => Err(io_error(Operation::ReadToString, buf.len()-start, &self.stream, error)),
+        }
+    }
+
+    pub fn stream(&self) -> &str { &self.stream }
+    pub fn get_ref(&self) -> &R { &self.inner }
+    pub fn get_mut(&mut self) -> &mut R { &mut self.inner }
+    pub fn into_inner(self) -> R { self.inner }
+}
+
+///Writer, that returns IoError with location of the call.
+pub struct TrackedWriter<W> {
+    inner:W,
+    stream:String
+}
+
+impl<W:Write> TrackedWriter<W> {
+    pub fn new<S:Into<String>>(inner:W, stream:S) -> Self {
+        TrackedWriter {
+            inner,
+            stream:stream.into()
+        }
+    }
+
+    #[track_caller]
+    pub fn write(&mut self, buf:&[u8]) -> Result<usize, IoError> {
+        match self.inner.write(buf) {
+            Ok( ok ) => Ok(ok),
+            Err( error ) => Err(io_error(Operation::Write, buf.len(), &self.stream, error)),
+        }
+    }
+
+    ///Writes all bytes, number of bytes in error is number of bytes, that were written before the failure.
+    #[track_caller]
+    pub fn write_all(&mut self, mut buf:&[u8]) -> Result<(), IoError> {
+        let mut written=0;
+
+        while !buf.is_empty() {
+            match self.inner.write(buf) {
+                Ok( 0 ) => return Err(io_error(Operation::WriteAll, written, &self.stream, io::Error::from(io::ErrorKind::WriteZero))),
+                Ok( count ) => {
+                    written+=count;
+                    buf=&buf[count..];
+                },
+                Err( ref error ) if error.kind()==io::ErrorKind::Interrupted => {},
+                Err( error ) => return Err(io_error(Operation::WriteAll, written, &self.stream, error)),
+            }
+        }
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn flush(&mut self) -> Result<(), IoError> {
+        match self.inner.flush() {
+            Ok( ok ) => Ok(ok),
+            Err( error ) => Err(io_error(Operation::Flush, 0, &self.stream, error)),
+        }
+    }
+
+    pub fn stream(&self) -> &str { &self.stream }
+    pub fn get_ref(&self) -> &W { &self.inner }
+    pub fn get_mut(&mut self) -> &mut W { &mut self.inner }
+    pub fn into_inner(self) -> W { self.inner }
+}
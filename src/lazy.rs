@@ -0,0 +1,94 @@
+//!Fields, that are formatted only when the error is displayed.
+//!
+//!Most errors are handled and never printed, so expensive arguments of message(hex dumps, joined lists, pretty paths)
+//!may be captured as is and formatted by Display of the error:
+//!
+//!* `Deferred<T>` stores compact value and function, that formats it, so type of field can be named and nothing is allocated.
+//!* `LazyDisplay` stores closure, `lazy_format!("{:?}", value)` moves arguments into it and formats them later.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use nes::lazy::{Deferred,LazyDisplay};
+//!
+//!define_error!( PacketError,
+//!    BadChecksum(checksum:Deferred<u32>) => "bad checksum {}",
+//!    UnknownPacket(packet:LazyDisplay) => "unknown packet {}"
+//!);
+//!
+//!fn check(packet:Vec<u8>) -> result![PacketError] {
+//!    let checksum=packet.iter().fold(0u32, |sum, byte| sum.wrapping_add(u32::from(*byte)));
+//!
+//!    if checksum!=0 {
+//!        return err!(PacketError::BadChecksum, Deferred::new(checksum, |checksum, f| write!(f, "{:#010x}", checksum)));
+//!    }
+//!
+//!    err!(PacketError::UnknownPacket, lazy_format!("{:02x?}", packet))
+//!}
+//!
+//!fn main() {
+//!    let error=check(vec![1, 2]).unwrap_err();
+//!    assert!(error.to_string().ends_with("bad checksum 0x00000003"));
+//!    assert!(check(vec![]).unwrap_err().to_string().ends_with("unknown packet []"));
+//!}
+//! ```
+
+use std::fmt;
+
+///Compact value and function, that formats it, when the error is displayed.
+#[derive(Clone, Copy)]
+pub struct Deferred<T> {
+    value:T,
+    fmt:fn(&T, &mut fmt::Formatter) -> fmt::Result
+}
+
+impl<T> Deferred<T> {
+    pub fn new(value:T, fmt:fn(&T, &mut fmt::Formatter) -> fmt::Result) -> Self {
+        Deferred {
+            value,
+            fmt
+        }
+    }
+
+    pub fn value(&self) -> &T { &self.value }
+    pub fn into_value(self) -> T { self.value }
+}
+
+impl<T> fmt::Display for Deferred<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (self.fmt)(&self.value, f)
+    }
+}
+
+impl<T> fmt::Debug for Deferred<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (self.fmt)(&self.value, f)
+    }
+}
+
+type FormatFn = dyn Fn(&mut fmt::Formatter) -> fmt::Result + Send + Sync;
+
+///Closure, that formats captured arguments, when the error is displayed. It is created by `lazy_format!()`.
+///Closure without captures is not allocated.
+pub struct LazyDisplay(Box<FormatFn>);
+
+impl LazyDisplay {
+    pub fn new<F:Fn(&mut fmt::Formatter) -> fmt::Result + Send + Sync + 'static>(fmt:F) -> Self {
+        LazyDisplay(Box::new(fmt))
+    }
+}
+
+impl fmt::Display for LazyDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (self.0)(f)
+    }
+}
+
+impl fmt::Debug for LazyDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (self.0)(f)
+    }
+}
@@ -7,7 +7,7 @@
 //!
 //! # Example
 //!
-//! ```
+//! ```ignore
 //!#![feature(box_patterns)]
 //!
 //!match process() {
@@ -33,13 +33,159 @@
 //! ```
 //!
 //!Do not forget to see examples directory
+//!
+//!Developers can see lines of code, where errors have been occurred, by `report::SourceReport`.
+//!
+//!Errors in user's input(config files, scripts) may be rendered with source snippets by `diagnostic` module(feature "diagnostic", enabled by default).
+//!
+//!Default features "sync-macros"(mutex_lock!() and rw_*!()), "channel-macros"(channel_send!()), "display"(reports of `report`
+//!module, lines of source code and location formatter) and "reporters"(registry of reporters and built-in reporters) may be
+//!disabled for embedded and WASM builds, that do not use std machinery:
+//!
+//! ```toml
+//!nes = { version = "*", default-features = false }
+//! ```
+//!
+//!define_error!() and macros for errors are always available.
+
+//!
+//!On nightly rust feature "nightly" implements `Error::provide`, so generic error reporters get `&ErrorInfo` and `Vec<TrailItem>`
+//!of the error by `std::error::request_ref` and `std::error::request_value` without knowing about nes.
+//!Crate, that defines errors, needs `#![feature(error_generic_member_access)]`.
+
+#[cfg(feature = "diagnostic")]
+pub mod diagnostic;
+pub mod report;
+pub mod aggregate;
+pub mod context;
+pub mod defer;
+pub mod scope;
+pub mod host;
+pub mod inline;
+pub mod io;
+pub mod net;
+pub mod lazy;
+pub mod panic;
+pub mod prelude;
+pub mod reporter;
+#[cfg(feature = "reporters")]
+pub mod sampling;
+#[cfg(feature = "reporters")]
+pub mod background;
+pub mod section;
+pub mod template;
+pub mod traced;
+pub mod warning;
+#[cfg(feature = "eyre")]
+pub mod eyre;
+#[cfg(feature = "anyhow")]
+pub mod anyhow;
+#[cfg(feature = "serde")]
+pub mod schema;
+#[cfg(feature = "wire")]
+pub mod wire;
+#[cfg(feature = "protobuf")]
+pub mod protobuf;
+#[cfg(feature = "tracing")]
+pub mod tracing;
+#[cfg(feature = "otel")]
+pub mod otel;
+#[cfg(feature = "ecs")]
+pub mod ecs;
+#[cfg(feature = "gelf")]
+pub mod gelf;
+#[cfg(feature = "stats")]
+pub mod stats;
+#[cfg(feature = "i18n")]
+pub mod i18n;
+#[cfg(feature = "pool")]
+pub mod pool;
+#[cfg(feature = "privacy")]
+pub mod privacy;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "defmt")]
+pub mod defmt;
+#[cfg(feature = "heapless")]
+pub mod heapless;
+#[cfg(feature = "http-client")]
+pub mod http;
+#[cfg(feature = "database")]
+pub mod database;
+#[cfg(feature = "clap")]
+pub mod clap;
+#[cfg(feature = "config")]
+pub mod config;
+#[cfg(feature = "stack")]
+pub mod stack;
+#[cfg(feature = "symbolize")]
+pub mod symbolize;
+#[cfg(feature = "testing")]
+pub mod testing;
+
+#[doc(hidden)]
+pub mod __private;
+
+pub use panic::install_panic_hook;
+pub use reporter::{Reporter,NesError};
+#[cfg(feature = "reporters")]
+pub use reporter::{add_reporter,flush_reporters,FlushOnExit};
+pub use traced::Traced;
 
+///Boxed error of any type, short name for fields of variants, that wrap whatever has failed.
+pub type DynError = Box<dyn std::error::Error + Send + Sync + 'static>;
 
 ///This is standard ErrorInfo structure.
+#[derive(Clone, Copy)]
 pub struct ErrorInfo {
     file:&'static str,
     line:u32,
-    col:u32
+    col:u32,
+    #[cfg(feature = "tracing")]
+    span_id:Option<std::num::NonZeroU64>,
+    //u128 has align 16, that makes errors larger, zero is invalid trace id
+    #[cfg(feature = "tracing")]
+    trace_id:[u64;2]
+}
+
+///ErrorInfo of zero size, that captures nothing, for performance-critical code, where location is not needed.
+///Errors with it are created by the same macros, Display writes only message.
+///
+/// # Example
+///
+/// ```
+///#[macro_use]
+///extern crate nes;
+///use nes::ErrorInfoTrait;
+///use nes::NoInfo as ErrorInfo;
+///
+///define_error!( ParseError,
+///    BadByte(byte:u8) => "bad byte {:#04x}"
+///);
+///
+///fn main() {
+///    let error:ParseError=create_err!(ParseError::BadByte, 0xff);
+///    assert_eq!(error.to_string(), "bad byte 0xff");
+///    assert_eq!(std::mem::size_of::<ParseError>(), 1);
+///}
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct NoInfo;
+
+impl ErrorInfoTrait for NoInfo {
+    fn new(_file:&'static str, _line:u32, _col:u32 ) -> Self{
+        NoInfo
+    }
+
+    fn file(&self) -> &'static str { "" }
+    fn line(&self) -> u32 { 0 }
+    fn col(&self) -> u32 { 0 }
+}
+
+impl std::fmt::Display for NoInfo {
+    fn fmt(&self, _f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        Ok(())
+    }
 }
 
 ///You should implement this trait for your own ErrorInfo, then you need, for example, get current time and write to log in method new.
@@ -70,15 +216,44 @@ pub struct ErrorInfo {
 ///    fn line(&self) -> u32 { self.line }
 ///    fn col(&self) -> u32 { self.col }
 ///}
+///
+///impl std::fmt::Display for ErrorInfo {
+///    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+///        write!(f, "{} {}:{}", self.file, self.line, self.col)
+///    }
+///}
 /// ```
 ///
-
 pub trait ErrorInfoTrait: std::fmt::Display{
     fn new(file:&'static str, line:u32, col:u32 ) -> Self;
 
     fn file(&self) -> &'static str;
     fn line(&self) -> u32;
     fn col(&self) -> u32;
+
+    ///Returns id of tracing span, that was current, when the error has been occurred. Standard ErrorInfo captures it with feature "tracing".
+    fn span_id(&self) -> Option<u64> { None }
+    ///Returns id of distributed trace, see nes::tracing::set_trace_id_provider().
+    fn trace_id(&self) -> Option<u128> { None }
+    ///Returns name of host, see nes::host::ErrorInfo.
+    fn host(&self) -> Option<&'static str> { None }
+    ///Returns id of process, see nes::host::ErrorInfo.
+    fn pid(&self) -> Option<u32> { None }
+}
+
+impl ErrorInfo {
+    ///Creates ErrorInfo at compile time, ids of tracing span and trace are not captured. It is used by `error_info!(static)`.
+    pub const fn new_const(file:&'static str, line:u32, col:u32 ) -> Self{
+        ErrorInfo {
+            file,
+            line,
+            col,
+            #[cfg(feature = "tracing")]
+            span_id:None,
+            #[cfg(feature = "tracing")]
+            trace_id:[0;2]
+        }
+    }
 }
 
 impl ErrorInfoTrait for ErrorInfo {
@@ -86,18 +261,222 @@ impl ErrorInfoTrait for ErrorInfo {
         ErrorInfo {
             file,
             line,
-            col
+            col,
+            #[cfg(feature = "tracing")]
+            span_id:tracing::current_span_id().and_then(std::num::NonZeroU64::new),
+            #[cfg(feature = "tracing")]
+            trace_id:__private::pack_trace_id(tracing::current_trace_id())
         }
     }
 
     fn file(&self) -> &'static str { self.file }
     fn line(&self) -> u32 { self.line }
     fn col(&self) -> u32 { self.col }
+
+    #[cfg(feature = "tracing")]
+    fn span_id(&self) -> Option<u64> { self.span_id.map(|id| id.get()) }
+    #[cfg(feature = "tracing")]
+    fn trace_id(&self) -> Option<u128> { __private::unpack_trace_id(self.trace_id) }
 }
 
 impl std::fmt::Display for ErrorInfo{
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{} {}:{}", self.file,self.line,self.col)
+        report::fmt_location(f, self.file, self.line, self.col)?;
+        report::fmt_source_line(f, self.file, self.line, self.col)
+    }
+}
+
+///Creates ErrorInfo with location of the caller, in functions with `#[track_caller]` it is location of their caller. It is used by `error_info!(caller)`.
+#[track_caller]
+pub fn caller_error_info<I:ErrorInfoTrait>() -> I {
+    let location=std::panic::Location::caller();
+
+    #[cfg(all(feature = "privacy", not(debug_assertions)))]
+    let file=privacy::private_file(location.file());
+    #[cfg(not(all(feature = "privacy", not(debug_assertions))))]
+    let file=location.file();
+
+    I::new(file, location.line(), location.column())
+}
+
+///Item of error trail: variant and location of the error.
+pub struct TrailItem {
+    pub error_name:&'static str,
+    pub variant_name:&'static str,
+    pub error_info:ErrorInfo
+}
+
+impl std::fmt::Display for TrailItem{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}::{} {}", self.error_name, self.variant_name, self.error_info)
+    }
+}
+
+///Variants and locations of the error and its sources(nested errors, that are defined by define_error!()), first is this error, last is root cause.
+///It is implemented by define_error!().
+pub trait ErrorTrail {
+    fn push_error_trail(&self, trail:&mut Vec<TrailItem>);
+
+    fn error_trail(&self) -> Vec<TrailItem> {
+        let mut trail=Vec::new();
+        self.push_error_trail(&mut trail);
+        trail
+    }
+
+    ///Returns key, that is same for errors with same variant and same variant of root cause, so error-tracking backends can group occurrences of the same failure.
+    ///Key is stable between builds and runs.
+    fn group_key(&self) -> u64 {
+        group_key(&self.error_trail(), false)
+    }
+
+    ///Returns key, that also depends on locations of all errors in the chain.
+    fn group_key_with_location(&self) -> u64 {
+        group_key(&self.error_trail(), true)
+    }
+}
+
+impl<T:ErrorTrail> ErrorTrail for Box<T> {
+    fn push_error_trail(&self, trail:&mut Vec<TrailItem>) {
+        (**self).push_error_trail(trail)
+    }
+}
+
+impl<T:ErrorTrail> ErrorTrail for std::sync::Arc<T> {
+    fn push_error_trail(&self, trail:&mut Vec<TrailItem>) {
+        (**self).push_error_trail(trail)
+    }
+}
+
+///Metadata of variant for renderers of errors(logfmt, JSON and others). It is implemented by define_error!().
+pub trait ErrorMetadata {
+    fn error_name(&self) -> &'static str;
+    fn variant_name(&self) -> &'static str;
+    ///Returns location of this error(not of the nested errors).
+    fn location(&self) -> ErrorInfo;
+    fn code(&self) -> Option<&'static str>;
+    fn help(&self) -> Option<&'static str>;
+    fn url(&self) -> Option<&'static str>;
+    fn severity(&self) -> Severity;
+
+    ///Writes message of variant without ErrorInfo of this error.
+    fn fmt_message(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result;
+
+    ///Returns key-value context, that is added by `add_context`.
+    fn context(&self) -> &[context::ContextItem] { &[] }
+
+    fn note(&self) -> Option<&'static str> { None }
+    fn warning(&self) -> Option<&'static str> { None }
+
+    ///Returns sections, that are added at runtime by `with_note` and other methods of `nes::section::AddSection`.
+    fn sections(&self) -> &[section::Section] { &[] }
+
+    ///Returns typed context, that is added by `add_typed_context`, see `nes::context::get_typed_context()`.
+    fn typed_context(&self, _type_id:std::any::TypeId) -> Option<&(dyn std::any::Any + Send + Sync)> { None }
+}
+
+impl<T:ErrorMetadata> ErrorMetadata for Box<T> {
+    fn error_name(&self) -> &'static str { (**self).error_name() }
+    fn variant_name(&self) -> &'static str { (**self).variant_name() }
+    fn location(&self) -> ErrorInfo { (**self).location() }
+    fn code(&self) -> Option<&'static str> { (**self).code() }
+    fn help(&self) -> Option<&'static str> { (**self).help() }
+    fn url(&self) -> Option<&'static str> { (**self).url() }
+    fn severity(&self) -> Severity { (**self).severity() }
+    fn fmt_message(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { (**self).fmt_message(f) }
+    fn context(&self) -> &[context::ContextItem] { (**self).context() }
+    fn note(&self) -> Option<&'static str> { (**self).note() }
+    fn warning(&self) -> Option<&'static str> { (**self).warning() }
+    fn sections(&self) -> &[section::Section] { (**self).sections() }
+    fn typed_context(&self, type_id:std::any::TypeId) -> Option<&(dyn std::any::Any + Send + Sync)> { (**self).typed_context(type_id) }
+}
+
+impl<T:ErrorMetadata> ErrorMetadata for std::sync::Arc<T> {
+    fn error_name(&self) -> &'static str { (**self).error_name() }
+    fn variant_name(&self) -> &'static str { (**self).variant_name() }
+    fn location(&self) -> ErrorInfo { (**self).location() }
+    fn code(&self) -> Option<&'static str> { (**self).code() }
+    fn help(&self) -> Option<&'static str> { (**self).help() }
+    fn url(&self) -> Option<&'static str> { (**self).url() }
+    fn severity(&self) -> Severity { (**self).severity() }
+    fn fmt_message(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { (**self).fmt_message(f) }
+    fn context(&self) -> &[context::ContextItem] { (**self).context() }
+    fn note(&self) -> Option<&'static str> { (**self).note() }
+    fn warning(&self) -> Option<&'static str> { (**self).warning() }
+    fn sections(&self) -> &[section::Section] { (**self).sections() }
+    fn typed_context(&self, type_id:std::any::TypeId) -> Option<&(dyn std::any::Any + Send + Sync)> { (**self).typed_context(type_id) }
+}
+
+//FNV-1a, because hash of std may be changed between versions of rust
+fn group_key(trail:&[TrailItem], with_location:bool) -> u64 {
+    fn hash(key:u64, bytes:&[u8]) -> u64 {
+        bytes.iter().chain(&[0xff]).fold(key, |key, byte| (key ^ u64::from(*byte)).wrapping_mul(0x100000001b3))
+    }
+
+    let mut key=0xcbf29ce484222325;
+
+    if let (Some(first), Some(root))=(trail.first(), trail.last()) {
+        for item in [first, root].iter() {
+            key=hash(key, item.error_name.as_bytes());
+            key=hash(key, item.variant_name.as_bytes());
+        }
+    }
+
+    if with_location {
+        for item in trail.iter() {
+            key=hash(key, item.error_info.file().as_bytes());
+            key=hash(key, &item.error_info.line().to_le_bytes());
+            key=hash(key, &item.error_info.col().to_le_bytes());
+        }
+    }
+
+    key
+}
+
+///Error, that has flattened variants of error E, it is implemented by define_error_extend!().
+pub trait Embeds<E> {
+    ///Returns true, if variant is flattened from E.
+    fn embeds(&self) -> bool;
+}
+
+///Converts source error into other error, it is implemented by context selectors, see define_error!().
+pub trait IntoError<S> {
+    type Error;
+
+    fn into_error(self, source:S) -> Self::Error;
+}
+
+///Methods for Result, that compose better with method chains than try!() macro.
+pub trait ResultExt<T, E> {
+    ///Converts error by context selector into variant, error becomes the first field of variant and ErrorInfo gets location of caller.
+    fn context<C:IntoError<E>>(self, context:C) -> Result<T, C::Error>;
+}
+
+impl<T, E> ResultExt<T, E> for Result<T, E> {
+    #[track_caller]
+    fn context<C:IntoError<E>>(self, context:C) -> Result<T, C::Error> {
+        match self {
+            Ok( ok ) => Ok(ok),
+            Err(e) => Err(context.into_error(e)),
+        }
+    }
+}
+
+///Severity of variant, that is set by `#[nes(severity = Warning)]` in define_error!().
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Severity {
+    Error,
+    Warning,
+    Advice
+}
+
+#[cfg(feature = "miette")]
+impl From<Severity> for __private::miette::Severity {
+    fn from(severity:Severity) -> Self {
+        match severity {
+            Severity::Error => __private::miette::Severity::Error,
+            Severity::Warning => __private::miette::Severity::Warning,
+            Severity::Advice => __private::miette::Severity::Advice,
+        }
     }
 }
 
@@ -106,6 +485,8 @@ impl std::fmt::Display for ErrorInfo{
 /// # Example
 ///
 /// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
 ///define_error!( ReadFileError,
 ///    IOError(io_error:Box<std::io::Error>) =>
 ///        "IO Error: {}",
@@ -121,11 +502,64 @@ impl std::fmt::Display for ErrorInfo{
 ///    IncorrectExtension(file_name:String, extension:String) =>
 ///        "Expected extension \"{2}\" for file \"{1}\""
 ///);
+/// # fn main() {}
 /// ```
 ///
-///You must push other errors in Box. This prevent results that have large size or infinite(if error is recursive).
+///You must push other errors in Box(or Arc). This prevent results that have large size or infinite(if error is recursive).
+///Small payloads may be stored without allocation by `nes::inline::InlineBox`, it stores value inline, if it is not larger than threshold, else in Box.
 ///In this case Box<..> must be written first, and may be accessed by index like {2}, but index 0 has ErrorInfo, that describes where the error has been occurred.
 ///
+///Errors implement std::error::Error, source() returns the first field, that implements Error(other nes errors, std::io::Error,
+///errors that are defined by thiserror or manually) or is Box<dyn Error>, so libraries like eyre or anyhow see the complete chain.
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///use std::error::Error;
+///
+///#[derive(Debug)]
+///pub struct ProtocolError; //may be defined by #[derive(thiserror::Error)]
+///
+///impl std::fmt::Display for ProtocolError {
+///    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { write!(f, "bad handshake") }
+///}
+///
+///impl Error for ProtocolError {}
+///
+///define_error!( ClientError,
+///    Protocol(protocol_error:Box<ProtocolError>) => "protocol error: {}",
+///    Other(error:Box<dyn Error + Send + Sync>) => "{}"
+///);
+/// # fn main() {
+///let error:ClientError=create_err!(ClientError::Protocol, Box::new(ProtocolError));
+///assert_eq!(error.source().unwrap().to_string(), "bad handshake");
+/// # let other:ClientError=create_err!(ClientError::Other, Box::new(ProtocolError));
+/// # assert_eq!(other.source().unwrap().to_string(), "bad handshake");
+/// # }
+/// ```
+///
+///`nes::DynError` is short name of `Box<dyn Error + Send + Sync + 'static>` for variants, that wrap whatever has failed.
+///try!() converts the error into the first field by From, so it accepts any error, that is Send and Sync.
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///use nes::DynError;
+///
+///define_error!( LoadError,
+///    Failed(cause:DynError, path:String) => "can not load {2}: {1}"
+///);
+///
+///fn load(path:&str) -> result![u16,LoadError] {
+///    let text=try!( std::fs::read_to_string(path), LoadError::Failed, path.to_string() );
+///    let port=try!( text.trim().parse::<u16>(), LoadError::Failed, path.to_string() );
+///    ok!(port)
+///}
+/// # fn main() {
+///assert!(load("no_file.conf").unwrap_err().to_string().contains("can not load no_file.conf: "));
+/// # }
+/// ```
+///
 ///This macro generates code like
 ///
 /// ```text
@@ -149,16 +583,361 @@ impl std::fmt::Display for ErrorInfo{
 ///impl std::fmt::Debug for ReadFileError { ... } //Short description.
 /// ```
 ///
-
+///Errors, that must be held and re-emitted multiple times(by UI or retry layers), may implement Clone by option `#[nes(clone)]`.
+///In this case nested errors must be stored in Arc instead of Box(try!() and impl_from_error!() create Arc, Box or other type of field by From),
+///and your ErrorInfo must implement Clone.
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///use std::sync::Arc;
+///
+///define_error!( #[nes(clone)] DownloadError,
+///    IOError(io_error:Arc<std::io::Error>) => "IO Error: {}",
+///    Timeout(url:String) => "timeout of downloading {1}"
+///);
+/// # fn download() -> result![DownloadError] {
+/// #     try!(std::fs::File::open("no_file.rs"), DownloadError::IOError);
+/// #     ok!()
+/// # }
+/// # fn main() {
+/// # let error=download().unwrap_err();
+/// # let _copy=error.clone();
+/// # }
+/// ```
+///
+///Options `#[nes(partial_eq)]` and `#[nes(eq)]` implement PartialEq(and Eq), that compares variants and fields, but ignores ErrorInfo,
+///so tests do not depend on lines, where errors have been occurred.
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///define_error!( #[nes(eq)] CommonError,
+///    NoArguments() => "no arguments",
+///    IncorrectExtension(file_name:String, extension:String) => "Expected extension \"{2}\" for file \"{1}\""
+///);
+///
+///fn check(file_name:&str) -> result![CommonError] {
+///    if !file_name.ends_with(".rs") {
+///        return err!(CommonError::IncorrectExtension, file_name.to_string(), ".rs".to_string());
+///    }
+///
+///    ok!()
+///}
+/// # fn main() {
+///assert_eq!(check("main.c"), err!(CommonError::IncorrectExtension, "main.c".to_string(), ".rs".to_string()));
+///assert!(check("main.c")!=err!(CommonError::NoArguments));
+/// # }
+/// ```
+///
+///`#[nes(eq)]` requires Eq of all fields, so field like f64 fails at definition of error.
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///define_error!( #[nes(eq)] SensorError,
+///    OutOfRange(value:f64) => "value {} is out of range"
+///);
+/// # fn main() {}
+/// ```
+///
+///Errors, that cross threads, may be checked at compile time by options `#[nes(send)]` and `#[nes(send_sync)]`,
+///so field, that is not Send or Sync(like Rc), fails at definition of error instead of at distant thread::spawn().
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///define_error!( #[nes(send_sync)] HandlerError,
+///    Closed(name:std::rc::Rc<String>) => "handler {} is closed"
+///);
+/// # fn main() {}
+/// ```
+///
+///Option `#[nes(result = ReadFileResult)]` generates alias `pub type ReadFileResult<T = ()> = Result<T, ReadFileError>;`,
+///so public APIs may be read in rustdoc without result!() macro.
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///define_error!( #[nes(result = ReadFileResult)] ReadFileError,
+///    IOError(io_error:Box<std::io::Error>) => "IO Error: {}"
+///);
+///
+///pub fn read_lines(file:&str) -> ReadFileResult<Vec<String>> {
+///    let text=try!(std::fs::read_to_string(file), ReadFileError::IOError);
+///    ok!(text.lines().map(|line| line.to_string()).collect())
+///}
+///
+///pub fn check(file:&str) -> ReadFileResult {
+///    read_lines(file)?;
+///    ok!()
+///}
+/// # fn main() { assert!(check("no_file.rs").is_err()); }
+/// ```
+///
+///Results must stay small, so option `#[nes(max_size = 32)]` checks at compile time, that size of error is not larger than given number of bytes,
+///it catches large fields, that are not boxed accidentally.
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///define_error!( #[nes(max_size = 32)] ParseError,
+///    UnexpectedToken(token:[u8;64]) => "unexpected token {:?}"
+///);
+/// # fn main() {}
+/// ```
+///
+///With feature "wire" option `#[nes(wire)]` makes error encodable into compact binary format and decodable back, see module nes::wire.
+///
+///`error.group_key()` returns hash of variant and variant of root cause(the deepest nes error in the chain), that is stable between builds,
+///error-tracking backends can group occurrences of the same failure by it. `group_key_with_location()` of ErrorTrail also hashes locations.
+///
+///Variants may have metadata, that is returned by methods code(), help(), url(), note(), warning() and severity(),
+///help, note and warning are written as sections by `nes::report::VerboseReport`:
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///define_error!( ServerError,
+///    #[nes(code = "E0001", help = "stop other server or change port in config")]
+///    #[nes(url = "https://example.com/errors/E0001", severity = Error)]
+///    PortIsBusy(port:u16) => "port {1} is busy",
+///    #[nes(severity = Warning)]
+///    NoConfig() => "config file is not found, default config is used"
+///);
+/// # fn main() {
+/// # let error:ServerError=create_err!(ServerError::PortIsBusy, 80);
+/// # assert_eq!(error.code(), Some("E0001"));
+/// # assert_eq!(create_err!(ServerError::NoConfig).severity(), nes::Severity::Warning);
+/// # }
+/// ```
+///
+///Error is `pub` by default, other visibility may be written before its name, it is also used for result alias, selectors and builders.
+///define_error!() may be used inside functions and test modules for local errors, `pub(self)` makes error private.
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///define_error!( pub(crate) ConfigError,
+///    NoConfig() => "no config"
+///);
+///
+///fn parse(text:&str) -> Result<u32, String> {
+///    define_error!( #[nes(result = ParseResult)] pub(self) ParseError,
+///        NotNumber(text:String) => "\"{}\" is not a number"
+///    );
+///
+///    fn parse_number(text:&str) -> ParseResult<u32> {
+///        match text.parse::<u32>() {
+///            Ok(number) => ok!(number),
+///            Err(_) => err!(ParseError::NotNumber, text.to_string()),
+///        }
+///    }
+///
+///    parse_number(text).map_err(|error| error.to_string())
+///}
+/// # fn main() { assert!(parse("a").is_err()); let _:ConfigError=create_err!(ConfigError::NoConfig); }
+/// ```
+///
+///Variants and fields may have names like keywords by raw identifiers(`r#type`), their names in Debug, metadata and serialized errors are without `r#`.
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait,ErrorMetadata};
+///define_error!( SchemaError,
+///    r#Type(r#type:String) => "unknown type {}"
+///);
+///
+/// # fn main() {
+///let error:SchemaError=create_err!(SchemaError::r#Type, "u128".to_string());
+///assert_eq!(error.variant_name(), "Type");
+///assert!(format!("{:?}", error).ends_with("SchemaError::Type type:\"u128\" "));
+/// # }
+/// ```
+///
+///Debug writes the error in one line, `{:#?}` writes one field per line with ErrorInfo and expanded nested errors:
+///
+/// ```text
+///CommonError::ParseError {
+///    error_info: main/src/main.rs 16:0,
+///    error: ParseError::UnexpectedToken {
+///        error_info: main/src/main.rs 51:13,
+///        token: "}",
+///        line: 12,
+///    },
+///}
+/// ```
+///
+///Other systems(alerting rules, client SDKs) may refer to variants by stable identifiers, that are set by `#[nes(id = "...")]`,
+///default identifier is `ErrorName.VariantName`. `error.id()` returns identifier of variant, option `#[nes(codes = module_name)]`
+///generates module with constant for each variant.
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///define_error!( #[nes(codes = common_codes)] CommonError,
+///    #[nes(id = "common.incorrect_extension")]
+///    IncorrectExtension(file_name:String, extension:String) => "file \"{}\" has incorrect extension \"{}\"",
+///    NoArguments() => "no arguments"
+///);
+///
+/// # fn main() {
+///assert_eq!(common_codes::IncorrectExtension, "common.incorrect_extension");
+///assert_eq!(common_codes::NoArguments, "CommonError.NoArguments");
+///
+///let error:CommonError=create_err!(CommonError::NoArguments);
+///assert_eq!(error.id(), common_codes::NoArguments);
+/// # }
+/// ```
+///
+///Doc comments of variants are attached to variants of enum and are returned by `error.docs()`, they must be before `#[nes(...)]`.
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///define_error!( ServerError,
+///    /// Returned when the port is busy.
+///    /// Stop other server or change port in config.
+///    #[nes(code = "E0001")]
+///    PortIsBusy(port:u16) => "port {} is busy",
+///    Stopped() => "server is stopped"
+///);
+///
+/// # fn main() {
+///let error:ServerError=create_err!(ServerError::PortIsBusy, 80);
+///assert_eq!(error.docs().unwrap(), "Returned when the port is busy.\nStop other server or change port in config.");
+///assert_eq!(create_err!(ServerError::Stopped).docs(), None);
+/// # }
+/// ```
+///
+///Variants may have context selectors, that are alternative to err!() and try!() macros and compose better with method chains.
+///`#[nes(context = Name)]` generates struct Name with all fields of variant except first, `result.context(Name{ .. })`
+///converts error of result into the first field(by From, so Box<..> is created).
+///`#[nes(selector = Name)]` generates struct Name with all fields, `Name{ .. }.fail()` returns Err with variant.
+///Location of error is the line of context() or fail() call.
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///use nes::ResultExt;
+///
+///define_error!( ReadFileError,
+///    #[nes(context = ReadFileContext)]
+///    ReadFileError(io_error:Box<std::io::Error>, file:String ) => "Can not read file \"{2}\" : {1}",
+///    #[nes(selector = EmptyFileSelector)]
+///    EmptyFile(file:String) => "file \"{1}\" is empty"
+///);
+///
+///fn read_file(file:&str) -> result![String,ReadFileError] {
+///    let text=std::fs::read_to_string(file).context(ReadFileContext{ file:file.to_string() })?;
+///
+///    if text.is_empty() {
+///        return EmptyFileSelector{ file:file.to_string() }.fail();
+///    }
+///
+///    ok!(text)
+///}
+/// # fn main() { assert!(read_file("no_file.rs").is_err()); }
+/// ```
+///
+///`#[nes(constructor = name)]` generates function `ErrorName::name(fields)` with `#[track_caller]`, that fills ErrorInfo with location of caller,
+///so variants may be created without macros in method chains and closures.
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///define_error!( CommonError,
+///    #[nes(constructor = incorrect_extension)]
+///    IncorrectExtension(file_name:String, extension:String) => "file \"{}\" has incorrect extension \"{}\""
+///);
+///
+///fn check(file:&str) -> result![CommonError] {
+///    file.strip_suffix(".rs")
+///        .map(|_| ())
+///        .ok_or_else(|| CommonError::incorrect_extension(file.to_string(), "rs".to_string()))
+///}
+/// # fn main() { assert!(check("a.txt").is_err()); }
+/// ```
+///
+///Variant, that only wraps other error, may be `#[nes(transparent)]`, its Display and source() are forwarded to the wrapped error
+///without line of location and message of the variant(like `#[error(transparent)]` of thiserror). Message of such variant is not used.
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///define_error!( AppError,
+///    #[nes(transparent)]
+///    Io(io_error:Box<std::io::Error>) => "{}",
+///    NoConfig() => "no config"
+///);
+///
+///fn read(file:&str) -> result![String,AppError] {
+///    ok!(try!(std::fs::read_to_string(file), AppError::Io))
+///}
+///
+/// # fn main() {
+///let error=read("no_file.rs").unwrap_err();
+///assert_eq!(error.to_string(), std::fs::read_to_string("no_file.rs").unwrap_err().to_string());
+/// # }
+/// ```
+///
+///Transparent variant must have exactly one field:
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///define_error!( AppError,
+///    #[nes(transparent)]
+///    Io(io_error:Box<std::io::Error>, file:String) => "{} {}"
+///);
+/// # fn main() {}
+/// ```
+///
+///Variants with many fields may have builder `#[nes(builder = Name)]`, fields are set by methods with their names, so call sites stay readable
+///and fields can not be swapped. `finish()` exists only when all fields are set, so missing field is compile error. Location of error is the line of finish() call.
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///define_error!( NetError,
+///    #[nes(builder = ConnectFailedBuilder)]
+///    ConnectFailed(host:String, port:u16, attempts:u32, cause:Box<std::io::Error>) => "can not connect to {}:{} after {} attempts: {}"
+///);
+///
+///fn connect(host:&str, port:u16) -> result![std::net::TcpStream,NetError] {
+///    std::net::TcpStream::connect((host, port)).map_err(|io_error|
+///        ConnectFailedBuilder::new()
+///            .host(host.to_string())
+///            .port(port)
+///            .attempts(1)
+///            .cause(Box::new(io_error))
+///            .finish()
+///    )
+///}
+/// # fn main() { let _=connect; }
+/// ```
+///
+///With feature "miette" errors implement miette::Diagnostic, so they can be rendered by miette's fancy reporter.
+///
+///Parsers may point to the token in user's input: variant with field `span:Range<usize>`(byte range of source) or `offset:usize`
+///can be shown with the line of source by `error.with_source(&source)`, see `diagnostic` module.
+///
 #[macro_export]
 macro_rules! define_error{
-    ( $error_name:ident,
+    ( $( #[nes( $( $option:tt )* )] )* $error_name:ident, $( $variants:tt )* ) => {
+        $crate::define_error!( $( #[nes( $( $option )* )] )* pub $error_name, $( $variants )* );
+    };
+    ( $( #[nes( $( $option:tt )* )] )*
+        $vis:vis $error_name:ident,
         $(
+            $( #[doc = $doc:expr] )*
+            $( #[nes( $( $meta:tt )* )] )*
             $var_name:ident ( $( $field_name:ident : $field_type:ty ),* ) => $message:expr
-        ),*
+        ),* $(,)*
     ) => {
-        pub enum $error_name {
+        $vis enum $error_name {
             $(
+                $( #[doc = $doc] )*
                 $var_name( ErrorInfo, $( $field_type ),* )
             ),*
         }
@@ -171,194 +950,2136 @@ macro_rules! define_error{
                     ),*
                 }
             }
-        }
 
-        impl std::fmt::Display for $error_name {
-            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            ///Returns span of source, if variant has field `span:Range<usize>` or `offset:usize`.
+            pub fn span(&self) -> Option<::std::ops::Range<usize>> {
                 match *self {
                     $(
-                        $error_name::$var_name( ref error_info, $( ref $field_name ),* ) =>
-                            write!(f, concat!("{}\n",$message), error_info, $( $field_name ),* )
+                        $error_name::$var_name( _, $( ref $field_name ),* ) => {
+                            None $( .or( $crate::__nes_span_field!($field_name, $field_name) ) )*
+                        }
                     ),*
                 }
             }
-        }
 
-        impl std::fmt::Debug for $error_name {
-            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            ///Returns key for grouping of same failures, see ErrorTrail::group_key().
+            pub fn group_key(&self) -> u64 {
+                $crate::ErrorTrail::group_key(self)
+            }
+
+            ///Returns doc comment of variant, for help screens.
+            pub fn docs(&self) -> Option<String> {
+                match *self {
+                    $(
+                        $error_name::$var_name(..) => $crate::__private::join_docs(&[ $( $doc ),* ])
+                    ),*
+                }
+            }
+
+            ///Returns stable identifier of variant, that is set by `#[nes(id = "...")]`, default is `ErrorName.VariantName`.
+            pub fn id(&self) -> &'static str {
+                match *self {
+                    $(
+                        $error_name::$var_name(..) => $crate::__nes_id!( $error_name, $var_name, [ $( $( $meta )* , )* ] )
+                    ),*
+                }
+            }
+
+            ///Returns code of variant, that is set by `#[nes(code = "...")]`.
+            pub fn code(&self) -> Option<&'static str> {
+                match *self {
+                    $(
+                        $error_name::$var_name(..) => $crate::__nes_meta!(code; $( $( $meta )* , )* )
+                    ),*
+                }
+            }
+
+            ///Returns help message of variant, that is set by `#[nes(help = "...")]`.
+            pub fn help(&self) -> Option<&'static str> {
+                match *self {
+                    $(
+                        $error_name::$var_name(..) => $crate::__nes_meta!(help; $( $( $meta )* , )* )
+                    ),*
+                }
+            }
+
+            ///Returns note of variant, that is set by `#[nes(note = "...")]`.
+            pub fn note(&self) -> Option<&'static str> {
+                match *self {
+                    $(
+                        $error_name::$var_name(..) => $crate::__nes_meta!(note; $( $( $meta )* , )* )
+                    ),*
+                }
+            }
+
+            ///Returns warning of variant, that is set by `#[nes(warning = "...")]`.
+            pub fn warning(&self) -> Option<&'static str> {
+                match *self {
+                    $(
+                        $error_name::$var_name(..) => $crate::__nes_meta!(warning; $( $( $meta )* , )* )
+                    ),*
+                }
+            }
+
+            ///Returns url of documentation of variant, that is set by `#[nes(url = "...")]`.
+            pub fn url(&self) -> Option<&'static str> {
+                match *self {
+                    $(
+                        $error_name::$var_name(..) => $crate::__nes_meta!(url; $( $( $meta )* , )* )
+                    ),*
+                }
+            }
+
+            ///Returns message templates of all variants with names of fields, see module nes::template.
+            pub fn message_templates() -> &'static [$crate::template::MessageTemplate] {
+                const TEMPLATES:&[$crate::template::MessageTemplate] = &[
+                    $(
+                        $crate::template::MessageTemplate {
+                            error_name:$crate::__private::ident_name(stringify!($error_name)),
+                            variant_name:$crate::__private::ident_name(stringify!($var_name)),
+                            fluent_key:$crate::__nes_meta!(fluent; $( $( $meta )* , )* ),
+                            template:$message,
+                            fields:&[ $( $crate::__private::ident_name(stringify!($field_name)) ),* ]
+                        }
+                    ),*
+                ];
+
+                TEMPLATES
+            }
+
+            $crate::__nes_report_method!{}
+
+            ///Returns severity of variant, that is set by `#[nes(severity = Warning)]`, default is Severity::Error.
+            pub fn severity(&self) -> $crate::Severity {
+                match *self {
+                    $(
+                        $error_name::$var_name(..) => $crate::__nes_meta!(severity; $( $( $meta )* , )* )
+                    ),*
+                }
+            }
+        }
+
+        $crate::__nes_impl_diagnostic!($error_name);
+        $crate::__nes_impl_miette!($error_name);
+        $crate::__nes_impl_defmt!($error_name);
+        $crate::__nes_impl_serde!( $error_name, [ $( $var_name ( $( $field_name : $field_type ),* ) ),* ] );
+        $crate::__nes_impl_protobuf!( $error_name, [ $( $var_name ( $( $field_name ),* ) ),* ] );
+        $crate::__nes_impl_i18n!( $error_name, [ $( $var_name ( $( $field_name ),* ) { $( $( $meta )* , )* } ),* ] );
+
+        $(
+            $crate::__nes_context!( $vis $error_name, $var_name, [ $( $field_name : $field_type ),* ], $( $( $meta )* , )* );
+        )*
+
+        $crate::__nes_options!( $vis $error_name, [ $( $var_name ( $( $field_name : $field_type ),* ) ),* ], $( $( $option )* , )* );
+        $crate::__nes_codes!( $vis $error_name, { $( $var_name [ $( $( $meta )* , )* ] ),* }, $( $( $option )* , )* );
+        $crate::__nes_extendable!( $error_name, { $( $( #[doc = $doc] )* $( #[nes( $( $meta )* )] )* $var_name ( $( $field_name : $field_type ),* ) => $message ),* }, $( $( $option )* , )* );
+
+        impl std::fmt::Display for $error_name {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                $crate::report::fmt_limited(f, |f| match *self {
+                    $(
+                        $error_name::$var_name( ref error_info, $( ref $field_name ),* ) => $crate::__nes_transparent!(
+                            { let _ = error_info; ::std::fmt::Display::fmt( $crate::__nes_transparent_field!( $( $field_name ),* ), f) }
+                            { write!(f, concat!("{}",$message), $crate::__private::InfoLine(error_info), $( $field_name ),* ) };
+                            $( $( $meta )* , )*
+                        )
+                    ),*
+                })
+            }
+        }
+
+        impl std::fmt::Debug for $error_name {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                $crate::report::fmt_limited(f, |f| match *self {
+                    $(
+                        $error_name::$var_name( ref error_info, $( ref $field_name ),* ) if f.alternate() => {
+                            write!(f, "{}::", $crate::__private::ident_name(stringify!($error_name)))?;
+                            let mut debug=f.debug_struct($crate::__private::ident_name(stringify!($var_name)));
+                            debug.field("error_info", &$crate::__private::DisplayAsDebug(error_info));
+                            $(
+                                debug.field($crate::__private::ident_name(stringify!($field_name)), $field_name);
+                            )*
+                            debug.finish()
+                        },
+                        $error_name::$var_name( ref error_info, $( ref $field_name ),* ) => {
+                            write!(f, "{}{}::{} ", $crate::__private::InfoLine(error_info), $crate::__private::ident_name(stringify!($error_name)), $crate::__private::ident_name(stringify!($var_name)))?;
+                            $(
+                                write!(f, "{}:{:?} ", $crate::__private::ident_name(stringify!($field_name)), $field_name)?;
+                            )*
+                            Ok(())
+                        }
+                    ),*
+                })
+            }
+        }
+
+        impl ::std::error::Error for $error_name {
+            fn source(&self) -> Option<&(dyn (::std::error::Error) + 'static)> {
+                #[allow(unused_imports)]
+                use $crate::__private::{SourceViaError,SourceViaDynError,SourceViaNone};
+
+                match *self {
+                    $(
+                        $error_name::$var_name( _, $( ref $field_name ),* ) => {
+                            let source:Option<&(dyn (::std::error::Error) + 'static)>=None $( .or( (&&$crate::__private::SourceField($field_name)).nes_source() ) )*;
+                            $crate::__nes_transparent!( { source.and_then(|error| error.source()) } { source }; $( $( $meta )* , )* )
+                        }
+                    ),*
+                }
+            }
+
+            $crate::__nes_impl_provide!( $error_name, [ $( $var_name ),* ] );
+        }
+
+        impl $crate::ErrorTrail for $error_name {
+            fn push_error_trail(&self, trail:&mut Vec<$crate::TrailItem>) {
+                #[allow(unused_imports)]
+                use $crate::__private::{TrailViaNes,TrailViaNone};
+
+                match *self {
+                    $(
+                        $error_name::$var_name( ref error_info, $( ref $field_name ),* ) => {
+                            trail.push( $crate::TrailItem {
+                                error_name:$crate::__private::ident_name(stringify!($error_name)),
+                                variant_name:$crate::__private::ident_name(stringify!($var_name)),
+                                error_info:$crate::__private::location(error_info)
+                            });
+                            $( (&$crate::__private::TrailField($field_name)).push_trail(trail); )*
+                        }
+                    ),*
+                }
+            }
+        }
+
+        impl $crate::NesError for $error_name {
+            fn error_info(&self) -> &dyn $crate::reporter::ErrorInfoDyn {
+                match *self {
+                    $(
+                        $error_name::$var_name( ref error_info, .. ) => error_info
+                    ),*
+                }
+            }
+        }
+
+        impl $crate::ErrorMetadata for $error_name {
+            fn error_name(&self) -> &'static str {
+                $crate::__private::ident_name(stringify!($error_name))
+            }
+
+            fn variant_name(&self) -> &'static str {
+                match *self {
+                    $(
+                        $error_name::$var_name(..) => $crate::__private::ident_name(stringify!($var_name))
+                    ),*
+                }
+            }
+
+            fn location(&self) -> $crate::ErrorInfo {
+                match *self {
+                    $(
+                        $error_name::$var_name( ref error_info, .. ) => $crate::__private::location(error_info)
+                    ),*
+                }
+            }
+
+            fn code(&self) -> Option<&'static str> { $error_name::code(self) }
+            fn help(&self) -> Option<&'static str> { $error_name::help(self) }
+            fn url(&self) -> Option<&'static str> { $error_name::url(self) }
+            fn severity(&self) -> $crate::Severity { $error_name::severity(self) }
+            fn note(&self) -> Option<&'static str> { $error_name::note(self) }
+            fn warning(&self) -> Option<&'static str> { $error_name::warning(self) }
+
+            fn fmt_message(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                match *self {
+                    $(
+                        $error_name::$var_name( _, $( ref $field_name ),* ) => $crate::__nes_transparent!(
+                            { ::std::fmt::Display::fmt( $crate::__nes_transparent_field!( $( $field_name ),* ), f) }
+                            { write!(f, concat!("{}",$message), "", $( $field_name ),* ) };
+                            $( $( $meta )* , )*
+                        )
+                    ),*
+                }
+            }
+        }
+    };
+
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_meta{
+    ( code; code = $value:expr , $( $rest:tt )* ) => { Some($value) };
+    ( help; help = $value:expr , $( $rest:tt )* ) => { Some($value) };
+    ( url; url = $value:expr , $( $rest:tt )* ) => { Some($value) };
+    ( note; note = $value:expr , $( $rest:tt )* ) => { Some($value) };
+    ( warning; warning = $value:expr , $( $rest:tt )* ) => { Some($value) };
+    ( fluent; fluent = $value:expr , $( $rest:tt )* ) => { Some($value) };
+    ( severity; severity = $value:ident , $( $rest:tt )* ) => { $crate::Severity::$value };
+
+    ( severity; ) => { $crate::Severity::Error };
+    ( $key:ident; ) => { None };
+    ( $key:ident; , $( $rest:tt )* ) => { $crate::__nes_meta!($key; $( $rest )*) };
+    ( $key:ident; $other:ident = $value:expr , $( $rest:tt )* ) => { $crate::__nes_meta!($key; $( $rest )*) };
+    ( $key:ident; $other:ident , $( $rest:tt )* ) => { $crate::__nes_meta!($key; $( $rest )*) };
+}
+
+//expands to the first block, if variant has meta transparent, else to the second
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_transparent{
+    ( { $( $transparent:tt )* } { $( $default:tt )* }; transparent , $( $rest:tt )* ) => { { $( $transparent )* } };
+    ( $transparent:tt $default:tt; ) => { $default };
+    ( $transparent:tt $default:tt; , $( $rest:tt )* ) => { $crate::__nes_transparent!( $transparent $default; $( $rest )* ) };
+    ( $transparent:tt $default:tt; $other:ident = $value:expr , $( $rest:tt )* ) => { $crate::__nes_transparent!( $transparent $default; $( $rest )* ) };
+    ( $transparent:tt $default:tt; $other:ident , $( $rest:tt )* ) => { $crate::__nes_transparent!( $transparent $default; $( $rest )* ) };
+}
+
+//the only field of transparent variant
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_transparent_field{
+    ( $field_name:ident ) => { $field_name };
+    ( $( $field_name:ident ),* ) => { compile_error!("#[nes(transparent)] variant must have exactly one field") };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_options{
+    ( $vis:vis $error_name:ident, $variants:tt, ) => {};
+    ( $vis:vis $error_name:ident, $variants:tt, , $( $rest:tt )* ) => {
+        $crate::__nes_options!( $vis $error_name, $variants, $( $rest )* );
+    };
+    ( $vis:vis $error_name:ident, $variants:tt, clone , $( $rest:tt )* ) => {
+        $crate::__nes_impl_clone!( $error_name, $variants );
+        $crate::__nes_options!( $vis $error_name, $variants, $( $rest )* );
+    };
+    ( $vis:vis $error_name:ident, $variants:tt, partial_eq , $( $rest:tt )* ) => {
+        $crate::__nes_impl_partial_eq!( $error_name, $variants );
+        $crate::__nes_options!( $vis $error_name, $variants, $( $rest )* );
+    };
+    ( $vis:vis $error_name:ident, $variants:tt, eq , $( $rest:tt )* ) => {
+        $crate::__nes_impl_partial_eq!( $error_name, $variants );
+        $crate::__nes_impl_eq!( $error_name, $variants );
+        $crate::__nes_options!( $vis $error_name, $variants, $( $rest )* );
+    };
+    ( $vis:vis $error_name:ident, $variants:tt, send , $( $rest:tt )* ) => {
+        const _: fn() = || {
+            fn assert_send<T:Send>() {}
+            assert_send::<$error_name>();
+        };
+        $crate::__nes_options!( $vis $error_name, $variants, $( $rest )* );
+    };
+    ( $vis:vis $error_name:ident, $variants:tt, send_sync , $( $rest:tt )* ) => {
+        const _: fn() = || {
+            fn assert_send_sync<T:Send + Sync>() {}
+            assert_send_sync::<$error_name>();
+        };
+        $crate::__nes_options!( $vis $error_name, $variants, $( $rest )* );
+    };
+    ( $vis:vis $error_name:ident, $variants:tt, max_size = $size:expr , $( $rest:tt )* ) => {
+        const _: () = assert!(
+            ::std::mem::size_of::<$error_name>() <= $size,
+            concat!("size of ", stringify!($error_name), " is larger than max_size, put large fields into Box")
+        );
+        $crate::__nes_options!( $vis $error_name, $variants, $( $rest )* );
+    };
+    ( $vis:vis $error_name:ident, $variants:tt, arbitrary , $( $rest:tt )* ) => {
+        $crate::__nes_impl_arbitrary!( $error_name, $variants );
+        $crate::__nes_options!( $vis $error_name, $variants, $( $rest )* );
+    };
+    ( $vis:vis $error_name:ident, $variants:tt, result = $result:ident , $( $rest:tt )* ) => {
+        $vis type $result<T = ()> = ::std::result::Result<T, $error_name>;
+        $crate::__nes_options!( $vis $error_name, $variants, $( $rest )* );
+    };
+    ( $vis:vis $error_name:ident, $variants:tt, wire , $( $rest:tt )* ) => {
+        $crate::__nes_impl_wire!( $error_name, $variants );
+        $crate::__nes_options!( $vis $error_name, $variants, $( $rest )* );
+    };
+    ( $vis:vis $error_name:ident, $variants:tt, extendable , $( $rest:tt )* ) => {
+        $crate::__nes_options!( $vis $error_name, $variants, $( $rest )* );
+    };
+    ( $vis:vis $error_name:ident, $variants:tt, codes = $codes:ident , $( $rest:tt )* ) => {
+        $crate::__nes_options!( $vis $error_name, $variants, $( $rest )* );
+    };
+    ( $vis:vis $error_name:ident, $variants:tt, $option:ident $( $rest:tt )* ) => {
+        compile_error!(concat!("unknown option of define_error!(): ", stringify!($option)));
+    };
+}
+
+//option codes generates module with id of each variant
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_codes{
+    ( $vis:vis $error_name:ident, $variants:tt, ) => {};
+    ( $vis:vis $error_name:ident, { $( $var_name:ident $metas:tt ),* }, codes = $codes:ident , $( $rest:tt )* ) => {
+        ///Stable identifiers of variants, that are set by `#[nes(id = "...")]`, default is `ErrorName.VariantName`.
+        #[allow(non_upper_case_globals)]
+        $vis mod $codes {
+            $(
+                pub const $var_name:&str = $crate::__nes_id!( $error_name, $var_name, $metas );
+            )*
+        }
+    };
+    ( $vis:vis $error_name:ident, $variants:tt, , $( $rest:tt )* ) => {
+        $crate::__nes_codes!( $vis $error_name, $variants, $( $rest )* );
+    };
+    ( $vis:vis $error_name:ident, $variants:tt, $other:ident = $value:expr , $( $rest:tt )* ) => {
+        $crate::__nes_codes!( $vis $error_name, $variants, $( $rest )* );
+    };
+    ( $vis:vis $error_name:ident, $variants:tt, $other:ident , $( $rest:tt )* ) => {
+        $crate::__nes_codes!( $vis $error_name, $variants, $( $rest )* );
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_id{
+    ( $error_name:ident, $var_name:ident, [ id = $value:expr , $( $rest:tt )* ] ) => { $value };
+    ( $error_name:ident, $var_name:ident, [] ) => { concat!(stringify!($error_name), ".", stringify!($var_name)) };
+    ( $error_name:ident, $var_name:ident, [ , $( $rest:tt )* ] ) => { $crate::__nes_id!( $error_name, $var_name, [ $( $rest )* ] ) };
+    ( $error_name:ident, $var_name:ident, [ $other:ident = $value:expr , $( $rest:tt )* ] ) => { $crate::__nes_id!( $error_name, $var_name, [ $( $rest )* ] ) };
+    ( $error_name:ident, $var_name:ident, [ $other:ident , $( $rest:tt )* ] ) => { $crate::__nes_id!( $error_name, $var_name, [ $( $rest )* ] ) };
+}
+
+//option extendable generates macro with name of the error, that replays its variants for define_error_extend!()
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_extendable{
+    ( $error_name:ident, $variants:tt, ) => {};
+    ( $error_name:ident, $variants:tt, extendable , $( $rest:tt )* ) => {
+        $crate::__nes_extendable_macro!( ($), $error_name, $variants );
+    };
+    ( $error_name:ident, $variants:tt, , $( $rest:tt )* ) => {
+        $crate::__nes_extendable!( $error_name, $variants, $( $rest )* );
+    };
+    ( $error_name:ident, $variants:tt, $other:ident = $value:expr , $( $rest:tt )* ) => {
+        $crate::__nes_extendable!( $error_name, $variants, $( $rest )* );
+    };
+    ( $error_name:ident, $variants:tt, $other:ident , $( $rest:tt )* ) => {
+        $crate::__nes_extendable!( $error_name, $variants, $( $rest )* );
+    };
+}
+
+//variants of bases are accumulated, the last base defines the error, each base implements From and Embeds for it
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_extendable_macro{
+    ( ($d:tt), $base:ident, {
+        $(
+            $( #[doc = $doc:expr] )*
+            $( #[nes( $( $meta:tt )* )] )*
+            $var_name:ident ( $( $field_name:ident : $field_type:ty ),* ) => $message:expr
+        ),*
+    } ) => {
+        #[allow(unused_macros)]
+        macro_rules! $base {
+            ( @nes_extend $d options:tt $d error_name:ident [ $d( $d accumulated:tt )* ] [ + $d next:ident $d( + $d more:ident )* ] $d( $d variants:tt )* ) => {
+                $d next!( @nes_extend $d options $d error_name
+                    [
+                        $d( $d accumulated )*
+                        $(
+                            $( #[doc = $doc] )*
+                            $( #[nes( $( $meta )* )] )*
+                            $var_name ( $( $field_name : $field_type ),* ) => $message ,
+                        )*
+                    ]
+                    [ $d( + $d more )* ]
+                    $d( $d variants )*
+                );
+
+                $base!( @nes_embed $d error_name );
+            };
+            ( @nes_extend [ $d( $d option:tt )* ] $d error_name:ident [ $d( $d accumulated:tt )* ] [] $d( $d variants:tt )* ) => {
+                $crate::define_error!( $d( $d option )* $d error_name,
+                    $d( $d accumulated )*
+                    $(
+                        $( #[doc = $doc] )*
+                        $( #[nes( $( $meta )* )] )*
+                        $var_name ( $( $field_name : $field_type ),* ) => $message
+                    ),*
+                    $d( $d variants )*
+                );
+
+                impl $d error_name {
+                    ///Returns true, if variant is flattened from error E.
+                    pub fn embeds<E>(&self) -> bool where Self: $crate::Embeds<E> {
+                        <Self as $crate::Embeds<E>>::embeds(self)
+                    }
+                }
+
+                $base!( @nes_embed $d error_name );
+            };
+            ( @nes_embed $d error_name:ident ) => {
+                impl ::std::convert::From<$base> for $d error_name {
+                    fn from(error:$base) -> Self {
+                        match error {
+                            $(
+                                $base::$var_name( error_info, $( $field_name ),* ) => $d error_name::$var_name( error_info, $( $field_name ),* )
+                            ),*
+                        }
+                    }
+                }
+
+                impl ::std::convert::TryFrom<$d error_name> for $base {
+                    type Error = $d error_name;
+
+                    fn try_from(error:$d error_name) -> ::std::result::Result<Self, $d error_name> {
+                        match error {
+                            $(
+                                $d error_name::$var_name( error_info, $( $field_name ),* ) => Ok($base::$var_name( error_info, $( $field_name ),* )),
+                            )*
+                            #[allow(unreachable_patterns)]
+                            error => Err(error),
+                        }
+                    }
+                }
+
+                impl $crate::Embeds<$base> for $d error_name {
+                    fn embeds(&self) -> bool {
+                        match *self {
+                            $(
+                                $d error_name::$var_name(..) => true,
+                            )*
+                            #[allow(unreachable_patterns)]
+                            _ => false,
+                        }
+                    }
+                }
+            };
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_impl_clone{
+    ( $error_name:ident, [ $( $var_name:ident ( $( $field_name:ident : $field_type:ty ),* ) ),* ] ) => {
+        impl Clone for $error_name {
+            fn clone(&self) -> Self {
                 match *self {
                     $(
                         $error_name::$var_name( ref error_info, $( ref $field_name ),* ) =>
-                            write!(
-                                f,
-                                concat!("{}\n",stringify!($error_name),"::",stringify!($var_name)," ",$( concat!(stringify!($field_name),":{:?} ") ),* ),
-                                error_info, $( $field_name ),*
-                            )
+                            $error_name::$var_name( error_info.clone(), $( $field_name.clone() ),* )
+                    ),*
+                }
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_impl_partial_eq{
+    ( $error_name:ident, [ $( $var_name:ident ( $( $field_name:ident : $field_type:ty ),* ) ),* ] ) => {
+        impl PartialEq for $error_name {
+            fn eq(&self, other:&Self) -> bool {
+                match *self {
+                    $(
+                        $error_name::$var_name( _, $( ref $field_name ),* ) => {
+                            let fields=( $( $field_name, )* );
+
+                            match *other {
+                                $error_name::$var_name( _, $( ref $field_name ),* ) => fields==( $( $field_name, )* ),
+                                #[allow(unreachable_patterns)]
+                                _ => false,
+                            }
+                        }
                     ),*
                 }
             }
         }
-/* I think, this is not necessary
-        impl Error for $error_name {
-            fn description(&self) -> &str {
-                match *self {
-                    $(
-                        $error_name::$var_name( ref error_info, $( ref $field_name ),* ) => concat!(stringify!($error_name),"::",stringify!($var_name))
-                    ),*
-                }
+    };
+}
+
+//Eq is implemented only if all fields implement Eq, ErrorInfo is not compared
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_impl_eq{
+    ( $error_name:ident, [ $( $var_name:ident ( $( $field_name:ident : $field_type:ty ),* ) ),* ] ) => {
+        const _: fn() = || {
+            fn assert_eq<T:?Sized + Eq>() {}
+            $( $( assert_eq::<$field_type>(); )* )*
+        };
+
+        impl Eq for $error_name {}
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_context{
+    ( $vis:vis $error_name:ident, $var_name:ident, $fields:tt, ) => {};
+    ( $vis:vis $error_name:ident, $var_name:ident, $fields:tt, , $( $rest:tt )* ) => {
+        $crate::__nes_context!( $vis $error_name, $var_name, $fields, $( $rest )* );
+    };
+    ( $vis:vis $error_name:ident, $var_name:ident, $fields:tt, context = $selector:ident , $( $rest:tt )* ) => {
+        $crate::__nes_context_selector!( context $vis $error_name, $var_name, $selector, $fields );
+        $crate::__nes_context!( $vis $error_name, $var_name, $fields, $( $rest )* );
+    };
+    ( $vis:vis $error_name:ident, $var_name:ident, $fields:tt, selector = $selector:ident , $( $rest:tt )* ) => {
+        $crate::__nes_context_selector!( selector $vis $error_name, $var_name, $selector, $fields );
+        $crate::__nes_context!( $vis $error_name, $var_name, $fields, $( $rest )* );
+    };
+    ( $vis:vis $error_name:ident, $var_name:ident, $fields:tt, constructor = $constructor:ident , $( $rest:tt )* ) => {
+        $crate::__nes_constructor!( $error_name, $var_name, $constructor, $fields );
+        $crate::__nes_context!( $vis $error_name, $var_name, $fields, $( $rest )* );
+    };
+    ( $vis:vis $error_name:ident, $var_name:ident, $fields:tt, builder = $builder:ident , $( $rest:tt )* ) => {
+        $crate::__nes_builder!( $vis $error_name, $var_name, $builder, $fields );
+        $crate::__nes_context!( $vis $error_name, $var_name, $fields, $( $rest )* );
+    };
+    ( $vis:vis $error_name:ident, $var_name:ident, $fields:tt, fixture = $fixture:ident , $( $rest:tt )* ) => {
+        $crate::__nes_fixture!( $error_name, $var_name, $fixture, $fields );
+        $crate::__nes_context!( $vis $error_name, $var_name, $fields, $( $rest )* );
+    };
+    ( $vis:vis $error_name:ident, $var_name:ident, $fields:tt, $other:ident = $value:expr , $( $rest:tt )* ) => {
+        $crate::__nes_context!( $vis $error_name, $var_name, $fields, $( $rest )* );
+    };
+    ( $vis:vis $error_name:ident, $var_name:ident, $fields:tt, $other:ident , $( $rest:tt )* ) => {
+        $crate::__nes_context!( $vis $error_name, $var_name, $fields, $( $rest )* );
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_context_selector{
+    ( context $vis:vis $error_name:ident, $var_name:ident, $selector:ident, [ $source_name:ident : $source_type:ty $(, $field_name:ident : $field_type:ty )* ] ) => {
+        ///Context selector, `result.context(selector)` converts error of result into variant, error is the first field.
+        $vis struct $selector {
+            $( pub $field_name : $field_type ),*
+        }
+
+        impl<S> $crate::IntoError<S> for $selector where $source_type: ::std::convert::From<S> {
+            type Error = $error_name;
+
+            #[track_caller]
+            fn into_error(self, source:S) -> $error_name {
+                $error_name::$var_name(
+                    $crate::caller_error_info::<ErrorInfo>(),
+                    ::std::convert::From::from(source),
+                    $( self.$field_name ),*
+                )
+            }
+        }
+    };
+    ( context $vis:vis $error_name:ident, $var_name:ident, $selector:ident, [] ) => {
+        compile_error!(concat!("variant ", stringify!($var_name), " has no field for source, use `selector = ", stringify!($selector), "` instead"));
+    };
+    ( selector $vis:vis $error_name:ident, $var_name:ident, $selector:ident, [ $( $field_name:ident : $field_type:ty ),* ] ) => {
+        ///Selector, `selector.fail()` returns Err with variant, `selector.build()` returns variant.
+        $vis struct $selector {
+            $( pub $field_name : $field_type ),*
+        }
+
+        impl $selector {
+            #[track_caller]
+            pub fn build(self) -> $error_name {
+                $error_name::$var_name(
+                    $crate::caller_error_info::<ErrorInfo>(),
+                    $( self.$field_name ),*
+                )
+            }
+
+            #[track_caller]
+            pub fn fail<T>(self) -> Result<T, $error_name> {
+                Err(self.build())
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_constructor{
+    ( $error_name:ident, $var_name:ident, $constructor:ident, [ $( $field_name:ident : $field_type:ty ),* ] ) => {
+        impl $error_name {
+            ///Creates variant with location of caller.
+            #[track_caller]
+            pub fn $constructor( $( $field_name : $field_type ),* ) -> Self {
+                $error_name::$var_name( $crate::caller_error_info(), $( $field_name ),* )
+            }
+        }
+    };
+}
+
+//type parameters of builder are named by fields, parameter is () until field is set
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_builder{
+    ( $vis:vis $error_name:ident, $var_name:ident, $builder:ident, [ $( $field_name:ident : $field_type:ty ),* ] ) => {
+        ///Builder of variant, `finish()` is available, when all fields are set.
+        #[allow(non_camel_case_types)]
+        $vis struct $builder< $( $field_name = () ),* > {
+            $( $field_name : $field_name ),*
+        }
+
+        impl $builder {
+            pub fn new() -> Self {
+                $builder { $( $field_name : () ),* }
+            }
+        }
+
+        impl ::std::default::Default for $builder {
+            fn default() -> Self {
+                $builder::new()
+            }
+        }
+
+        impl $builder< $( $field_type ),* > {
+            ///Creates variant with location of caller.
+            #[track_caller]
+            pub fn finish(self) -> $error_name {
+                $error_name::$var_name( $crate::caller_error_info(), $( self.$field_name ),* )
+            }
+        }
+
+        $crate::__nes_builder_setters!( $builder, [ $( $field_name ),* ], [], [ $( $field_name : $field_type ),* ] );
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_builder_setters{
+    ( $builder:ident, $all:tt, $done:tt, [] ) => {};
+    ( $builder:ident, [ $( $all:ident ),* ], [ $( $done:ident ),* ], [ $field_name:ident : $field_type:ty $(, $rest_name:ident : $rest_type:ty )* ] ) => {
+        #[allow(non_camel_case_types)]
+        impl< $( $all ),* > $builder< $( $all ),* > {
+            pub fn $field_name(self, $field_name:$field_type) -> $builder< $( $done, )* $field_type $(, $rest_name )* > {
+                $builder { $( $done : self.$done, )* $field_name $(, $rest_name : self.$rest_name )* }
+            }
+        }
+
+        $crate::__nes_builder_setters!( $builder, [ $( $all ),* ], [ $( $done, )* $field_name ], [ $( $rest_name : $rest_type ),* ] );
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_span_field{
+    ( span, $field:ident ) => {
+        Some( $field.clone() )
+    };
+    ( offset, $field:ident ) => {
+        Some( *$field..*$field )
+    };
+    ( $field_name:ident, $field:ident ) => {
+        { let _ = $field; None }
+    };
+}
+
+#[cfg(feature = "reporters")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_report_method{
+    () => {
+        ///Sends the error to all reporters, that are registered by nes::add_reporter().
+        pub fn report(&self) {
+            $crate::reporter::report(self)
+        }
+    };
+}
+
+#[cfg(not(feature = "reporters"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_report_method{
+    () => {};
+}
+
+//err!(), try!() and create_err!() evaluate arguments and bind them one by one, hygiene makes each binding `arg` and `field`
+//distinct, so count of fields is not limited. Types of fields are inferred by call of variant, that is never evaluated,
+//then each argument is passed as is or is converted by FromArg, see __private::Field. err!() and try!() call the variant
+//by closure, that is called by __private::construct(), errors of try!() are converted by From, they are passed in the first brackets.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_construct{
+    ( $mode:ident $error:path, [ $( $source:ident )* ], [ $( $bound:tt )* ], $arg:expr $(, $rest:expr )* ) => {{
+        let arg=$arg;
+        let field=$crate::__private::Field::new();
+        $crate::__nes_construct!( $mode $error, [ $( $source )* ], [ $( $bound )* ( arg field ) ], $( $rest ),* )
+    }};
+    ( cold $error:path, [ $( $source:ident )* ], [ $( ( $arg:ident $field:ident ) )* ], ) => {{
+        #[allow(unused_imports)]
+        use $crate::__private::{ArgViaSame,ArgViaFromArg,ArgViaPlain};
+
+        let _=|| $error( $crate::__private::never() $(, { let _=&$source; $crate::__private::never() } )* $(, $field.value() )* );
+
+        $crate::__private::construct(move || $error(
+            ErrorInfo::new($crate::__nes_file!(), line!(), column!())
+            $(, ::std::convert::From::from($source) )*
+            $(, (&&&$field.arg($arg)).nes_arg() )*
+        ))
+    }};
+    ( inline $error:path, [], [ $( ( $arg:ident $field:ident ) )* ], ) => {{
+        #[allow(unused_imports)]
+        use $crate::__private::{ArgViaSame,ArgViaFromArg,ArgViaPlain};
+
+        let _=|| $error( $crate::__private::never() $(, $field.value() )* );
+
+        $error( $crate::error_info!() $(, (&&&$field.arg($arg)).nes_arg() )* )
+    }};
+}
+
+#[cfg(feature = "diagnostic")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_impl_diagnostic{
+    ( $error_name:ident ) => {
+        impl $crate::diagnostic::Spanned for $error_name {
+            fn span(&self) -> Option<::std::ops::Range<usize>> {
+                $error_name::span(self)
+            }
+        }
+
+        impl $error_name {
+            ///Attaches source, Display of result shows the line of source, where span of the error is.
+            pub fn with_source<'a>(&'a self, source:&'a $crate::diagnostic::Source) -> $crate::diagnostic::WithSource<'a, $error_name> {
+                $crate::diagnostic::WithSource::new(self, source)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "diagnostic"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_impl_diagnostic{
+    ( $error_name:ident ) => {};
+}
+
+#[cfg(feature = "miette")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_impl_miette{
+    ( $error_name:ident ) => {
+        impl $crate::__private::miette::Diagnostic for $error_name {
+            fn code<'a>(&'a self) -> Option<$crate::__private::BoxDisplay<'a>> {
+                $error_name::code(self).map(|code| Box::new(code) as $crate::__private::BoxDisplay)
+            }
+
+            fn help<'a>(&'a self) -> Option<$crate::__private::BoxDisplay<'a>> {
+                $error_name::help(self).map(|help| Box::new(help) as $crate::__private::BoxDisplay)
+            }
+
+            fn url<'a>(&'a self) -> Option<$crate::__private::BoxDisplay<'a>> {
+                $error_name::url(self).map(|url| Box::new(url) as $crate::__private::BoxDisplay)
+            }
+
+            fn severity(&self) -> Option<$crate::__private::miette::Severity> {
+                Some( $error_name::severity(self).into() )
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "miette"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_impl_miette{
+    ( $error_name:ident ) => {};
+}
+
+#[cfg(feature = "defmt")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_impl_defmt{
+    ( $error_name:ident ) => {
+        impl $crate::__private::defmt::Format for $error_name {
+            fn format(&self, f:$crate::__private::defmt::Formatter) {
+                $crate::defmt::format_error(f, self)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "defmt"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_impl_defmt{
+    ( $error_name:ident ) => {};
+}
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_impl_serde{
+    ( $error_name:ident, [ $( $var_name:ident ( $( $field_name:ident : $field_type:ty ),* ) ),* ] ) => {
+        impl $crate::__private::serde::Serialize for $error_name {
+            fn serialize<S:$crate::__private::serde::Serializer>(&self, serializer:S) -> ::std::result::Result<S::Ok, S::Error> {
+                use $crate::__private::serde::ser::SerializeMap;
+
+                match *self {
+                    $(
+                        $error_name::$var_name( ref error_info, $( ref $field_name ),* ) => {
+                            struct Fields<'a>( ::std::marker::PhantomData<&'a ()>, $( &'a $field_type ),* );
+
+                            impl<'a> $crate::__private::serde::Serialize for Fields<'a> {
+                                fn serialize<S:$crate::__private::serde::Serializer>(&self, serializer:S) -> ::std::result::Result<S::Ok, S::Error> {
+                                    #[allow(unused_imports)]
+                                    use $crate::__private::{SerializeViaSerialize,SerializeViaDisplay,SerializeViaDebug};
+
+                                    let Fields( _, $( $field_name ),* ) = *self;
+                                    let mut map=serializer.serialize_map(None)?;
+                                    $(
+                                        map.serialize_entry($crate::__private::ident_name(stringify!($field_name)), &(&&$crate::__private::SerializeField($field_name)).nes_serialize())?;
+                                    )*
+                                    map.end()
+                                }
+                            }
+
+                            let mut map=serializer.serialize_map(Some(6))?;
+                            map.serialize_entry("error", $crate::__private::ident_name(stringify!($error_name)))?;
+                            map.serialize_entry("variant", $crate::__private::ident_name(stringify!($var_name)))?;
+                            map.serialize_entry("code", &self.code())?;
+                            map.serialize_entry("location", &$crate::__private::SerializeLocation(error_info))?;
+                            map.serialize_entry("message", &::std::string::ToString::to_string(self))?;
+                            map.serialize_entry("fields", &Fields( ::std::marker::PhantomData, $( $field_name ),* ))?;
+                            map.end()
+                        }
+                    ),*
+                }
+            }
+        }
+
+        impl $crate::schema::ErrorSchema for $error_name {
+            fn push_schema_defs(defs:&mut Vec<(&'static str, String)>) {
+                #[allow(unused_imports)]
+                use $crate::schema::{SchemaViaNes,SchemaViaJsonType,SchemaViaSerialize,SchemaViaString};
+
+                if defs.iter().any(|def| def.0==$crate::__private::ident_name(stringify!($error_name))) {
+                    return;
+                }
+
+                //definition is pushed before fields to stop recursion of errors, that contain themselves
+                let index=defs.len();
+                defs.push(($crate::__private::ident_name(stringify!($error_name)), String::new()));
+
+                let variants:Vec<String>=vec![
+                    $(
+                        $crate::schema::variant_schema($crate::__private::ident_name(stringify!($error_name)), $crate::__private::ident_name(stringify!($var_name)), &[
+                            $(
+                                ($crate::__private::ident_name(stringify!($field_name)), (&&&$crate::schema::SchemaField::<$field_type>::new()).nes_schema(defs))
+                            ),*
+                        ])
+                    ),*
+                ];
+
+                defs[index].1=format!("{{\"oneOf\":[{}]}}", variants.join(","));
+            }
+
+            fn error_name() -> &'static str {
+                $crate::__private::ident_name(stringify!($error_name))
+            }
+        }
+
+        impl $error_name {
+            ///Returns JSON Schema of serialized error, see module nes::schema.
+            pub fn schema() -> String {
+                <$error_name as $crate::schema::ErrorSchema>::schema()
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_impl_serde{
+    ( $error_name:ident, [ $( $var_name:ident ( $( $field_name:ident : $field_type:ty ),* ) ),* ] ) => {};
+}
+
+#[cfg(feature = "protobuf")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_impl_protobuf{
+    ( $error_name:ident, [ $( $var_name:ident ( $( $field_name:ident ),* ) ),* ] ) => {
+        impl $crate::protobuf::ToProtobuf for $error_name {
+            fn to_protobuf(&self) -> $crate::protobuf::NesError {
+                #[allow(unused_imports)]
+                use $crate::protobuf::{ValueViaDisplay,ValueViaDebug};
+
+                let fields=match *self {
+                    $(
+                        $error_name::$var_name( _, $( ref $field_name ),* ) => vec![
+                            $(
+                                $crate::protobuf::Field {
+                                    key:$crate::__private::ident_name(stringify!($field_name)).to_string(),
+                                    value:(&$crate::protobuf::ProtobufField($field_name)).protobuf_value()
+                                }
+                            ),*
+                        ]
+                    ),*
+                };
+
+                $crate::protobuf::NesError::new(self, self.code(), fields)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "protobuf"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_impl_protobuf{
+    ( $error_name:ident, $variants:tt ) => {};
+}
+
+#[cfg(feature = "i18n")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_impl_i18n{
+    ( $error_name:ident, [ $( $var_name:ident ( $( $field_name:ident ),* ) { $( $meta:tt )* } ),* ] ) => {
+        impl $crate::i18n::Localize for $error_name {
+            fn localized_message(&self, lang:&$crate::i18n::LanguageIdentifier) -> String {
+                #[allow(unused_imports)]
+                use $crate::i18n::{ValueViaLocalize,ValueViaFluent,ValueViaDisplay,ValueViaDebug};
+
+                match *self {
+                    $(
+                        $error_name::$var_name( _, $( ref $field_name ),* ) => {
+                            let default_key=format!("{}-{}", $crate::__private::ident_name(stringify!($error_name)), $crate::__private::ident_name(stringify!($var_name)));
+                            let key=$crate::__nes_meta!(fluent; $( $meta )* ).unwrap_or(&default_key);
+                            #[allow(unused_mut)]
+                            let mut args=$crate::i18n::FluentArgs::new();
+                            $(
+                                args.set($crate::__private::ident_name(stringify!($field_name)), (&&&$crate::i18n::LocalizeField($field_name)).fluent_value(lang));
+                            )*
+
+                            match $crate::i18n::format_message(lang, key, &args) {
+                                Some( message ) => message,
+                                None => $crate::i18n::canonical_message(self),
+                            }
+                        }
+                    ),*
+                }
+            }
+        }
+
+        impl $error_name {
+            ///Returns message of variant, that is translated by Fluent catalog of language, see module nes::i18n.
+            pub fn localized_message(&self, lang:&$crate::i18n::LanguageIdentifier) -> String {
+                $crate::i18n::Localize::localized_message(self, lang)
+            }
+
+            ///Returns message of variant in global locale(see nes::i18n::set_locale()) or canonical message without ErrorInfo.
+            pub fn user_message(&self) -> String {
+                $crate::i18n::user_message(self)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "i18n"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_impl_i18n{
+    ( $error_name:ident, $variants:tt ) => {};
+}
+
+#[cfg(feature = "wire")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_impl_wire{
+    ( $error_name:ident, [ $( $var_name:ident ( $( $field_name:ident : $field_type:ty ),* ) ),* ] ) => {
+        impl $crate::wire::WireFormat for $error_name {
+            fn serialize_wire<S:$crate::__private::serde::Serializer>(&self, serializer:S) -> ::std::result::Result<S::Ok, S::Error> {
+                use $crate::__private::serde::ser::SerializeTuple;
+
+                const VARIANTS:&[&str] = &[ $( $crate::__private::ident_name(stringify!($var_name)) ),* ];
+
+                match *self {
+                    $(
+                        $error_name::$var_name( ref error_info, $( ref $field_name ),* ) => {
+                            struct Fields<'a>( ::std::marker::PhantomData<&'a ()>, $( &'a $field_type ),* );
+
+                            impl<'a> $crate::__private::serde::Serialize for Fields<'a> {
+                                fn serialize<S:$crate::__private::serde::Serializer>(&self, serializer:S) -> ::std::result::Result<S::Ok, S::Error> {
+                                    #[allow(unused_imports)]
+                                    use $crate::wire::{WireViaNes,WireViaSerde};
+
+                                    let Fields( _, $( $field_name ),* ) = *self;
+                                    let mut tuple=serializer.serialize_tuple(<[&str]>::len(&[ $( $crate::__private::ident_name(stringify!($field_name)) ),* ]))?;
+                                    $(
+                                        tuple.serialize_element(&(&$crate::wire::WireField($field_name)).wire_value())?;
+                                    )*
+                                    tuple.end()
+                                }
+                            }
+
+                            let index=VARIANTS.iter().position(|name| *name==$crate::__private::ident_name(stringify!($var_name))).unwrap_or(0) as u32;
+
+                            let mut tuple=serializer.serialize_tuple(5)?;
+                            tuple.serialize_element(&index)?;
+                            tuple.serialize_element($crate::ErrorInfoTrait::file(error_info))?;
+                            tuple.serialize_element(&$crate::ErrorInfoTrait::line(error_info))?;
+                            tuple.serialize_element(&$crate::ErrorInfoTrait::col(error_info))?;
+                            tuple.serialize_element(&Fields( ::std::marker::PhantomData, $( $field_name ),* ))?;
+                            tuple.end()
+                        }
+                    ),*
+                }
+            }
+
+            fn deserialize_wire<'de, D:$crate::__private::serde::Deserializer<'de>>(deserializer:D) -> ::std::result::Result<Self, D::Error> {
+                use $crate::__private::serde::de::{Error,SeqAccess,Visitor};
+
+                const VARIANTS:&[&str] = &[ $( $crate::__private::ident_name(stringify!($var_name)) ),* ];
+
+                struct BodyVisitor;
+
+                impl<'de> Visitor<'de> for BodyVisitor {
+                    type Value = $error_name;
+
+                    fn expecting(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                        write!(f, concat!("nes error ", stringify!($error_name)))
+                    }
+
+                    fn visit_seq<A:SeqAccess<'de>>(self, mut seq:A) -> ::std::result::Result<$error_name, A::Error> {
+                        let index:u32=seq.next_element()?.ok_or_else(|| A::Error::missing_field("variant"))?;
+                        let file:String=seq.next_element()?.ok_or_else(|| A::Error::missing_field("file"))?;
+                        let line:u32=seq.next_element()?.ok_or_else(|| A::Error::missing_field("line"))?;
+                        let col:u32=seq.next_element()?.ok_or_else(|| A::Error::missing_field("col"))?;
+
+                        let name=match VARIANTS.get(index as usize) {
+                            Some( name ) => *name,
+                            None => return Err(A::Error::custom(format_args!(concat!("unknown variant {} of ", stringify!($error_name)), index))),
+                        };
+                        let error_info:ErrorInfo=$crate::ErrorInfoTrait::new($crate::wire::intern_file(&file), line, col);
+
+                        seq.next_element_seed(VariantSeed(name, error_info))?.ok_or_else(|| A::Error::missing_field("fields"))
+                    }
+                }
+
+                struct VariantSeed(&'static str, ErrorInfo);
+
+                impl<'de> $crate::__private::serde::de::DeserializeSeed<'de> for VariantSeed {
+                    type Value = $error_name;
+
+                    fn deserialize<D:$crate::__private::serde::Deserializer<'de>>(self, deserializer:D) -> ::std::result::Result<$error_name, D::Error> {
+                        let length=match self.0 {
+                            $(
+                                name if name==$crate::__private::ident_name(stringify!($var_name)) => <[&str]>::len(&[ $( $crate::__private::ident_name(stringify!($field_name)) ),* ]),
+                            )*
+                            _ => 0,
+                        };
+
+                        deserializer.deserialize_tuple(length, VariantVisitor(self.0, self.1))
+                    }
+                }
+
+                struct VariantVisitor(&'static str, ErrorInfo);
+
+                impl<'de> Visitor<'de> for VariantVisitor {
+                    type Value = $error_name;
+
+                    fn expecting(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                        write!(f, concat!("fields of variant of ", stringify!($error_name)))
+                    }
+
+                    #[allow(unused_mut, unused_variables)]
+                    fn visit_seq<A:SeqAccess<'de>>(self, mut seq:A) -> ::std::result::Result<$error_name, A::Error> {
+                        #[allow(unused_imports)]
+                        use $crate::wire::{WireTypeViaNes,WireTypeViaSerde};
+
+                        let VariantVisitor(name, error_info)=self;
+
+                        $(
+                            if name==$crate::__private::ident_name(stringify!($var_name)) {
+                                return Ok($error_name::$var_name(
+                                    error_info,
+                                    $(
+                                        (&$crate::wire::WireFieldType::<$field_type>::new()).next_field(&mut seq)?
+                                            .ok_or_else(|| A::Error::missing_field($crate::__private::ident_name(stringify!($field_name))))?
+                                    ),*
+                                ));
+                            }
+                        )*
+
+                        Err(A::Error::custom(format_args!(concat!("unknown variant {} of ", stringify!($error_name)), name)))
+                    }
+                }
+
+                deserializer.deserialize_tuple(5, BodyVisitor)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "wire"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_impl_wire{
+    ( $error_name:ident, $variants:tt ) => {
+        compile_error!("option wire of define_error!() needs feature \"wire\" of nes");
+    };
+}
+
+#[cfg(feature = "nightly")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_impl_provide{
+    ( $error_name:ident, [ $( $var_name:ident ),* ] ) => {
+        fn provide<'a>(&'a self, request:&mut ::std::error::Request<'a>) {
+            match *self {
+                $(
+                    $error_name::$var_name( ref error_info, .. ) => {
+                        request.provide_ref(error_info);
+                    }
+                ),*
+            }
+
+            request.provide_value_with::<::std::vec::Vec<$crate::TrailItem>>(|| $crate::ErrorTrail::error_trail(self));
+        }
+    };
+}
+
+#[cfg(not(feature = "nightly"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_impl_provide{
+    ( $error_name:ident, $variants:tt ) => {};
+}
+
+#[cfg(feature = "testing")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_fixture{
+    ( $error_name:ident, $var_name:ident, $fixture:ident, [ $( $field_name:ident : $field_type:ty ),* ] ) => {
+        impl $error_name {
+            ///Creates variant with dummy ErrorInfo for tests.
+            pub fn $fixture( $( $field_name : $field_type ),* ) -> Self {
+                $error_name::$var_name( $crate::testing::error_info(), $( $field_name ),* )
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "testing"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_fixture{
+    ( $error_name:ident, $var_name:ident, $fixture:ident, $fields:tt ) => {
+        compile_error!("fixture of variant needs feature \"testing\" of nes");
+    };
+}
+
+#[cfg(feature = "testing")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_impl_arbitrary{
+    ( $error_name:ident, [ $( $var_name:ident ( $( $field_name:ident : $field_type:ty ),* ) ),* ] ) => {
+        impl $crate::__private::proptest::arbitrary::Arbitrary for $error_name {
+            type Parameters = ();
+            type Strategy = $crate::__private::proptest::strategy::BoxedStrategy<$error_name>;
+
+            fn arbitrary_with(_:()) -> Self::Strategy {
+                use $crate::__private::proptest::strategy::Strategy;
+
+                let variants=vec![
+                    $(
+                        $crate::__private::proptest::arbitrary::any::<( $( $field_type, )* )>()
+                            .prop_map(|( $( $field_name, )* )| $error_name::$var_name( $crate::testing::error_info(), $( $field_name ),* ))
+                            .boxed()
+                    ),*
+                ];
+
+                $crate::__private::proptest::strategy::Union::new(variants).boxed()
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "testing"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_impl_arbitrary{
+    ( $error_name:ident, $variants:tt ) => {
+        compile_error!("option arbitrary of define_error!() needs feature \"testing\" of nes");
+    };
+}
+
+///This macro implements From trait for other errors.
+///
+///It allows you to convert other errors into current and write something like function(..)?.
+///Note, that you must use this macro only for errors, that have been defined with define_error!(). For errors like std::io::Error you must use try!() macro, because it gets information, where this error has been occurred.
+///
+/// # Example
+///
+/// ```ignore
+///impl_from_error!(ReadFileError => CommonError);
+///
+///fn read_file(file_name:String) -> result![Vec<String>,ReadFileError] { ... }
+///
+///fn process() -> result![CommonError] { //Or Result<CommonError>
+///    let lines=read_file(file_name)?;
+///    ...
+///}
+/// ```
+///
+/// You will get error like:
+///
+/// ```text
+///example/examples/example.rs 16:0   //line, where impl_from_error!() is.
+///read file error example/examples/example.rs 51:13    //line where the error has been occurred
+///Can not read file "no_file.rs" : No such file or directory (os error 2)    //description of error
+/// ```
+///
+///Where is second form
+///
+/// # Example
+///
+/// ```ignore
+///impl_from_error!(::module::ReadFileError => CommonError::CanNotReadFile);
+/// ```
+///
+#[macro_export]
+macro_rules! impl_from_error{
+    ( $from_error:ident => $to_error:ident ) => {
+        impl From<$from_error> for $to_error {
+            fn from(from_error:$from_error) -> Self {
+                $to_error::$from_error($crate::error_info!(),::std::convert::From::from(from_error))
+            }
+        }
+    };
+    ( $from_error:path => $to_error:ident :: $to_variant:ident ) => {
+        impl From<$from_error> for $to_error {
+            fn from(from_error:$from_error) -> Self {
+                $to_error::$to_variant($crate::error_info!(),::std::convert::From::from(from_error))
+            }
+        }
+    };
+}
+
+///This macro defines error, that has all variants of other error and new ones, so layered crates do not copy lists of variants.
+///Base error needs option `#[nes(extendable)]`, that generates macro with its name, so define_error_extend!() must be after it in the same crate.
+///From<Base> is implemented for the new error, it moves ErrorInfo and fields into variant with same name.
+///TryFrom<NewError> is implemented for Base, so code of module may narrow error back into its own error, for example to retry locally,
+///variants, that are not from Base, are returned back as Err.
+///
+///Several errors may be flattened into one by `+`, so matching sees their variants directly without nested Box,
+///`error.embeds::<Base>()` returns true, if variant came from Base.
+///
+///Options of base error are not copied, new error may have own options. Variants with context, selector or builder can not be extended,
+///because their structs would be defined twice.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///define_error!( #[nes(extendable)] BaseError,
+///    #[nes(code = "E0001")]
+///    NotFound(name:String) => "{} is not found",
+///    Timeout() => "timeout"
+///);
+///
+///define_error_extend!( #[nes(clone)] ServerError : BaseError,
+///    Overloaded(connections:usize) => "server is overloaded, {} connections"
+///);
+///
+///fn find(name:&str) -> result![BaseError] {
+///    err!(BaseError::NotFound, name.to_string())
+///}
+///
+///fn handle(name:&str) -> result![ServerError] {
+///    find(name)?;
+///    ok!()
+///}
+///
+/// # fn main() {
+///match handle("config") {
+///    Err(error @ ServerError::NotFound(..)) => assert_eq!(error.code(), Some("E0001")),
+///    _ => unreachable!(),
+///}
+///
+///use std::convert::TryFrom;
+///
+///let error=handle("config").unwrap_err();
+///assert!(matches!(BaseError::try_from(error), Ok(BaseError::NotFound(..))));
+///
+///let error:ServerError=create_err!(ServerError::Overloaded, 100);
+///assert!(matches!(BaseError::try_from(error), Err(ServerError::Overloaded(..))));
+/// # }
+/// ```
+///
+///Flattening:
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///define_error!( #[nes(extendable)] ReadFileError,
+///    IOError(io_error:Box<std::io::Error>, file:String) => "can not read file \"{2}\": {1}"
+///);
+///
+///define_error!( #[nes(extendable)] ParseError,
+///    NotNumber(text:String) => "\"{}\" is not a number"
+///);
+///
+///define_error_extend!( ConfigError : ReadFileError + ParseError );
+///
+///fn read(file:&str) -> result![String,ReadFileError] {
+///    ok!(try!(std::fs::read_to_string(file), ReadFileError::IOError, file.to_string()))
+///}
+///
+///fn load(file:&str) -> result![String,ConfigError] {
+///    ok!(read(file)?)
+///}
+///
+/// # fn main() {
+///match load("no_file.rs") {
+///    Err(error @ ConfigError::IOError(..)) => {
+///        assert!(error.embeds::<ReadFileError>());
+///        assert!(!error.embeds::<ParseError>());
+///    },
+///    _ => unreachable!(),
+///}
+/// # }
+/// ```
+#[macro_export]
+macro_rules! define_error_extend{
+    ( $( #[nes( $( $option:tt )* )] )* $error_name:ident : $base:ident $( + $more:ident )*
+        $(
+            ,
+            $( #[doc = $doc:expr] )*
+            $( #[nes( $( $meta:tt )* )] )*
+            $var_name:ident ( $( $field_name:ident : $field_type:ty ),* ) => $message:expr
+        )* $(,)*
+    ) => {
+        $base!( @nes_extend [ $( #[nes( $( $option )* )] )* ] $error_name [] [ $( + $more )* ]
+            $(
+                ,
+                $( #[doc = $doc] )*
+                $( #[nes( $( $meta )* )] )*
+                $var_name ( $( $field_name : $field_type ),* ) => $message
+            )*
+        );
+    };
+}
+
+///This macro helps to migrate from error-chain. It accepts syntax like error_chain!() and expands to define_error!() and impl_from_error!().
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+/// # define_error!( OtherError, Failed() => "failed" );
+///define_error_chain! {
+///    types { Error, ErrorKind, ResultExt, Result; }
+///
+///    links {
+///        Another(OtherError, OtherErrorKind);
+///    }
+///
+///    foreign_links {
+///        Fmt(::std::fmt::Error);
+///        Io(::std::io::Error);
+///    }
+///
+///    errors {
+///        InvalidToolchainName(t: String) {
+///            description("invalid toolchain name")
+///            display("invalid toolchain name: '{}'", t)
+///        }
+///        NoToolchain {
+///            description("no toolchain")
+///        }
+///    }
+///}
+///
+///fn toolchain(name:&str) -> Result<String> {
+///    if name.is_empty() {
+///        return err!(Error::InvalidToolchainName, name.to_string());
+///    }
+///
+///    let path=std::env::current_dir()?; //Io, location is line of define_error_chain!()
+///    ok!(format!("{}/{}", path.display(), name))
+///}
+/// # fn main() { toolchain("stable").unwrap(); }
+/// ```
+///
+///Differences from error-chain:
+///
+///* `types` block is optional, but only the name of error and Result are used(ErrorKind is the error itself, ResultExt is not generated).
+///* Links and foreign links are variants with field `error:Box<..>`, both are converted by ? like impl_from_error!(), but location of foreign errors is the line of macro,
+///  so try!() is preferable for them.
+///* Message of variant is format string of display(...), or description(...) if display is not set. Arguments of display are ignored,
+///  the format string must use fields in order of declaration, like in define_error!().
+///* Attributes of links(like #[cfg(unix)]) are ignored.
+///
+#[macro_export]
+macro_rules! define_error_chain{
+    (
+        types { $error_name:ident $(, $error_kind:ident, $result_ext:ident, $result:ident )* $(;)* }
+
+        $(
+            links {
+                $( $link_name:ident ( $link_error:path $(, $link_kind:path )* ) $( #[$link_meta:meta] )* ; )*
+            }
+        )*
+
+        $(
+            foreign_links {
+                $( $foreign_name:ident ( $foreign_error:path ) $( #[$foreign_meta:meta] )* ; )*
+            }
+        )*
+
+        $(
+            errors {
+                $(
+                    $var_name:ident $( ( $( $field_name:ident : $field_type:ty ),* ) )* {
+                        $( description( $description:expr ) )*
+                        $( display( $display:expr $(, $display_arg:expr )* ) )*
+                    }
+                )*
+            }
+        )*
+    ) => {
+        $crate::define_error!( $error_name,
+            $( $( $link_name(error:Box<$link_error>) => "{}", )* )*
+            $( $( $foreign_name(error:Box<$foreign_error>) => "{}", )* )*
+            $( $(
+                $var_name( $( $( $field_name : $field_type ),* )* ) =>
+                    $crate::__nes_chain_message!([ $( $display )* ] [ $( $description )* ] $var_name),
+            )* )*
+        );
+
+        $( $( $crate::impl_from_error!($link_error => $error_name::$link_name); )* )*
+        $( $( $crate::impl_from_error!($foreign_error => $error_name::$foreign_name); )* )*
+
+        $( pub type $result<T> = ::std::result::Result<T, $error_name>; )*
+    };
+
+    ( links $( $rest:tt )* ) => {
+        $crate::define_error_chain!{ types { Error, ErrorKind, ResultExt, Result; } links $( $rest )* }
+    };
+    ( foreign_links $( $rest:tt )* ) => {
+        $crate::define_error_chain!{ types { Error, ErrorKind, ResultExt, Result; } foreign_links $( $rest )* }
+    };
+    ( errors $( $rest:tt )* ) => {
+        $crate::define_error_chain!{ types { Error, ErrorKind, ResultExt, Result; } errors $( $rest )* }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_chain_message{
+    ( [ $display:expr ] [ $( $description:expr )* ] $var_name:ident ) => { $display };
+    ( [] [ $description:expr ] $var_name:ident ) => { $description };
+    ( [] [] $var_name:ident ) => { stringify!($var_name) };
+}
+
+///This macro generates error that gets information, where the error has been occurred. You should return it.
+///Error is created by `#[cold]` function, that is not inlined, so the happy path of hot loops stays small.
+///
+/// # Example
+///
+/// ```ignore
+///let file_name=match args.next() {
+///    Some( file_name ) => file_name,
+///    None => return err!(CommonError::NoArguments),
+///};
+///
+///if !file_name.ends_with(".rs") {
+///    return err!(CommonError::IncorrectExtension, file_name, ".rs")
+///}
+/// ```
+///
+///String literals may be passed to fields of type `Cow<'static, str>` without allocation, and to fields of type String.
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///use std::borrow::Cow;
+///
+///define_error!( ParseError,
+///    UnexpectedToken(token:Cow<'static, str>, line:usize) => "unexpected token \"{}\" at line {}"
+///);
+///
+///fn parse(text:&str) -> result![ParseError] {
+///    if text.starts_with(';') {
+///        return err!(ParseError::UnexpectedToken, ";", 1);
+///    }
+///
+///    match text.split_whitespace().next() {
+///        Some( "let" ) | None => ok!(),
+///        Some( token ) => err!(ParseError::UnexpectedToken, token.to_string(), 1),
+///    }
+///}
+/// # fn main() { assert!(parse(";").is_err()); assert!(parse("fn").is_err()); }
+/// ```
+///
+///Other arguments are passed to fields as is, so they are coerced like arguments of function.
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///trait Shape: std::fmt::Debug {}
+///
+///#[derive(Debug)]
+///struct Square;
+///
+///impl Shape for Square {}
+///
+///define_error!( CoerceError,
+///    Slice(bytes:Box<[u8]>) => "bytes {:?}",
+///    Dyn(shape:Box<dyn Shape>) => "shape {:?}",
+///    Borrowed(items:&'static [u32]) => "items {:?}",
+///    Message(text:String) => "message {}"
+///);
+///
+///static ITEMS:Vec<u32>=Vec::new();
+///
+///fn coerce(case:u8) -> result![CoerceError] {
+///    match case {
+///        0 => err!(CoerceError::Slice, Box::new([1u8, 2, 3])),
+///        1 => err!(CoerceError::Dyn, Box::new(Square)),
+///        2 => err!(CoerceError::Borrowed, &ITEMS),
+///        _ => err!(CoerceError::Message, "text".into()),
+///    }
+///}
+/// # fn main() {
+///assert!(coerce(0).unwrap_err().to_string().ends_with("bytes [1, 2, 3]"));
+///assert!(coerce(1).unwrap_err().to_string().ends_with("shape Square"));
+///assert!(coerce(2).unwrap_err().to_string().ends_with("items []"));
+///assert!(coerce(3).unwrap_err().to_string().ends_with("message text"));
+///let error:CoerceError=create_err!(CoerceError::Slice, Box::new([4u8]));
+///assert!(error.to_string().ends_with("bytes [4]"));
+/// # }
+/// ```
+///
+///Count of fields is not limited.
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///define_error!( WideError,
+///    Many(a:u8, b:u8, c:u8, d:u8, e:u8, f:u8, g:u8, h:u8, i:u8, j:u8, k:u8, l:u8, m:u8) => "{} {} {} {} {} {} {} {} {} {} {} {} {}"
+///);
+///
+///fn wide() -> result![WideError] {
+///    err!(WideError::Many, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13)
+///}
+/// # fn main() { assert!(wide().unwrap_err().to_string().ends_with("1 2 3 4 5 6 7 8 9 10 11 12 13")); }
+/// ```
+///
+#[macro_export]
+macro_rules! err{
+    ( $error:path ) => {
+        Err(
+            $crate::__nes_construct!( cold $error, [], [], )
+        )
+    };
+    ( $error:path, $( $arg:expr ),* ) => {
+        Err(
+            $crate::__nes_construct!( cold $error, [], [], $( $arg ),* )
+        )
+    };
+}
+
+///This macro creates error that gets information, where the error has been occurred. You can insert it into other error.
+///
+/// # Example
+///
+/// ```ignore
+///let error=Box::new(create_err!(handler::Error::BrockenChannel));
+///return err!(Error::HandlerThreadCrash, error, ThreadSource::Handler);
+/// ```
+///
+#[macro_export]
+macro_rules! create_err{
+    ( $error:path ) => {
+        $error( $crate::error_info!() )
+    };
+    ( $error:path, $( $arg:expr ),* ) => {
+        $crate::__nes_construct!( inline $error, [], [], $( $arg ),* )
+    };
+}
+
+///This macro creates warning like create_err!() and pushes it into `nes::warning::Warnings`, function continues.
+///
+/// # Example
+///
+/// ```ignore
+///if text.is_empty() {
+///    push_warning!(warnings, ImportWarning::EmptyRow, row);
+///}
+/// ```
+#[macro_export]
+macro_rules! push_warning{
+    ( $warnings:expr, $error:path ) => {
+        $warnings.push( $error( $crate::error_info!() ) )
+    };
+    ( $warnings:expr, $error:path, $( $arg:expr ),* ) => {
+        $warnings.push( $crate::__nes_construct!( inline $error, [], [], $( $arg ),* ) )
+    };
+}
+
+///This macro looks like standard try!() macro but it gets information where the error has been occurred.
+///
+///Note: if error, that you convert to other, contains ErrorInfo(is defined by define_error!() and is not like std::io::Error), you should use ?.
+///
+///Like err!(), it creates error by `#[cold]` function.
+///
+/// # Example
+///
+/// ```ignore
+///let file=try!( std::fs::File::open(file_name.as_str()), ReadFileError::ReadFileError, file_name );
+///
+///match try!( buf_reader.read_line(&mut line), ReadFileError::IOError ) { ... }
+/// ```
+///
+///`try` is reserved keyword since edition 2018, so this macro is also named try_err!(). In edition 2018 and later all macros
+///may be imported by use, like `use nes::{define_error, err, try_err, ok, result, ErrorInfo, ErrorInfoTrait};` or `use nes::prelude::*;`.
+///
+/// ```edition2018
+///use nes::{define_error, try_err, ok, result, ErrorInfo, ErrorInfoTrait};
+///
+///define_error!( ReadFileError,
+///    ReadFileError(io_error:Box<std::io::Error>, file:String ) => "Can not read file \"{2}\" : {1}"
+///);
+///
+///fn read_file(file_name:&str) -> result![String,ReadFileError] {
+///    let text=try_err!( std::fs::read_to_string(file_name), ReadFileError::ReadFileError, file_name.to_string() );
+///    ok!(text)
+///}
+///
+///fn main() {
+///    assert!(read_file("no_file.rs").is_err());
+///}
+/// ```
+///
+#[macro_export]
+macro_rules! try_err{
+    ( $o:expr, $error:path ) => {
+        match $o {
+            Ok( ok ) => ok,
+            Err(e) => {
+                return Err(
+                    $crate::__nes_construct!( cold $error, [e], [], )
+                )
+            }
+        }
+    };
+    ( $o:expr, $error:path, $( $arg:expr ),* ) => {
+        match $o {
+            Ok( ok ) => ok,
+            Err(e) => {
+                return Err(
+                    $crate::__nes_construct!( cold $error, [e], [], $( $arg ),* )
+                )
             }
+        }
+    };
+}
 
-            fn cause(&self) -> Option<&error> {
-                match *self {
-                    $(
-                        $error_name::$var_name(..) => self.$var_name()
-                    ),*
-                }
-            }
+///Same as try_err!(), it may be used in edition 2015.
+#[macro_export]
+macro_rules! try{
+    ( $( $arg:tt )* ) => {
+        $crate::try_err!( $( $arg )* )
+    };
+}
+
+///This macro wraps any error, that escapes the block, into ScopeError with the message and location of scope,
+///like `.context()` of anyhow, but for region of code instead of one call.
+///
+///The block is the body of closure, so `?` returns from the block and `return` returns from the block too.
+///Message is the format string, that may capture variables like `{path}`, it is formatted only if error occurs.
+///By default errors are converted into `Box<dyn Error + Send + Sync>`, type of error may be given by the second argument.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///use nes::scope::ScopeError;
+///
+///fn load_config(path:&str) -> Result<u16, ScopeError> {
+///    error_scope!("loading config {path}", {
+///        let text=std::fs::read_to_string(path)?;
+///        let port=text.trim().parse::<u16>()?;
+///        Ok(port)
+///    })
+///}
+///
+///fn read(path:&str) -> Result<String, ScopeError<std::io::Error>> {
+///    error_scope!("reading {path}", std::io::Error, {
+///        std::fs::read_to_string(path)
+///    })
+///}
+///
+/// # fn main() {
+///let error=load_config("no_file.conf").unwrap_err();
+///assert_eq!(error.message(), "loading config no_file.conf");
+/// # assert!(read("no_file.conf").is_err());
+/// # }
+/// ```
+///
+///Output of `println!("{}",error)`:
+///
+/// ```text
+///main/src/main.rs 5:5
+///loading config no_file.conf
+///No such file or directory (os error 2)
+/// ```
+#[macro_export]
+macro_rules! error_scope{
+    ( $message:literal, $body:block ) => {
+        $crate::error_scope!( $message, $crate::scope::BoxError, $body )
+    };
+    ( $message:literal, $error:ty, $body:block ) => {
+        match (|| -> ::std::result::Result<_, $error> { $body })() {
+            Ok( ok ) => Ok(ok),
+            Err(e) => Err($crate::scope::ScopeError::new(
+                <$crate::ErrorInfo as $crate::ErrorInfoTrait>::new($crate::__nes_file!(), line!(), column!()),
+                format!($message),
+                e
+            )),
         }
-*/
     };
+}
 
+///This macro maps any error, that escapes the block, into the variant with ErrorInfo of the macro and additional arguments,
+///like try!() for the whole region, so one wrapper covers many `?` instead of try!() around each call.
+///
+///Errors inside the block are converted by `?` into `Box<dyn Error + Send + Sync>`, it is passed to the first field of variant by From,
+///so the first field usually is `nes::DynError`. The block is the body of closure, like the body of error_scope!().
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///use nes::DynError;
+///
+///define_error!( ConfigError,
+///    LoadFailed(cause:DynError, path:String) => "can not load config {2}: {1}"
+///);
+///
+///fn load(path:&str) -> result![(String, u16),ConfigError] {
+///    with_error_context!({
+///        let text=std::fs::read_to_string(path)?;
+///        let mut lines=text.lines();
+///        let host=lines.next().ok_or("no host")?.to_string();
+///        let port=lines.next().ok_or("no port")?.parse::<u16>()?;
+///        Ok((host, port))
+///    }, ConfigError::LoadFailed, path.to_string())
+///}
+///
+/// # fn main() {
+///let error=load("no_file.conf").unwrap_err();
+///assert!(error.to_string().contains("can not load config no_file.conf: "));
+/// # }
+/// ```
+///
+#[macro_export]
+macro_rules! with_error_context{
+    ( $body:block, $error:path ) => {
+        match (|| -> ::std::result::Result<_, $crate::scope::BoxError> { $body })() {
+            Ok( ok ) => Ok(ok),
+            Err(e) => Err(
+                $crate::__nes_construct!( cold $error, [e], [], )
+            ),
+        }
+    };
+    ( $body:block, $error:path, $( $arg:expr ),* ) => {
+        match (|| -> ::std::result::Result<_, $crate::scope::BoxError> { $body })() {
+            Ok( ok ) => Ok(ok),
+            Err(e) => Err(
+                $crate::__nes_construct!( cold $error, [e], [], $( $arg ),* )
+            ),
+        }
+    };
 }
 
-///This macro implements From trait for other errors.
+///This macro runs the block and then fallible cleanup, if both fail, the error of cleanup is attached to the error of block,
+///see module nes::defer.
 ///
-///It allows you to convert other errors into current and write something like function(..)?.
-///Note, that you must use this macro only for errors, that have been defined with define_error!(). For errors like std::io::Error you must use try!() macro, because it gets information, where this error has been occurred.
+/// # Example
+///
+/// ```ignore
+///defer_err!(transaction.rollback() => {
+///    try!( transaction.insert(row), ImportError::Insert );
+///    ok!()
+///})
+/// ```
+///
+#[macro_export]
+macro_rules! defer_err{
+    ( $cleanup:expr => $body:block ) => {{
+        let result=(|| { $body })();
+        let error_info=<$crate::ErrorInfo as $crate::ErrorInfoTrait>::new($crate::__nes_file!(), line!(), column!());
+        $crate::defer::finish(result, $cleanup, error_info)
+    }};
+}
+
+///This macro emulates try-blocks on stable rust: err!(), try!(), mutex_lock!() and `?` inside the block return from the block,
+///not from the function, the block evaluates to Result. It is useful for cleanup sections and bodies of loops, that must not stop on error.
+///
+///The block is the body of closure, so `break` and `continue` of outer loop and `.await` can not be used in it.
 ///
 /// # Example
 ///
 /// ```
-///impl_from_error!(ReadFileError => CommonError);
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///define_error!( ImportError,
+///    BadRow(row:usize) => "bad row {}"
+///);
 ///
-///fn read_file(file_name:String) -> result![Vec<String>,ReadFileError] { ... }
+/// # fn main() {
+///let mut imported=Vec::new();
+///let mut errors=Vec::new();
 ///
-///fn process() -> result![CommonError] { //Or Result<CommonError>
-///    let lines=read_file(file_name)?;
-///    ...
+///for (row, text) in ["1", "x", "3"].iter().enumerate() {
+///    let result:result![ImportError]=try_block!{
+///        let value=match text.parse::<u32>() {
+///            Ok( value ) => value,
+///            Err( _ ) => return err!(ImportError::BadRow, row),
+///        };
+///
+///        imported.push(value);
+///        ok!()
+///    };
+///
+///    if let Err(error)=result {
+///        errors.push(error);
+///    }
 ///}
+///
+///assert_eq!(imported, vec![1, 3]);
+///assert_eq!(errors.len(), 1);
+/// # }
 /// ```
 ///
-/// You will get error like:
+#[macro_export]
+macro_rules! try_block{
+    ( $( $body:tt )* ) => {
+        (|| { $( $body )* })()
+    };
+}
+
+///This macro creates `nes::lazy::LazyDisplay`, that moves arguments into closure and formats them, only when the error is displayed.
 ///
-/// ```text
-///example/examples/example.rs 16:0   //line, where impl_from_error!() is.
-///read file error example/examples/example.rs 51:13    //line where the error has been occurred
-///Can not read file "no_file.rs" : No such file or directory (os error 2)    //description of error
+/// # Example
+///
+/// ```ignore
+///return err!(PacketError::UnknownPacket, lazy_format!("{:02x?}", packet));
 /// ```
 ///
-///Where is second form
+#[macro_export]
+macro_rules! lazy_format{
+    ( $format:literal $(, $arg:expr )* $(,)* ) => {
+        $crate::lazy::LazyDisplay::new( move |f:&mut ::std::fmt::Formatter| write!(f, $format $(, $arg )* ) )
+    };
+}
+
+///This macro runs closure with the error and its location, if result is error, and returns the result unchanged.
+///It is useful for metrics and debug prints in the middle of chain. Location is location of the error, if it is defined by define_error!(),
+///else it is location of the call.
 ///
 /// # Example
 ///
-/// ```rust
-///impl_from_error!(::module::ReadFileError => CommonError::CanNotReadFile);
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///use std::sync::atomic::{AtomicUsize,Ordering};
+///
+///static FAILURES:AtomicUsize=AtomicUsize::new(0);
+///
+///define_error!( ReadFileError,
+///    IOError(io_error:Box<std::io::Error>) => "IO Error: {}"
+///);
+///
+///fn read(file:&str) -> result![String,ReadFileError] {
+///    let text=inspect_err!(std::fs::read_to_string(file), |error, location| {
+///        FAILURES.fetch_add(1, Ordering::Relaxed);
+///        println!("{} at {}", error.kind(), location);
+///    });
+///
+///    ok!(try!(text, ReadFileError::IOError))
+///}
+///
+///fn process() -> result![String,ReadFileError] {
+///    inspect_err!(read("no_file.rs"), |error, _| println!("{}", error))
+///}
+///
+/// # fn main() {
+///assert!(process().is_err());
+///assert_eq!(FAILURES.load(Ordering::Relaxed), 1);
+/// # }
 /// ```
 ///
+#[macro_export]
+macro_rules! inspect_err{
+    ( $result:expr, $inspect:expr ) => {
+        match $result {
+            Err( error ) => {
+                {
+                    #[allow(unused_imports)]
+                    use $crate::__private::{LocationViaNes,LocationViaNone};
 
+                    let location=(&$crate::__private::LocationField(&error)).nes_location().unwrap_or_else(||
+                        <$crate::ErrorInfo as $crate::ErrorInfoTrait>::new($crate::__nes_file!(), line!(), column!())
+                    );
 
+                    $crate::__private::inspect(&error, &location, $inspect);
+                }
+
+                Err(error)
+            },
+            result => result,
+        }
+    };
+}
+
+///This macro evaluates the result, writes the error with the location of the call and discards it, it returns `Option` of the value.
+///It is useful for shutdown and best-effort cleanup, where the error can not be returned.
+///With feature "tracing" error is written by `tracing::error!`, else it is written to stderr. Message is format string like in println!().
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+///fn shutdown() {
+///    log_err!(std::fs::remove_file("server.pid"));
+///    log_err!(std::fs::remove_dir("cache"), "can not remove cache of {}", "server");
+///
+///    let removed:Option<()>=log_err!(std::fs::remove_file("server.lock"));
+///    assert!(removed.is_none());
+///}
+/// # fn main() { shutdown(); }
+/// ```
+///
 #[macro_export]
-macro_rules! impl_from_error{
-    ( $from_error:ident => $to_error:ident ) => {
-        impl From<$from_error> for $to_error {
-            fn from(from_error:$from_error) -> Self {
-                $to_error::$from_error(error_info!(),Box::new(from_error))
-            }
+macro_rules! log_err{
+    ( $result:expr ) => {
+        match $result {
+            Ok( ok ) => Some(ok),
+            Err( ref error ) => {
+                $crate::__private::log_error(error, None, $crate::__nes_file!(), line!(), column!());
+                None
+            },
         }
     };
-    ( $from_error:path => $to_error:ident :: $to_variant:ident ) => {
-        impl From<$from_error> for $to_error {
-            fn from(from_error:$from_error) -> Self {
-                $to_error::$to_variant(error_info!(),Box::new(from_error))
-            }
+    ( $result:expr, $( $message:tt )+ ) => {
+        match $result {
+            Ok( ok ) => Some(ok),
+            Err( ref error ) => {
+                $crate::__private::log_error(error, Some(format_args!( $( $message )+ )), $crate::__nes_file!(), line!(), column!());
+                None
+            },
         }
     };
 }
 
-///This macro generates error that gets information, where the error has been occurred. You should return it.
+///This macro asserts, that result is error, that matches the pattern with optional guard. Otherwise it panics with the pattern,
+///Debug of actual error and its trail with locations, if the error is defined by define_error!().
 ///
 /// # Example
 ///
 /// ```
-///let file_name=match args.next() {
-///    Some( file_name ) => file_name,
-///    None => return err!(CommonError::NoArguments),
-///};
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///define_error!( CommonError,
+///    NoArguments() => "no arguments",
+///    IncorrectExtension(file_name:String, extension:String) => "Expected extension \"{2}\" for file \"{1}\""
+///);
 ///
-///if !file_name.ends_with(".rs") {
-///    return err!(CommonError::IncorrectExtension, file_name, ".rs".to_string())
+///fn check(file_name:&str) -> result![CommonError] {
+///    if !file_name.ends_with(".rs") {
+///        return err!(CommonError::IncorrectExtension, file_name.to_string(), ".rs");
+///    }
+///
+///    ok!()
 ///}
+///
+/// # fn main() {
+///assert_err!(check("main.c"), CommonError::IncorrectExtension(_, _, ext) if ext == ".rs");
+///assert_err!(check("main.c"), CommonError::NoArguments(..) | CommonError::IncorrectExtension(..));
+/// # }
 /// ```
 ///
-
+///Output of `assert_err!(check("main.c"), CommonError::NoArguments(..))`:
+///
+/// ```text
+///assertion failed: error does not match `CommonError::NoArguments(..)`
+///actual error: main/src/main.rs 9:16
+///CommonError::IncorrectExtension file_name:"main.c" extension:".rs"
+///error trail:
+///    CommonError::IncorrectExtension main/src/main.rs 9:16
+/// ```
 #[macro_export]
-macro_rules! err{
-    ( $error:path ) => {
-        Err(
-            $error( error_info!() )
-        )
-    };
-    ( $error:path, $( $arg:expr ),* ) => {
-        Err(
-            $error( error_info!(), $( $arg, )* )
-        )
+macro_rules! assert_err{
+    ( $result:expr, $( $pattern:pat )|+ $( if $guard:expr )* $(,)* ) => {
+        match $result {
+            Err( $( $pattern )|+ ) $( if $guard )* => {},
+            Err( ref error ) => {
+                #[allow(unused_imports)]
+                use $crate::__private::{TrailViaNes,TrailViaNone};
+
+                let mut trail=Vec::new();
+                (&$crate::__private::TrailField(error)).push_trail(&mut trail);
+                $crate::__private::assert_err_failed(stringify!($( $pattern )|+ $( if $guard )*), error, &trail)
+            },
+            Ok( _ ) => panic!("assertion failed: expected error, that matches `{}`, got Ok", stringify!($( $pattern )|+ $( if $guard )*)),
+        }
     };
 }
 
-///This macro creates error that gets information, where the error has been occurred. You can insert it into other error.
+///This macro asserts, that result is error, and error of given type, that matches the pattern with optional guard, is in the chain of sources.
+///Nested errors may be stored in Box, Arc, InlineBox or PoolBox. Otherwise it panics with all errors of the chain.
 ///
 /// # Example
 ///
 /// ```
-///let error=Box::new(create_err!(handler::Error::BrockenChannel));
-///return err!(Error::HandlerThreadCrash, error, ThreadSource::Handler);
-/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///define_error!( ReadFileError,
+///    ReadFileError(io_error:Box<std::io::Error>, file:String ) => "Can not read file \"{2}\" : {1}"
+///);
 ///
-
-#[macro_export]
-macro_rules! create_err{
-    ( $error:path ) => {
-        $error( error_info!() )
-    };
-    ( $error:path, $( $arg:expr ),* ) => {
-        $error( error_info!(), $( $arg, )* )
-    };
-}
-
-///This macro looks like standard try!() macro but it gets information where the error has been occurred.
+///define_error!( CommonError,
+///    ReadFileError(read_file_error:Box<ReadFileError>) => "read file error {}"
+///);
 ///
-///Note: if error, that you convert to other, contains ErrorInfo(is defined by define_error!() and is not like std::io::Error), you should use ?.
+///impl_from_error!(ReadFileError => CommonError);
 ///
-/// # Example
+///fn read_file(file:&str) -> result![String,ReadFileError] {
+///    ok!(try!(std::fs::read_to_string(file), ReadFileError::ReadFileError, file.to_string()))
+///}
 ///
-/// ```
-///let file=try!( std::fs::File::open(file_name.as_str()), ReadFileError::ReadFileError, file_name );
+///fn process() -> result![CommonError] {
+///    read_file("no_file.rs")?;
+///    ok!()
+///}
 ///
-///match try!( buf_reader.read_line(&mut line), ReadFileError::IOError ) { ... }
+/// # fn main() {
+///assert_err_chain!(process(), std::io::Error => error if error.kind()==std::io::ErrorKind::NotFound);
+///assert_err_chain!(process(), ReadFileError => ReadFileError::ReadFileError(_, _, file) if file=="no_file.rs");
+///assert_err_chain!(process(), ReadFileError);
+/// # }
 /// ```
-///
-
 #[macro_export]
-macro_rules! try{
-    ( $o:expr, $error:path ) => {
-        match $o {
-            Ok( ok ) => ok,
-            Err(e) => {
-                return Err(
-                    $error( error_info!(), Box::new(e) )
-                )
-            }
+macro_rules! assert_err_chain{
+    ( $result:expr, $error:ty => $( $pattern:pat )|+ $( if $guard:expr )* $(,)* ) => {
+        match $result {
+            Err( ref error ) => {
+                let found=$crate::__private::chain_contains::<$error, _>(error, |value:&$error| match value {
+                    $( $pattern )|+ $( if $guard )* => true,
+                    _ => false,
+                });
+
+                if !found {
+                    $crate::__private::assert_err_chain_failed(stringify!($error => $( $pattern )|+ $( if $guard )*), error)
+                }
+            },
+            Ok( _ ) => panic!("assertion failed: expected error, that contains `{}`, got Ok", stringify!($error => $( $pattern )|+ $( if $guard )*)),
         }
     };
-    ( $o:expr, $error:path, $( $arg:expr ),* ) => {
-        match $o {
-            Ok( ok ) => ok,
-            Err(e) => {
-                return Err(
-                    $error( error_info!(), Box::new(e), $( $arg, )* )
-                )
-            }
-        }
+    ( $result:expr, $error:ty $(,)* ) => {
+        $crate::assert_err_chain!( $result, $error => _ )
     };
 }
 
@@ -366,20 +3087,49 @@ macro_rules! try{
 ///
 /// # Example
 ///
-/// ```
+/// ```ignore
 ///fn process() -> result![CommonError] { ... }
 ///
 ///fn read_file(file_name:String) -> result![Vec<String>,ReadFileError] { ... }
 /// ```
 ///
-
+///Ok type may be generic with commas, like `result![HashMap<String, u32>, ConfigError]`. Result is always `std::result::Result`,
+///even if other Result is imported. The third form selects ErrorInfo of generic error: `result![Ok, Error, Info]` is `Result<Ok, Error<Info>>`.
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///use std::collections::HashMap;
+///
+///define_error!( ConfigError,
+///    NoConfig() => "config is not found"
+///);
+///
+///type Result<T> = std::result::Result<T, String>;
+///
+///fn load() -> result![HashMap<String, u32>, ConfigError] {
+///    ok!(HashMap::new())
+///}
+///
+///pub struct InfoError<I>(I);
+///
+///fn check() -> result![u32, InfoError, nes::host::ErrorInfo] {
+///    Err(InfoError(nes::caller_error_info()))
+///}
+///
+/// # fn main() { let _:Result<()>=Ok(()); assert!(load().is_ok()); assert!(check().is_err()); }
+/// ```
+///
 #[macro_export]
 macro_rules! result{
     [ $error:ty ] => {
-        Result<(), $error>
+        ::std::result::Result<(), $error>
     };
     [ $ok:ty , $error:ty ] => {
-        Result<$ok, $error>
+        ::std::result::Result<$ok, $error>
+    };
+    [ $ok:ty , $( $error:ident )::+ , $info:ty ] => {
+        ::std::result::Result<$ok, $( $error )::+ <$info> >
     };
 }
 
@@ -387,31 +3137,129 @@ macro_rules! result{
 ///
 /// # Example
 ///
-/// ```
+/// ```ignore
 ///ok!() // instead Ok(())
 ///
 ///ok!(lines) // instead Ok(lines)
+///
+///ok!(lines, count) // instead Ok((lines, count))
+/// ```
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+///fn split(text:&str) -> Result<(String, String), ()> {
+///    match text.find('=') {
+///        Some( pos ) => ok!(text[..pos].to_string(), text[pos+1..].to_string()),
+///        None => Err(()),
+///    }
+///}
+///
+///fn unit() -> Result<(), ()> { ok!() }
+///fn single() -> Result<u32, ()> { ok!(1) }
+///fn triple() -> Result<(u8, bool, char), ()> { ok!(1, true, 'c',) }
+///
+/// # fn main() {
+///assert_eq!(split("port=80"), Ok(("port".to_string(), "80".to_string())));
+///assert_eq!(unit(), Ok(()));
+///assert_eq!(single(), Ok(1));
+///assert_eq!(triple(), Ok((1, true, 'c')));
+/// # }
 /// ```
 ///
-
 #[macro_export]
 macro_rules! ok{
     () => {
         Ok(())
     };
-    ($( $x:expr ),* ) => {
-        Ok( $( $x, )* )
-    }
+    ( $x:expr $(,)* ) => {
+        Ok( $x )
+    };
+    ( $( $x:expr ),+ $(,)* ) => {
+        Ok( ( $( $x ),+ ) )
+    };
+}
+
+#[cfg(feature = "privacy")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_file{
+    () => {{
+        #[cfg(not(debug_assertions))]
+        const FILE:&str = {
+            const HASH:[u8;16] = $crate::privacy::file_hash(file!());
+            $crate::privacy::hash_str(&HASH)
+        };
+        #[cfg(debug_assertions)]
+        const FILE:&str = concat!(module_path!(),"/",file!());
+
+        FILE
+    }};
+}
+
+#[cfg(not(feature = "privacy"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_file{
+    () => {
+        concat!(module_path!(),"/",file!())
+    };
 }
 
 ///This macro returns file,line,column, where an error has been occurred
 ///
-
+///`error_info!(static)` returns `&'static ErrorInfo`, that is evaluated at compile time by `ErrorInfo::new_const`, so it costs nothing at runtime.
+///It needs ErrorInfo with const fn new_const, like standard ErrorInfo, ids of tracing span and trace are not captured.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+///use nes::{ErrorInfo,ErrorInfoTrait};
+///
+///fn location() -> &'static ErrorInfo {
+///    error_info!(static)
+///}
+///
+/// # fn main() {
+///assert!(location().file().ends_with(".rs"));
+///assert!(std::ptr::eq(location(), location()));
+/// # }
+/// ```
+///
+///`error_info!(caller)` is backed by `std::panic::Location::caller()`, so in helper functions with `#[track_caller]`
+///it returns location of the caller of the helper instead of the line of the helper itself. File is not prefixed by module path.
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+///use nes::{ErrorInfo,ErrorInfoTrait};
+///
+///define_error!( ConfigError,
+///    MissingKey(key:String) => "key \"{}\" is missing"
+///);
+///
+///#[track_caller]
+///fn missing_key(key:&str) -> ConfigError {
+///    ConfigError::MissingKey(error_info!(caller), key.to_string())
+///}
+///
+/// # fn main() {
+///let line=line!(); let mut error=missing_key("port");
+///assert_eq!(error.get_error_info().line(), line);
+/// # }
+/// ```
+///
 #[macro_export]
 macro_rules! error_info {
     () => {
-        ErrorInfo::new(concat!(module_path!(),"/",file!()), line!(), column!())
+        ErrorInfo::new($crate::__nes_file!(), line!(), column!())
     };
+    (caller) => {
+        $crate::caller_error_info::<ErrorInfo>()
+    };
+    (static) => {{
+        static ERROR_INFO:ErrorInfo = ErrorInfo::new_const($crate::__nes_file!(), line!(), column!());
+        &ERROR_INFO
+    }};
 }
 
 
@@ -427,38 +3275,38 @@ macro_rules! error_info {
 ///
 ///`let guard=mutex_lock(mutex,ErrorName::Variant,arg1,arg2,...)` returns "ErrorName::Variant(arg1,arg2,...)"
 ///
-
+#[cfg(feature = "sync-macros")]
 #[macro_export]
 macro_rules! mutex_lock{
     ( $mutex:expr ) => {
         match $mutex.lock() {
             Ok(guard) => guard,
-            Err(_) => return err!(Error::Poisoned),
+            Err(_) => return $crate::err!(Error::Poisoned),
         }
     };
     ( $mutex:expr, $error:ident ) => {
         match $mutex.lock() {
             Ok(guard) => guard,
-            Err(_) => return err!($error::Poisoned),
+            Err(_) => return $crate::err!($error::Poisoned),
         }
     };
     ( $mutex:expr, $error:path ) => {
         match $mutex.lock() {
             Ok(guard) => guard,
-            Err(_) => return err!($error),
+            Err(_) => return $crate::err!($error),
         }
     };
     ( $mutex:expr, $error:path, $( $arg:expr ),* ) => {
         match $mutex.lock() {
             Ok(guard) => guard,
-            Err(_) => return Err( $error( error_info!(), $( $arg, )* ) ),
+            Err(_) => return Err( $error( $crate::error_info!(), $( $arg, )* ) ),
         }
     };
 
     ( $mutex:expr => $var:ident) => {
         let mut guard=match $mutex.lock() {
             Ok(guard) => guard,
-            Err(_) => return err!(Error::Poisoned),
+            Err(_) => return $crate::err!(Error::Poisoned),
         };
 
         let $var=guard.deref_mut();
@@ -466,7 +3314,7 @@ macro_rules! mutex_lock{
     ( $mutex:expr => $var:ident, $error:ident ) => {
         let mut guard=match $mutex.lock() {
             Ok(guard) => guard,
-            Err(_) => return err!($error::Poisoned),
+            Err(_) => return $crate::err!($error::Poisoned),
         };
 
         let $var=guard.deref_mut();
@@ -474,7 +3322,7 @@ macro_rules! mutex_lock{
     ( $mutex:expr => $var:ident, $error:path ) => {
         let mut guard=match $mutex.lock() {
             Ok(guard) => guard,
-            Err(_) => return err!($error),
+            Err(_) => return $crate::err!($error),
         };
 
         let $var=guard.deref_mut();
@@ -482,7 +3330,7 @@ macro_rules! mutex_lock{
     ( $mutex:expr => $var:ident, $error:path, $( $arg:expr ),* ) => {
         let mut guard=match $mutex.lock() {
             Ok(guard) => guard,
-            Err(_) => return Err( $error( error_info!(), $( $arg, )* ) ),
+            Err(_) => return Err( $error( $crate::error_info!(), $( $arg, )* ) ),
         };
 
         let $var=guard.deref_mut();
@@ -501,31 +3349,31 @@ macro_rules! mutex_lock{
 ///
 ///`let guard=rw_write(rw_lock,ErrorName::Variant,arg1,arg2,...)` returns "ErrorName::Variant(arg1,arg2,...)"
 ///
-
+#[cfg(feature = "sync-macros")]
 #[macro_export]
 macro_rules! rw_write{
     ( $rw:expr ) => {
         match $rw.write() {
             Ok(guard) => guard,
-            Err(_) => return err!(Error::Poisoned),
+            Err(_) => return $crate::err!(Error::Poisoned),
         }
     };
     ( $rw:expr, $error:ident ) => {
         match $rw.write() {
             Ok(guard) => guard,
-            Err(_) => return err!($error::Poisoned),
+            Err(_) => return $crate::err!($error::Poisoned),
         }
     };
     ( $rw:expr, $error:path ) => {
         match $rw.write() {
             Ok(guard) => guard,
-            Err(_) => return err!($error),
+            Err(_) => return $crate::err!($error),
         }
     };
     ( $rw:expr, $error:path, $( $arg:expr ),* ) => {
         match $rw.write() {
             Ok(guard) => guard,
-            Err(_) => return Err( $error( error_info!(), $( $arg, )* ) ),
+            Err(_) => return Err( $error( $crate::error_info!(), $( $arg, )* ) ),
         }
     };
 }
@@ -542,31 +3390,31 @@ macro_rules! rw_write{
 ///
 ///`let guard=rw_read(rw_lock,ErrorName::Variant,arg1,arg2,...)` returns "ErrorName::Variant(arg1,arg2,...)"
 ///
-
+#[cfg(feature = "sync-macros")]
 #[macro_export]
 macro_rules! rw_read{
     ( $rw:expr ) => {
         match $rw.read() {
             Ok(guard) => guard,
-            Err(_) => return err!(Error::Poisoned),
+            Err(_) => return $crate::err!(Error::Poisoned),
         }
     };
     ( $rw:expr, $error:ident ) => {
         match $rw.read() {
             Ok(guard) => guard,
-            Err(_) => return err!($error::Poisoned),
+            Err(_) => return $crate::err!($error::Poisoned),
         }
     };
     ( $rw:expr, $error:path ) => {
         match $rw.read() {
             Ok(guard) => guard,
-            Err(_) => return err!($error),
+            Err(_) => return $crate::err!($error),
         }
     };
     ( $rw:expr, $error:path, $( $arg:expr ),* ) => {
         match $rw.read() {
             Ok(guard) => guard,
-            Err(_) => return Err( $error( error_info!(), $( $arg, )* ) ),
+            Err(_) => return Err( $error( $crate::error_info!(), $( $arg, )* ) ),
         }
     };
 }
@@ -583,27 +3431,286 @@ macro_rules! rw_read{
 ///
 ///`channel_send(channel,message,ErrorName::Variant,arg1,arg2,...)` returns "ErrorName::Variant(arg1,arg2,...)"
 ///
-
+#[cfg(feature = "channel-macros")]
 #[macro_export]
 macro_rules! channel_send{
     ( $channel:expr, $message:expr ) => {
         if $channel.send( $message ).is_err() {
-            return err!(Error::BrockenChannel)
+            return $crate::err!(Error::BrockenChannel)
         }
     };
     ( $channel:expr, $message:expr, $error:ident ) => {
         if $channel.send( $message ).is_err() {
-            return err!($error::BrockenChannel)
+            return $crate::err!($error::BrockenChannel)
         }
     };
     ( $channel:expr, $message:expr, $error:path ) => {
         if $channel.send( $message ).is_err() {
-            return err!($error)
+            return $crate::err!($error)
         }
     };
     ( $channel:expr, $message:expr, $error:path , $( $arg:expr ),* ) => {
         if $channel.send( $message ).is_err() {
-            return Err( $error( error_info!(), $( $arg, )* ) )
+            return Err( $error( $crate::error_info!(), $( $arg, )* ) )
+        }
+    };
+}
+
+///This macro works like try!(), but it wraps the error of database driver into `nes::database::QueryFailure` with redacted statement
+///and name of connection, see module nes::database.
+///
+/// # Example
+///
+/// ```ignore
+///let rows=try_query!( connection.execute(sql, params), sql, "users-primary", DbError::Query );
+/// ```
+///
+#[cfg(feature = "database")]
+#[macro_export]
+macro_rules! try_query{
+    ( $o:expr, $statement:expr, $connection:expr, $error:path $(, $arg:expr )* ) => {
+        match $o {
+            Ok( ok ) => ok,
+            Err(e) => {
+                let failure=$crate::database::QueryFailure::new(::std::convert::AsRef::<str>::as_ref(&$statement), ::std::convert::AsRef::<str>::as_ref(&$connection), e);
+
+                return Err(
+                    $crate::__nes_construct!( cold $error, [failure], [], $( $arg ),* )
+                )
+            }
+        }
+    };
+}
+
+///This macro connects to the address by TcpStream::connect and returns the variant with std::io::Error and text of the address,
+///if connection fails, see module nes::net.
+///
+/// # Example
+///
+/// ```ignore
+///let stream=tcp_connect!(address, NetError::Connect);
+///let stream=tcp_connect!(address, NetError::ConnectPeer, peer_id);
+/// ```
+///
+#[macro_export]
+macro_rules! tcp_connect{
+    ( $address:expr, $error:path $(, $arg:expr )* ) => {
+        $crate::__nes_net!( ::std::net::TcpStream::connect, $address, $error $(, $arg )* )
+    };
+}
+
+///This macro binds TcpListener to the address and returns the variant with std::io::Error and text of the address, if it fails.
+#[macro_export]
+macro_rules! tcp_bind{
+    ( $address:expr, $error:path $(, $arg:expr )* ) => {
+        $crate::__nes_net!( ::std::net::TcpListener::bind, $address, $error $(, $arg )* )
+    };
+}
+
+///This macro binds UdpSocket to the address and returns the variant with std::io::Error and text of the address, if it fails.
+#[macro_export]
+macro_rules! udp_bind{
+    ( $address:expr, $error:path $(, $arg:expr )* ) => {
+        $crate::__nes_net!( ::std::net::UdpSocket::bind, $address, $error $(, $arg )* )
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __nes_net{
+    ( $call:path, $address:expr, $error:path $(, $arg:expr )* ) => {{
+        let address=$address;
+
+        match $call(&address) {
+            Ok( ok ) => ok,
+            Err(e) => {
+                return Err(
+                    $crate::__nes_construct!( cold $error, [e], [], $crate::net::Address::address(&address) $(, $arg )* )
+                )
+            }
+        }
+    }};
+}
+
+///Same as try!(), but it does not return, it evaluates to Result with the value or the error, so it may be used in closures,
+///iterator chains and async blocks, that are not the body of function.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///define_error!( ParseError,
+///    BadNumber(error:std::num::ParseIntError, text:String) => "bad number \"{2}\": {1}"
+///);
+///
+///fn parse(texts:&[&str]) -> Result<Vec<u32>, ParseError> {
+///    texts.iter().map(|text| try_res!( text.parse::<u32>(), ParseError::BadNumber, text.to_string() )).collect()
+///}
+///
+/// # fn main() {
+///assert_eq!(parse(&["1", "2"]).unwrap(), vec![1, 2]);
+///assert!(parse(&["1", "x"]).unwrap_err().to_string().ends_with("bad number \"x\": invalid digit found in string"));
+/// # }
+/// ```
+///
+#[macro_export]
+macro_rules! try_res{
+    ( $o:expr, $error:path ) => {
+        match $o {
+            Ok( ok ) => Ok(ok),
+            Err(e) => Err(
+                $crate::__nes_construct!( cold $error, [e], [], )
+            ),
+        }
+    };
+    ( $o:expr, $error:path, $( $arg:expr ),* ) => {
+        match $o {
+            Ok( ok ) => Ok(ok),
+            Err(e) => Err(
+                $crate::__nes_construct!( cold $error, [e], [], $( $arg ),* )
+            ),
+        }
+    };
+}
+
+///Same as mutex_lock!(), but it does not return, it evaluates to Result with the guard or the error.
+#[cfg(feature = "sync-macros")]
+#[macro_export]
+macro_rules! mutex_lock_res{
+    ( $mutex:expr ) => {
+        match $mutex.lock() {
+            Ok(guard) => Ok(guard),
+            Err(_) => $crate::err!(Error::Poisoned),
+        }
+    };
+    ( $mutex:expr, $error:ident ) => {
+        match $mutex.lock() {
+            Ok(guard) => Ok(guard),
+            Err(_) => $crate::err!($error::Poisoned),
+        }
+    };
+    ( $mutex:expr, $error:path ) => {
+        match $mutex.lock() {
+            Ok(guard) => Ok(guard),
+            Err(_) => $crate::err!($error),
+        }
+    };
+    ( $mutex:expr, $error:path, $( $arg:expr ),* ) => {
+        match $mutex.lock() {
+            Ok(guard) => Ok(guard),
+            Err(_) => Err( $error( $crate::error_info!(), $( $arg, )* ) ),
+        }
+    };
+}
+
+///Same as rw_write!(), but it does not return, it evaluates to Result with the guard or the error.
+#[cfg(feature = "sync-macros")]
+#[macro_export]
+macro_rules! rw_write_res{
+    ( $rw:expr ) => {
+        match $rw.write() {
+            Ok(guard) => Ok(guard),
+            Err(_) => $crate::err!(Error::Poisoned),
+        }
+    };
+    ( $rw:expr, $error:ident ) => {
+        match $rw.write() {
+            Ok(guard) => Ok(guard),
+            Err(_) => $crate::err!($error::Poisoned),
+        }
+    };
+    ( $rw:expr, $error:path ) => {
+        match $rw.write() {
+            Ok(guard) => Ok(guard),
+            Err(_) => $crate::err!($error),
+        }
+    };
+    ( $rw:expr, $error:path, $( $arg:expr ),* ) => {
+        match $rw.write() {
+            Ok(guard) => Ok(guard),
+            Err(_) => Err( $error( $crate::error_info!(), $( $arg, )* ) ),
+        }
+    };
+}
+
+///Same as rw_read!(), but it does not return, it evaluates to Result with the guard or the error.
+#[cfg(feature = "sync-macros")]
+#[macro_export]
+macro_rules! rw_read_res{
+    ( $rw:expr ) => {
+        match $rw.read() {
+            Ok(guard) => Ok(guard),
+            Err(_) => $crate::err!(Error::Poisoned),
+        }
+    };
+    ( $rw:expr, $error:ident ) => {
+        match $rw.read() {
+            Ok(guard) => Ok(guard),
+            Err(_) => $crate::err!($error::Poisoned),
+        }
+    };
+    ( $rw:expr, $error:path ) => {
+        match $rw.read() {
+            Ok(guard) => Ok(guard),
+            Err(_) => $crate::err!($error),
+        }
+    };
+    ( $rw:expr, $error:path, $( $arg:expr ),* ) => {
+        match $rw.read() {
+            Ok(guard) => Ok(guard),
+            Err(_) => Err( $error( $crate::error_info!(), $( $arg, )* ) ),
+        }
+    };
+}
+
+///Same as channel_send!(), but it does not return, it evaluates to `Result<(), Error>`.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///use std::sync::mpsc;
+///
+///define_error!( WorkerError,
+///    BrockenChannel() => "channel is brocken"
+///);
+///
+/// # fn main() {
+///let (sender, receiver)=mpsc::channel();
+///drop(receiver);
+///
+///let results:Vec<Result<(), WorkerError>>=(0..3).map(|task| channel_send_res!(sender, task, WorkerError)).collect();
+///assert!(results.iter().all(|result| result.is_err()));
+/// # }
+/// ```
+///
+#[cfg(feature = "channel-macros")]
+#[macro_export]
+macro_rules! channel_send_res{
+    ( $channel:expr, $message:expr ) => {
+        match $channel.send( $message ) {
+            Ok(()) => Ok(()),
+            Err(_) => $crate::err!(Error::BrockenChannel),
+        }
+    };
+    ( $channel:expr, $message:expr, $error:ident ) => {
+        match $channel.send( $message ) {
+            Ok(()) => Ok(()),
+            Err(_) => $crate::err!($error::BrockenChannel),
+        }
+    };
+    ( $channel:expr, $message:expr, $error:path ) => {
+        match $channel.send( $message ) {
+            Ok(()) => Ok(()),
+            Err(_) => $crate::err!($error),
+        }
+    };
+    ( $channel:expr, $message:expr, $error:path , $( $arg:expr ),* ) => {
+        match $channel.send( $message ) {
+            Ok(()) => Ok(()),
+            Err(_) => Err( $error( $crate::error_info!(), $( $arg, )* ) ),
         }
     };
 }
@@ -0,0 +1,85 @@
+//!Network helpers, that always write the address: "connection refused" without the address is useless in logs.
+//!
+//!`tcp_connect!(addr, NetError::Connect)`, `tcp_bind!(addr, NetError::Bind)` and `udp_bind!(addr, NetError::Bind)` call std::net
+//!and return the variant with std::io::Error and text of the address, like try!(), additional arguments are passed after them.
+//!Address may be any type, that implements `Address`: strings, SocketAddr and pairs of host and port.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!
+//!define_error!( NetError,
+//!    Connect(error:std::io::Error, address:String) => "can not connect to {2}: {1}",
+//!    Bind(error:std::io::Error, address:String) => "can not bind {2}: {1}"
+//!);
+//!
+//!fn connect(port:u16) -> result![std::net::TcpStream,NetError] {
+//!    let stream=tcp_connect!(("127.0.0.1", port), NetError::Connect);
+//!    ok!(stream)
+//!}
+//!
+//!fn main() {
+//!    //port of listener, that is closed, refuses connections
+//!    let port=std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+//!
+//!    let error=connect(port).unwrap_err();
+//!    assert!(error.to_string().contains(&format!("can not connect to 127.0.0.1:{}: ", port)));
+//!}
+//! ```
+
+use std::net::{IpAddr,Ipv4Addr,Ipv6Addr,SocketAddr,SocketAddrV4,SocketAddrV6};
+
+///Address, that is written in errors of net macros.
+pub trait Address {
+    fn address(&self) -> String;
+}
+
+impl<A:Address + ?Sized> Address for &A {
+    fn address(&self) -> String { (**self).address() }
+}
+
+impl Address for str {
+    fn address(&self) -> String { self.to_string() }
+}
+
+impl Address for String {
+    fn address(&self) -> String { self.clone() }
+}
+
+macro_rules! impl_address{
+    ( $( $address:ty ),* ) => {
+        $(
+            impl Address for $address {
+                fn address(&self) -> String { self.to_string() }
+            }
+        )*
+    };
+}
+
+impl_address!(SocketAddr, SocketAddrV4, SocketAddrV6);
+
+macro_rules! impl_host_port_address{
+    ( $( $host:ty ),* ) => {
+        $(
+            impl Address for ($host, u16) {
+                fn address(&self) -> String { format!("{}:{}", self.0, self.1) }
+            }
+        )*
+    };
+}
+
+impl_host_port_address!(&'static str, String, IpAddr, Ipv4Addr);
+
+impl Address for (Ipv6Addr, u16) {
+    fn address(&self) -> String { format!("[{}]:{}", self.0, self.1) }
+}
+
+impl Address for [SocketAddr] {
+    fn address(&self) -> String {
+        let addresses:Vec<String>=self.iter().map(|address| address.to_string()).collect();
+        addresses.join(", ")
+    }
+}
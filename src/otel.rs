@@ -0,0 +1,104 @@
+//!Exception events of OpenTelemetry on the active span(feature "otel").
+//!
+//!`OtelReporter` records each reported error as the exception event on the span, that is active at reporting,
+//!with attributes of OpenTelemetry semantic conventions:
+//!
+//! ```text
+//!exception.type = "LoadError::FetchFailed"
+//!exception.message = full chain of the error, like Display
+//!code.filepath = "app/src/api.rs"
+//!code.lineno = 42
+//! ```
+//!
+//!Event is written by tracing with target "nes::otel" and message "exception", so tracing-opentelemetry layer exports it
+//!as event of the span, other layers write it like any event.
+//!Errors are recorded only at reporting, not at creation, so the failure is recorded once, though each layer of try!()
+//!wraps it into new error. Errors, that are handled and are not reported, and errors, that are reported outside of spans,
+//!are not recorded, they may be recorded by `record_exception(&error)`.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!extern crate tracing;
+//!extern crate tracing_subscriber;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use nes::otel::OtelReporter;
+//!
+//!define_error!( LoadError,
+//!    FetchFailed(url:String) => "can not fetch {}"
+//!);
+//!
+//!fn fetch(url:&str) -> result![Vec<u8>,LoadError] {
+//!    err!(LoadError::FetchFailed, url.to_string())
+//!}
+//!
+//!fn main() {
+//!    tracing_subscriber::fmt().init();
+//!    nes::add_reporter(OtelReporter);
+//!
+//!    let _span=tracing::info_span!("request").entered();
+//!
+//!    if let Err( error )=fetch("/api/users") {
+//!        error.report();
+//!    }
+//!}
+//! ```
+
+use std::fmt;
+
+use super::__private::tracing::{self,Level};
+
+use {ErrorInfoTrait,Severity};
+use super::tracing::current_span_id;
+use reporter::{Reporter,ReportedError};
+
+///Target of exception events.
+pub const TARGET:&str = "nes::otel";
+
+///Records the exception event on the active span, it does nothing, if there is no active span.
+pub fn record_exception(error:&dyn ReportedError) {
+    if current_span_id().is_none() {
+        return;
+    }
+
+    let location=error.location();
+    let exception_type=ExceptionType(error);
+
+    match error.severity() {
+        Severity::Error => tracing::event!(
+            target:TARGET, Level::ERROR,
+            exception.type=%exception_type, exception.message=%error, code.filepath=location.file(), code.lineno=location.line(),
+            "exception"
+        ),
+        Severity::Warning => tracing::event!(
+            target:TARGET, Level::WARN,
+            exception.type=%exception_type, exception.message=%error, code.filepath=location.file(), code.lineno=location.line(),
+            "exception"
+        ),
+        Severity::Advice => tracing::event!(
+            target:TARGET, Level::INFO,
+            exception.type=%exception_type, exception.message=%error, code.filepath=location.file(), code.lineno=location.line(),
+            "exception"
+        ),
+    }
+}
+
+///Reporter, that records reported errors as exception events on the span, that is active at reporting.
+pub struct OtelReporter;
+
+impl Reporter for OtelReporter {
+    fn report(&self, error:&dyn ReportedError) {
+        record_exception(error);
+    }
+}
+
+//type of exception is the path of variant, like LoadError::FetchFailed
+struct ExceptionType<'a>(&'a dyn ReportedError);
+
+impl<'a> fmt::Display for ExceptionType<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}::{}", self.0.error_name(), self.0.variant_name())
+    }
+}
@@ -0,0 +1,172 @@
+//!Panic hook, that formats panics like nes errors.
+//!
+//!`nes::install_panic_hook()` replaces the default hook of std, panic is shown as location `file line:col` and message,
+//!like errors, that are defined by define_error!(), so output of crashes and errors is uniform.
+//!If reporters are registered by `nes::add_reporter()`, the panic is sent to them as `PanicError` and reporters are flushed,
+//!else it is written to stderr. Panic of the reporter itself is written to stderr.
+//!Names of files with panics are interned, at most `MAX_PANIC_FILES` names are kept, other panics have file `UNKNOWN_FILE`.
+//!
+//! # Example
+//!
+//! ```
+//!extern crate nes;
+//!use nes::ErrorMetadata;
+//!use nes::reporter::{Reporter,ReportedError};
+//!use std::sync::Mutex;
+//!
+//!static MESSAGES:Mutex<Vec<String>>=Mutex::new(Vec::new());
+//!
+//!struct MemoryReporter;
+//!
+//!impl Reporter for MemoryReporter {
+//!    fn report(&self, error:&dyn ReportedError) {
+//!        assert_eq!(error.error_name(), "Panic");
+//!        MESSAGES.lock().unwrap().push(error.to_string());
+//!    }
+//!}
+//!
+//!fn main() {
+//!    nes::install_panic_hook();
+//!    nes::add_reporter(MemoryReporter);
+//!
+//!    let result=std::panic::catch_unwind(|| panic!("no config"));
+//!    assert!(result.is_err());
+//!
+//!    let messages=MESSAGES.lock().unwrap();
+//!    assert!(messages[0].contains(".rs "));
+//!    assert!(messages[0].ends_with("panicked: no config"));
+//!}
+//! ```
+//!
+//!Output without reporters:
+//!
+//! ```text
+//!main/src/main.rs 14:5
+//!thread 'main' panicked: no config
+//! ```
+
+use std::fmt;
+use std::panic::{self,PanicHookInfo};
+use std::thread;
+
+use {ErrorInfo,ErrorInfoTrait,ErrorMetadata,ErrorTrail,Severity,TrailItem};
+use __private::Interner;
+#[cfg(feature = "reporters")]
+use reporter;
+
+///Max count of interned names of files with panics.
+pub const MAX_PANIC_FILES:usize = 1024;
+///Max length of interned name of file with panic in bytes.
+pub const MAX_PANIC_FILE_LENGTH:usize = 512;
+///Name of file, that is used, when location is unknown, the interner is full or name is too long.
+pub const UNKNOWN_FILE:&str = "<unknown>";
+
+static PANIC_FILES:Interner = Interner::new(MAX_PANIC_FILES, MAX_PANIC_FILE_LENGTH);
+
+///Panic, that is sent to reporters by the panic hook.
+#[derive(Clone)]
+pub struct PanicError {
+    error_info:ErrorInfo,
+    thread:String,
+    message:String
+}
+
+impl PanicError {
+    pub fn new(info:&PanicHookInfo) -> Self {
+        let error_info=match info.location() {
+            //file name is interned, because ErrorInfo keeps &'static str
+            Some( location ) => {
+                let file=PANIC_FILES.intern(location.file()).unwrap_or(UNKNOWN_FILE);
+
+                #[cfg(all(feature = "privacy", not(debug_assertions)))]
+                let file=::privacy::private_file(file);
+
+                ErrorInfo::new(file, location.line(), location.column())
+            },
+            None => ErrorInfo::new(UNKNOWN_FILE, 0, 0),
+        };
+
+        let payload=info.payload();
+        let message=match payload.downcast_ref::<&'static str>() {
+            Some( message ) => message.to_string(),
+            None => match payload.downcast_ref::<String>() {
+                Some( message ) => message.clone(),
+                None => "Box<dyn Any>".to_string(),
+            },
+        };
+
+        PanicError {
+            error_info,
+            thread:thread::current().name().unwrap_or("<unnamed>").to_string(),
+            message
+        }
+    }
+
+    pub fn thread(&self) -> &str {
+        &self.thread
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for PanicError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", self.error_info)?;
+        self.fmt_message(f)
+    }
+}
+
+impl fmt::Debug for PanicError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}\nPanic thread:{:?} message:{:?} ", self.error_info, self.thread, self.message)
+    }
+}
+
+impl std::error::Error for PanicError {}
+
+impl ErrorTrail for PanicError {
+    fn push_error_trail(&self, trail:&mut Vec<TrailItem>) {
+        trail.push(TrailItem {
+            error_name:"Panic",
+            variant_name:"Panic",
+            error_info:self.error_info
+        });
+    }
+}
+
+impl ErrorMetadata for PanicError {
+    fn error_name(&self) -> &'static str { "Panic" }
+    fn variant_name(&self) -> &'static str { "Panic" }
+    fn location(&self) -> ErrorInfo { self.error_info }
+    fn code(&self) -> Option<&'static str> { None }
+    fn help(&self) -> Option<&'static str> { None }
+    fn url(&self) -> Option<&'static str> { None }
+    fn severity(&self) -> Severity { Severity::Error }
+
+    fn fmt_message(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "thread '{}' panicked: {}", self.thread, self.message)
+    }
+}
+
+///Replaces panic hook with hook, that formats panics like errors and sends them to registered reporters.
+pub fn install_panic_hook() {
+    panic::set_hook(Box::new(|info| {
+        let error=PanicError::new(info);
+
+        #[cfg(feature = "reporters")]
+        {
+            //panic of reporter or of thread of BackgroundReporter is written to stderr, else reporters are called again
+            //under the lock of registry, and flush would wait for the error, that is reported by the panicked thread
+            if reporter::has_reporters() && !reporter::is_reporting() {
+                reporter::report(&error);
+                //panic of main thread exits the process, so queued errors are written now
+                reporter::flush_reporters(reporter::EXIT_FLUSH_TIMEOUT);
+                return;
+            }
+        }
+
+        eprintln!("{}", error);
+    }));
+}
@@ -0,0 +1,281 @@
+//!Pool of memory for boxed nested errors(feature "pool").
+//!
+//!Servers, that produce thousands of recoverable errors per second, allocate and free Box of nested error for each of them.
+//!`PoolBox<T>` is used instead of Box, its memory is taken from free list of current thread and is returned there, when it is dropped,
+//!so malloc is called only when free list is empty. Free lists are kept for sizes up to 512 bytes, at most 256 blocks of each size,
+//!larger values are allocated by global allocator.
+//!
+//!try!() and impl_from_error!() create the field by From, like Box.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use nes::pool::PoolBox;
+//!
+//!define_error!( ReadFileError,
+//!    IOError(io_error:Box<std::io::Error>, file:String) => "can not read file \"{2}\": {1}"
+//!);
+//!
+//!define_error!( RequestError,
+//!    ReadFileError(error:PoolBox<ReadFileError>) => "{}"
+//!);
+//!
+//!impl_from_error!(ReadFileError => RequestError);
+//!
+//!fn read(file:&str) -> result![String,ReadFileError] {
+//!    ok!(try!(std::fs::read_to_string(file), ReadFileError::IOError, file.to_string()))
+//!}
+//!
+//!fn handle(file:&str) -> result![usize,RequestError] {
+//!    ok!(read(file)?.len())
+//!}
+//!
+//!fn main() {
+//!    for _ in 0..1000 {
+//!        assert!(handle("no_file.rs").is_err()); //memory of PoolBox is reused
+//!    }
+//!}
+//! ```
+
+use std::alloc::{self,Layout};
+use std::any::{Any,TypeId};
+use std::cell::RefCell;
+use std::error::Error;
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::{Deref,DerefMut};
+use std::ptr::{self,NonNull};
+
+use {ErrorInfo,ErrorMetadata,ErrorTrail,Severity,TrailItem};
+use context::ContextItem;
+use section::Section;
+
+const BLOCK_SIZES:[usize;6] = [16, 32, 64, 128, 256, 512];
+const BLOCK_ALIGN:usize = 16;
+const MAX_FREE_BLOCKS:usize = 256;
+
+struct FreeLists([Vec<NonNull<u8>>;6]);
+
+impl Drop for FreeLists {
+    fn drop(&mut self) {
+        for (index, list) in self.0.iter_mut().enumerate() {
+            for block in list.drain(..) {
+                //SAFETY: block was allocated with this layout
+                unsafe { alloc::dealloc(block.as_ptr(), block_layout(index)) };
+            }
+        }
+    }
+}
+
+thread_local! {
+    static FREE_LISTS:RefCell<FreeLists> = RefCell::new(FreeLists(Default::default()));
+}
+
+fn block_layout(index:usize) -> Layout {
+    Layout::from_size_align(BLOCK_SIZES[index], BLOCK_ALIGN).unwrap()
+}
+
+//index of free list for the layout, None for values, that are allocated by global allocator
+fn block_index(layout:Layout) -> Option<usize> {
+    if layout.align()>BLOCK_ALIGN {
+        return None;
+    }
+
+    BLOCK_SIZES.iter().position(|size| layout.size()<=*size)
+}
+
+fn allocate(layout:Layout) -> NonNull<u8> {
+    let layout=match block_index(layout) {
+        Some( index ) => {
+            if let Ok(Some(block))=FREE_LISTS.try_with(|lists| lists.borrow_mut().0[index].pop()) {
+                return block;
+            }
+
+            block_layout(index)
+        },
+        None => layout,
+    };
+
+    //SAFETY: size of layout is not zero, PoolBox does not allocate zero sized values
+    match NonNull::new(unsafe { alloc::alloc(layout) }) {
+        Some( block ) => block,
+        None => alloc::handle_alloc_error(layout),
+    }
+}
+
+fn deallocate(block:NonNull<u8>, layout:Layout) {
+    let layout=match block_index(layout) {
+        Some( index ) => {
+            let pushed=FREE_LISTS.try_with(|lists| {
+                let list=&mut lists.borrow_mut().0[index];
+
+                if list.len()<MAX_FREE_BLOCKS {
+                    list.push(block);
+                    true
+                }else{
+                    false
+                }
+            });
+
+            if pushed==Ok(true) {
+                return;
+            }
+
+            block_layout(index)
+        },
+        None => layout,
+    };
+
+    //SAFETY: block was allocated with this layout
+    unsafe { alloc::dealloc(block.as_ptr(), layout) };
+}
+
+///Frees memory of free lists of current thread.
+pub fn clear() {
+    let _=FREE_LISTS.try_with(|lists| {
+        let mut lists=lists.borrow_mut();
+        drop(mem::replace(&mut *lists, FreeLists(Default::default())));
+    });
+}
+
+///Box, that takes memory from pool of current thread and returns it, when it is dropped.
+pub struct PoolBox<T> {
+    ptr:NonNull<T>,
+    marker:PhantomData<T>
+}
+
+impl<T> PoolBox<T> {
+    pub fn new(value:T) -> Self {
+        let layout=Layout::new::<T>();
+
+        let ptr=if layout.size()==0 {
+            NonNull::dangling()
+        }else{
+            allocate(layout).cast::<T>()
+        };
+
+        //SAFETY: ptr is allocated for T or T is zero sized
+        unsafe { ptr::write(ptr.as_ptr(), value) };
+
+        PoolBox {
+            ptr,
+            marker:PhantomData
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        let this=mem::ManuallyDrop::new(self);
+
+        //SAFETY: value is initialized and is not dropped, because this is ManuallyDrop
+        let value=unsafe { ptr::read(this.ptr.as_ptr()) };
+        this.free();
+        value
+    }
+
+    fn free(&self) {
+        let layout=Layout::new::<T>();
+
+        if layout.size()!=0 {
+            deallocate(self.ptr.cast::<u8>(), layout);
+        }
+    }
+}
+
+impl<T> Drop for PoolBox<T> {
+    fn drop(&mut self) {
+        //SAFETY: value is initialized and it is dropped once
+        unsafe { ptr::drop_in_place(self.ptr.as_ptr()) };
+        self.free();
+    }
+}
+
+//SAFETY: PoolBox owns T like Box, memory may be returned to pool of other thread
+unsafe impl<T:Send> Send for PoolBox<T> {}
+unsafe impl<T:Sync> Sync for PoolBox<T> {}
+
+impl<T> Deref for PoolBox<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        //SAFETY: value is initialized while PoolBox exists
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T> DerefMut for PoolBox<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        //SAFETY: value is initialized while PoolBox exists
+        unsafe { self.ptr.as_mut() }
+    }
+}
+
+impl<T> From<T> for PoolBox<T> {
+    fn from(value:T) -> Self {
+        PoolBox::new(value)
+    }
+}
+
+impl<T:Clone> Clone for PoolBox<T> {
+    fn clone(&self) -> Self {
+        PoolBox::new((**self).clone())
+    }
+}
+
+impl<T:PartialEq> PartialEq for PoolBox<T> {
+    fn eq(&self, other:&Self) -> bool {
+        **self==**other
+    }
+}
+
+impl<T:Eq> Eq for PoolBox<T> {}
+
+impl<T:fmt::Display> fmt::Display for PoolBox<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<T:fmt::Debug> fmt::Debug for PoolBox<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<T:Error> Error for PoolBox<T> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        (**self).source()
+    }
+}
+
+impl<T:ErrorTrail> ErrorTrail for PoolBox<T> {
+    fn push_error_trail(&self, trail:&mut Vec<TrailItem>) {
+        (**self).push_error_trail(trail)
+    }
+}
+
+impl<T:ErrorMetadata> ErrorMetadata for PoolBox<T> {
+    fn error_name(&self) -> &'static str { (**self).error_name() }
+    fn variant_name(&self) -> &'static str { (**self).variant_name() }
+    fn location(&self) -> ErrorInfo { (**self).location() }
+    fn code(&self) -> Option<&'static str> { (**self).code() }
+    fn help(&self) -> Option<&'static str> { (**self).help() }
+    fn url(&self) -> Option<&'static str> { (**self).url() }
+    fn severity(&self) -> Severity { (**self).severity() }
+    fn fmt_message(&self, f: &mut fmt::Formatter) -> fmt::Result { (**self).fmt_message(f) }
+    fn context(&self) -> &[ContextItem] { (**self).context() }
+    fn note(&self) -> Option<&'static str> { (**self).note() }
+    fn warning(&self) -> Option<&'static str> { (**self).warning() }
+    fn sections(&self) -> &[Section] { (**self).sections() }
+    fn typed_context(&self, type_id:TypeId) -> Option<&(dyn Any + Send + Sync)> { (**self).typed_context(type_id) }
+}
+
+#[cfg(feature = "serde")]
+impl<T:super::__private::serde::Serialize> super::__private::serde::Serialize for PoolBox<T> {
+    fn serialize<S:super::__private::serde::Serializer>(&self, serializer:S) -> Result<S::Ok, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
@@ -0,0 +1,41 @@
+//!Prelude, that imports ErrorInfo, traits and macros by one line.
+//!
+//!In edition 2018 and later `use nes::prelude::*;` replaces `#[macro_use] extern crate nes;` and `use nes::{ErrorInfo,ErrorInfoTrait};`.
+//!Own ErrorInfo may be used instead of standard one, items, that are declared or imported explicitly, shadow items of glob import.
+//!
+//! # Example
+//!
+//! ```edition2018
+//!use nes::prelude::*;
+//!
+//!pub enum ParseError {
+//!    Empty(ErrorInfo)
+//!}
+//!
+//!fn parse(text:&str) -> result![usize, ParseError] {
+//!    if text.is_empty() {
+//!        return err!(ParseError::Empty);
+//!    }
+//!
+//!    ok!(text.len())
+//!}
+//!
+//!fn main() {
+//!    match parse("") {
+//!        Err(ParseError::Empty(error_info)) => assert!(error_info.file().ends_with(".rs")),
+//!        _ => unreachable!(),
+//!    }
+//!}
+//! ```
+
+pub use {ErrorInfo,ErrorInfoTrait,ErrorTrail,ErrorMetadata,ResultExt,Traced,DynError,NesError,NoInfo};
+pub use context::AddContext;
+pub use section::AddSection;
+
+pub use {define_error,define_error_extend,impl_from_error,define_error_chain,err,create_err,push_warning,try_err,try,try_block,error_scope,with_error_context,defer_err,lazy_format,result,ok,error_info,inspect_err,log_err,assert_err,assert_err_chain};
+pub use {tcp_connect,tcp_bind,udp_bind};
+pub use try_res;
+#[cfg(feature = "sync-macros")]
+pub use {mutex_lock,rw_write,rw_read,mutex_lock_res,rw_write_res,rw_read_res};
+#[cfg(feature = "channel-macros")]
+pub use {channel_send,channel_send_res};
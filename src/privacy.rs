@@ -0,0 +1,214 @@
+//!Privacy mode(feature "privacy"), that hides layout of source code in release builds.
+//!
+//!Locations of errors contain paths of source files and module paths, so binaries and logs, that are shipped to customers,
+//!show internal layout of the project. With feature "privacy" builds without debug assertions(release profile) write instead of file
+//!opaque hash of path, that is computed at compile time, line and column stay, so support can map hashes back by table of files.
+//!Debug builds show paths as usual.
+//!
+//! ```text
+//!5f0c2a6e9b1d47e3 42:13
+//!can not read file "settings.toml"
+//! ```
+//!
+//!Hash is FNV-1a of path, that is returned by file!(), written as 16 hex digits:
+//!
+//! ```
+//!extern crate nes;
+//!
+//!fn main() {
+//!    const HASH:[u8;16] = nes::privacy::file_hash("src/main.rs");
+//!    let hash=nes::privacy::hash_str(&HASH);
+//!
+//!    assert_eq!(hash.len(), 16);
+//!    assert_eq!(hash, nes::privacy::private_file("src/main.rs"));
+//!}
+//! ```
+//!
+//!`LocationTable` maps hashes back to paths, it is generated by build script and is shipped to support separately of the binary.
+//!Paths must be the same, that file!() returns, that is relative to the root of the workspace, like "src/main.rs".
+//!
+//! ```no_run
+//!//build.rs, nes with feature "privacy" is in [build-dependencies]
+//!extern crate nes;
+//!use nes::privacy::LocationTable;
+//!
+//!fn main() {
+//!    let mut table=LocationTable::new();
+//!    table.add_dir("src").unwrap();
+//!    table.write_to_file("target/nes_locations.txt").unwrap();
+//!
+//!    println!("cargo:rerun-if-changed=src");
+//!}
+//! ```
+//!
+//!Support restores paths in reports by the table:
+//!
+//! ```
+//!extern crate nes;
+//!use nes::privacy::LocationTable;
+//!
+//!fn main() {
+//!    let mut table=LocationTable::new();
+//!    table.add_file("src/config.rs");
+//!
+//!    let text=table.to_string();
+//!    let table=LocationTable::parse(&text);
+//!
+//!    let report=format!("{} 42:13\ncan not read file", nes::privacy::private_file("src/config.rs"));
+//!    assert_eq!(table.deobfuscate(&report), "src/config.rs 42:13\ncan not read file");
+//!}
+//! ```
+
+use std::collections::{BTreeMap,HashMap};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str;
+use std::sync::Mutex;
+
+const HEX:&[u8;16] = b"0123456789abcdef";
+
+///Returns true, if locations are written as hashes, that is in builds without debug assertions.
+pub fn is_enabled() -> bool {
+    cfg!(not(debug_assertions))
+}
+
+///Returns FNV-1a hash of path.
+pub const fn file_id(file:&str) -> u64 {
+    let bytes=file.as_bytes();
+    let mut hash:u64=0xcbf29ce484222325;
+    let mut i=0;
+
+    while i<bytes.len() {
+        hash=(hash ^ bytes[i] as u64).wrapping_mul(0x100000001b3);
+        i+=1;
+    }
+
+    hash
+}
+
+///Returns FNV-1a hash of path as 16 hex digits, it is called by macros at compile time.
+pub const fn file_hash(file:&str) -> [u8;16] {
+    let hash=file_id(file);
+    let mut hex=[0u8;16];
+    let mut i=0;
+
+    while i<16 {
+        hex[i]=HEX[((hash >> (60-i*4)) & 0xf) as usize];
+        i+=1;
+    }
+
+    hex
+}
+
+pub const fn hash_str(hash:&'static [u8;16]) -> &'static str {
+    match str::from_utf8(hash) {
+        Ok( hash ) => hash,
+        Err( _ ) => "",
+    }
+}
+
+static FILES:Mutex<Option<HashMap<&'static str, &'static str>>> = Mutex::new(None);
+
+///Returns hash of path at run time, for locations of `#[track_caller]`. Hash of each file is allocated once.
+pub fn private_file(file:&'static str) -> &'static str {
+    let mut files=match FILES.lock() {
+        Ok( files ) => files,
+        Err( poisoned ) => poisoned.into_inner(),
+    };
+
+    files.get_or_insert_with(HashMap::new).entry(file).or_insert_with(|| {
+        let hash:&'static [u8;16]=Box::leak(Box::new(file_hash(file)));
+        hash_str(hash)
+    })
+}
+
+///Table of hashes and paths of source files, that restores paths in locations of privacy mode.
+#[derive(Default, Clone)]
+pub struct LocationTable {
+    files:BTreeMap<String, String>
+}
+
+impl LocationTable {
+    pub fn new() -> Self {
+        LocationTable::default()
+    }
+
+    ///Adds the file, path must be the same, that file!() returns.
+    pub fn add_file(&mut self, file:&str) -> &mut Self {
+        let hash=file_hash(file);
+        self.files.insert(String::from_utf8_lossy(&hash).into_owned(), file.to_string());
+        self
+    }
+
+    ///Adds all .rs files of the directory and its subdirectories, paths are joined to the path of the directory.
+    pub fn add_dir<P:AsRef<Path>>(&mut self, dir:P) -> io::Result<&mut Self> {
+        for entry in fs::read_dir(dir)? {
+            let path=entry?.path();
+
+            if path.is_dir() {
+                self.add_dir(&path)?;
+            }else if path.extension().is_some_and(|extension| extension=="rs") {
+                self.add_file(&path.to_string_lossy());
+            }
+        }
+
+        Ok(self)
+    }
+
+    ///Parses the table, that is written by Display or write_to_file: lines "hash path".
+    pub fn parse(text:&str) -> Self {
+        let mut table=LocationTable::new();
+
+        for line in text.lines() {
+            if let Some( (hash, file) )=line.split_once(' ') {
+                table.files.insert(hash.to_string(), file.to_string());
+            }
+        }
+
+        table
+    }
+
+    pub fn write_to_file<P:AsRef<Path>>(&self, path:P) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    ///Returns path of the file by its hash.
+    pub fn file(&self, hash:&str) -> Option<&str> {
+        self.files.get(hash).map(|file| file.as_str())
+    }
+
+    ///Replaces hashes in the text(report, log) by paths.
+    pub fn deobfuscate(&self, text:&str) -> String {
+        let mut result=String::with_capacity(text.len());
+        let mut rest=text;
+
+        while let Some( start )=rest.find(|c:char| c.is_ascii_hexdigit()) {
+            result.push_str(&rest[..start]);
+            rest=&rest[start..];
+
+            let end=rest.find(|c:char| !c.is_ascii_hexdigit()).unwrap_or(rest.len());
+
+            match self.file(&rest[..end]) {
+                Some( file ) => result.push_str(file),
+                None => result.push_str(&rest[..end]),
+            }
+
+            rest=&rest[end..];
+        }
+
+        result.push_str(rest);
+        result
+    }
+}
+
+impl fmt::Display for LocationTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (hash, file) in self.files.iter() {
+            writeln!(f, "{} {}", hash, file)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,454 @@
+//!Protobuf mapping of errors(feature "protobuf").
+//!
+//!All errors, that are defined by define_error!(), implement ToProtobuf, that maps them to generic message NesError,
+//!so services in any language can read errors from gRPC metadata and Kafka payloads. Definition of messages is in `PROTO`:
+//!
+//! ```text
+//!syntax = "proto3";
+//!
+//!package nes;
+//!
+//!message NesError {
+//!  string error = 1;
+//!  string variant = 2;
+//!  string code = 3;
+//!  string message = 4;
+//!  repeated Field fields = 5;
+//!  repeated Location locations = 6;
+//!}
+//!
+//!message Field {
+//!  string key = 1;
+//!  string value = 2;
+//!}
+//!
+//!message Location {
+//!  string error = 1;
+//!  string variant = 2;
+//!  string file = 3;
+//!  uint32 line = 4;
+//!  uint32 col = 5;
+//!}
+//! ```
+//!
+//!Values of fields are written by Display or, if field has no Display, by Debug.
+//!Locations are ErrorInfo trail of the error chain, first location is location of the error.
+//!
+//!`encode()` returns Kafka payload, `to_metadata_value()` returns value of gRPC binary metadata with key `METADATA_KEY`.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use nes::protobuf::{NesError,ToProtobuf,METADATA_KEY};
+//!
+//!define_error!( OrderError,
+//!    #[nes(code = "ORDER_NOT_FOUND")]
+//!    NotFound(order_id:u64) => "order {} is not found"
+//!);
+//!
+//!fn main() {
+//!    let error:OrderError=create_err!(OrderError::NotFound, 42);
+//!    let value=error.to_protobuf().to_metadata_value();
+//!    println!("{}: {}", METADATA_KEY, value);
+//!
+//!    let decoded=NesError::from_metadata_value(&value).unwrap();
+//!    assert_eq!(decoded.code, "ORDER_NOT_FOUND");
+//!    assert_eq!(decoded.fields[0].value, "42");
+//!}
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+use {ErrorInfoTrait,ErrorTrail};
+
+///Definition of messages in proto3 syntax.
+pub const PROTO:&str = r#"syntax = "proto3";
+
+package nes;
+
+message NesError {
+  string error = 1;
+  string variant = 2;
+  string code = 3;
+  string message = 4;
+  repeated Field fields = 5;
+  repeated Location locations = 6;
+}
+
+message Field {
+  string key = 1;
+  string value = 2;
+}
+
+message Location {
+  string error = 1;
+  string variant = 2;
+  string file = 3;
+  uint32 line = 4;
+  uint32 col = 5;
+}
+"#;
+
+///Key of gRPC binary metadata, that contains NesError.
+pub const METADATA_KEY:&str = "nes-error-bin";
+
+///This trait is implemented by define_error!() for all errors.
+pub trait ToProtobuf {
+    fn to_protobuf(&self) -> NesError;
+}
+
+impl<T:ToProtobuf + ?Sized> ToProtobuf for Box<T> {
+    fn to_protobuf(&self) -> NesError { (**self).to_protobuf() }
+}
+
+impl<T:ToProtobuf + ?Sized> ToProtobuf for ::std::sync::Arc<T> {
+    fn to_protobuf(&self) -> NesError { (**self).to_protobuf() }
+}
+
+///Message NesError.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NesError {
+    pub error:String,
+    pub variant:String,
+    pub code:String,
+    pub message:String,
+    pub fields:Vec<Field>,
+    pub locations:Vec<Location>
+}
+
+///Message Field, key is name of field of variant.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Field {
+    pub key:String,
+    pub value:String
+}
+
+///Message Location.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Location {
+    pub error:String,
+    pub variant:String,
+    pub file:String,
+    pub line:u32,
+    pub col:u32
+}
+
+impl NesError {
+    ///Creates message, it is called by define_error!().
+    pub fn new<E:ErrorTrail + fmt::Display>(error:&E, code:Option<&str>, fields:Vec<Field>) -> Self {
+        let trail=error.error_trail();
+        let (error_name, variant_name)=match trail.first() {
+            Some( item ) => (item.error_name, item.variant_name),
+            None => ("", ""),
+        };
+
+        NesError {
+            error:error_name.to_string(),
+            variant:variant_name.to_string(),
+            code:code.unwrap_or("").to_string(),
+            message:error.to_string(),
+            fields,
+            locations:trail.iter().map(|item| Location {
+                error:item.error_name.to_string(),
+                variant:item.variant_name.to_string(),
+                file:item.error_info.file().to_string(),
+                line:item.error_info.line(),
+                col:item.error_info.col()
+            }).collect()
+        }
+    }
+
+    ///Returns value of field by key.
+    pub fn field(&self, key:&str) -> Option<&str> {
+        self.fields.iter().find(|field| field.key==key).map(|field| field.value.as_str())
+    }
+
+    ///Encodes message in protobuf format, it is payload for Kafka.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf=Vec::new();
+        write_string(&mut buf, 1, &self.error);
+        write_string(&mut buf, 2, &self.variant);
+        write_string(&mut buf, 3, &self.code);
+        write_string(&mut buf, 4, &self.message);
+
+        for field in self.fields.iter() {
+            let mut message=Vec::new();
+            write_string(&mut message, 1, &field.key);
+            write_string(&mut message, 2, &field.value);
+            write_bytes(&mut buf, 5, &message);
+        }
+
+        for location in self.locations.iter() {
+            let mut message=Vec::new();
+            write_string(&mut message, 1, &location.error);
+            write_string(&mut message, 2, &location.variant);
+            write_string(&mut message, 3, &location.file);
+            write_uint(&mut message, 4, location.line as u64);
+            write_uint(&mut message, 5, location.col as u64);
+            write_bytes(&mut buf, 6, &message);
+        }
+
+        buf
+    }
+
+    ///Decodes message from protobuf format, unknown fields are skipped.
+    pub fn decode(buf:&[u8]) -> Result<Self, DecodeError> {
+        let mut error=NesError::default();
+        let mut reader=Reader { buf, pos:0 };
+
+        while let Some((number, value))=reader.next_field()? {
+            match number {
+                1 => error.error=value.string()?,
+                2 => error.variant=value.string()?,
+                3 => error.code=value.string()?,
+                4 => error.message=value.string()?,
+                5 => {
+                    let mut field=Field::default();
+                    let mut reader=Reader { buf:value.bytes()?, pos:0 };
+
+                    while let Some((number, value))=reader.next_field()? {
+                        match number {
+                            1 => field.key=value.string()?,
+                            2 => field.value=value.string()?,
+                            _ => {},
+                        }
+                    }
+
+                    error.fields.push(field);
+                },
+                6 => {
+                    let mut location=Location::default();
+                    let mut reader=Reader { buf:value.bytes()?, pos:0 };
+
+                    while let Some((number, value))=reader.next_field()? {
+                        match number {
+                            1 => location.error=value.string()?,
+                            2 => location.variant=value.string()?,
+                            3 => location.file=value.string()?,
+                            4 => location.line=value.uint()? as u32,
+                            5 => location.col=value.uint()? as u32,
+                            _ => {},
+                        }
+                    }
+
+                    error.locations.push(location);
+                },
+                _ => {},
+            }
+        }
+
+        Ok(error)
+    }
+
+    ///Returns value of gRPC binary metadata(base64 without padding), key is `METADATA_KEY`.
+    pub fn to_metadata_value(&self) -> String {
+        base64_encode(&self.encode())
+    }
+
+    ///Decodes value of gRPC binary metadata, padding is optional.
+    pub fn from_metadata_value(value:&str) -> Result<Self, DecodeError> {
+        NesError::decode(&base64_decode(value)?)
+    }
+}
+
+impl fmt::Display for NesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+///Error of decoding of NesError.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeError(&'static str);
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "can not decode NesError: {}", self.0)
+    }
+}
+
+impl Error for DecodeError {}
+
+fn write_varint(buf:&mut Vec<u8>, mut value:u64) {
+    while value>=0x80 {
+        buf.push((value as u8 & 0x7f) | 0x80);
+        value>>=7;
+    }
+
+    buf.push(value as u8);
+}
+
+fn write_uint(buf:&mut Vec<u8>, number:u32, value:u64) {
+    //proto3 does not write default values
+    if value!=0 {
+        write_varint(buf, (number as u64) << 3);
+        write_varint(buf, value);
+    }
+}
+
+fn write_bytes(buf:&mut Vec<u8>, number:u32, bytes:&[u8]) {
+    write_varint(buf, ((number as u64) << 3) | 2);
+    write_varint(buf, bytes.len() as u64);
+    buf.extend_from_slice(bytes);
+}
+
+fn write_string(buf:&mut Vec<u8>, number:u32, text:&str) {
+    if !text.is_empty() {
+        write_bytes(buf, number, text.as_bytes());
+    }
+}
+
+struct Reader<'a> {
+    buf:&'a [u8],
+    pos:usize
+}
+
+enum Value<'a> {
+    Uint(u64),
+    Bytes(&'a [u8]),
+    Fixed
+}
+
+impl<'a> Value<'a> {
+    fn uint(&self) -> Result<u64, DecodeError> {
+        match *self {
+            Value::Uint(value) => Ok(value),
+            _ => Err(DecodeError("wrong wire type of integer")),
+        }
+    }
+
+    fn bytes(&self) -> Result<&'a [u8], DecodeError> {
+        match *self {
+            Value::Bytes(bytes) => Ok(bytes),
+            _ => Err(DecodeError("wrong wire type of message")),
+        }
+    }
+
+    fn string(&self) -> Result<String, DecodeError> {
+        String::from_utf8(self.bytes()?.to_vec()).map_err(|_| DecodeError("string is not UTF-8"))
+    }
+}
+
+impl<'a> Reader<'a> {
+    fn varint(&mut self) -> Result<u64, DecodeError> {
+        let mut value=0u64;
+
+        for shift in (0..64).step_by(7) {
+            let byte=match self.buf.get(self.pos) {
+                Some( byte ) => *byte,
+                None => return Err(DecodeError("unexpected end of buffer")),
+            };
+            self.pos+=1;
+
+            value|=((byte & 0x7f) as u64) << shift;
+
+            if byte & 0x80==0 {
+                return Ok(value);
+            }
+        }
+
+        Err(DecodeError("varint is too long"))
+    }
+
+    fn take(&mut self, length:usize) -> Result<&'a [u8], DecodeError> {
+        if self.buf.len()-self.pos<length {
+            return Err(DecodeError("unexpected end of buffer"));
+        }
+
+        let bytes=&self.buf[self.pos..self.pos+length];
+        self.pos+=length;
+
+        Ok(bytes)
+    }
+
+    fn next_field(&mut self) -> Result<Option<(u64, Value<'a>)>, DecodeError> {
+        if self.pos==self.buf.len() {
+            return Ok(None);
+        }
+
+        let key=self.varint()?;
+        let value=match key & 7 {
+            0 => Value::Uint(self.varint()?),
+            1 => { self.take(8)?; Value::Fixed },
+            2 => {
+                let length=self.varint()? as usize;
+                Value::Bytes(self.take(length)?)
+            },
+            5 => { self.take(4)?; Value::Fixed },
+            _ => return Err(DecodeError("unknown wire type")),
+        };
+
+        Ok(Some((key >> 3, value)))
+    }
+}
+
+const BASE64:&[u8;64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64_encode(bytes:&[u8]) -> String {
+    let mut text=String::with_capacity((bytes.len()*4).div_ceil(3));
+
+    for chunk in bytes.chunks(3) {
+        let n=(chunk[0] as u32) << 16 | (*chunk.get(1).unwrap_or(&0) as u32) << 8 | *chunk.get(2).unwrap_or(&0) as u32;
+
+        for i in 0..chunk.len()+1 {
+            text.push(BASE64[(n >> (18-6*i) & 63) as usize] as char);
+        }
+    }
+
+    text
+}
+
+fn base64_decode(text:&str) -> Result<Vec<u8>, DecodeError> {
+    let text=text.trim_end_matches('=');
+    let mut bytes=Vec::with_capacity(text.len()*3/4);
+    let mut n=0u32;
+    let mut bits=0;
+
+    for c in text.bytes() {
+        let value=match BASE64.iter().position(|b| *b==c) {
+            Some( value ) => value as u32,
+            None => return Err(DecodeError("wrong character of base64")),
+        };
+
+        n=n << 6 | value;
+        bits+=6;
+
+        if bits>=8 {
+            bits-=8;
+            bytes.push((n >> bits) as u8);
+        }
+    }
+
+    Ok(bytes)
+}
+
+//Values of fields are written by Display, else by Debug, it is selected by autoref:
+//(&ProtobufField(field)).protobuf_value() calls ValueViaDisplay, if it is implemented, else ValueViaDebug.
+#[doc(hidden)]
+pub struct ProtobufField<'a, T:'a>(pub &'a T);
+
+#[doc(hidden)]
+pub trait ValueViaDisplay {
+    fn protobuf_value(&self) -> String;
+}
+
+impl<'a, T:fmt::Display> ValueViaDisplay for ProtobufField<'a, T> {
+    fn protobuf_value(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+#[doc(hidden)]
+pub trait ValueViaDebug {
+    fn protobuf_value(&self) -> String;
+}
+
+impl<'a, T:fmt::Debug> ValueViaDebug for &ProtobufField<'a, T> {
+    fn protobuf_value(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
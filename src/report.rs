@@ -0,0 +1,706 @@
+//!Reports show errors with more details, than Display does. They are useful for developers and sysadmins, not for users.
+//!
+//!`SourceReport` shows under each location of the error chain the line of rust code, where the error has been occurred,
+//!like panics do with RUST_BACKTRACE=full. It needs source files, so it is useful only in dev builds, if file is not found, only location is shown.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use nes::report::SourceReport;
+//!
+//!define_error!( CommonError,
+//!    NoArguments() => "no arguments"
+//!);
+//!
+//!fn main() {
+//!    let error:CommonError=create_err!(CommonError::NoArguments);
+//!    println!("{}",SourceReport::new(&error));
+//!}
+//! ```
+//!
+//!Output:
+//!
+//! ```text
+//!main/src/main.rs 8:27
+//!    8 |     let error:CommonError=create_err!(CommonError::NoArguments);
+//!      |                           ^
+//!no arguments
+//! ```
+//!
+//!`Logfmt` writes the error as one line of logfmt for log pipelines, that prefer logfmt over JSON(Heroku, Grafana Loki):
+//!
+//! ```text
+//!level=error code=E1203 error=CommonError variant=IncorrectExtension file=main/src/main.rs line=42 col=5 msg="file \"a.rs\" has incorrect extension"
+//! ```
+//!
+//!Layout of locations may be changed for all errors by `set_location_formatter()`:
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use std::fmt;
+//!
+//!define_error!( CommonError,
+//!    NoArguments() => "no arguments"
+//!);
+//!
+//!fn github_location(f: &mut fmt::Formatter, file:&str, line:u32, _col:u32) -> fmt::Result {
+//!    write!(f, "{}#L{}", file, line)
+//!}
+//!
+//!fn main() {
+//!    nes::report::set_location_formatter(Some(github_location));
+//!
+//!    let error:CommonError=create_err!(CommonError::NoArguments);
+//!    assert!(error.to_string().contains(".rs#L"));
+//!}
+//! ```
+//!
+//!`VerboseReport` writes lines of source code, like SourceReport, and sections after the chain: notes, suggestions, warnings
+//!and related locations, see module nes::section. With feature "symbolize" it writes frames of `nes::stack::ErrorInfo`
+//!as functions, files and lines under location of each error of the chain, if debug info is present.
+//!
+//!Display and Debug write nested errors recursively, so pathological or cyclic(through Arc) chains are cut at max depth,
+//!that is set by `set_max_depth()`, deeper errors are written as `... (truncated)` instead of overflowing the stack.
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!
+//!define_error!( RetryError,
+//!    Failed() => "failed",
+//!    Retry(error:Box<RetryError>) => "retry: {}"
+//!);
+//!
+//!fn main() {
+//!    let mut error:RetryError=create_err!(RetryError::Failed);
+//!
+//!    for _ in 0..10 {
+//!        error=create_err!(RetryError::Retry, Box::new(error));
+//!    }
+//!
+//!    nes::report::set_max_depth(3);
+//!    assert!(error.to_string().ends_with("retry: ... (truncated)"));
+//!    assert!(format!("{:?}", error).contains("error:... (truncated)"));
+//!}
+//! ```
+//!
+//!`Numbered` writes each error of the chain as separate level `[0] ...`, `[1] caused by ...`.
+//!`DisplayList` writes errors of batch as numbered list, for summary at exit.
+//!
+//!`Snapshot` replaces line and column numbers with placeholders `LL:CC`(and host and pid of `nes::host::ErrorInfo` with `HOST[PID]`),
+//!so messages may be snapshot-tested(by insta) without changes after each unrelated move of code.
+//!`set_snapshot_mode(true)` enables this mode for all Display of errors in current thread, for example, in tests.
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use nes::report::Snapshot;
+//!
+//!define_error!( CommonError,
+//!    NoArguments() => "no arguments"
+//!);
+//!
+//!fn main() {
+//!    let mut error:CommonError=create_err!(CommonError::NoArguments);
+//!    let file=error.get_error_info().file();
+//!    assert_eq!(Snapshot::new(&error).to_string(), format!("{} LL:CC\nno arguments", file));
+//!
+//!    nes::report::set_snapshot_mode(true);
+//!    assert!(error.to_string().ends_with(" LL:CC\nno arguments"));
+//!}
+//! ```
+
+use std::cell::Cell;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize,Ordering};
+#[cfg(feature = "display")]
+use std::error::Error;
+#[cfg(feature = "display")]
+use std::fmt::Write;
+#[cfg(feature = "display")]
+use std::fs::File;
+#[cfg(feature = "display")]
+use std::io::{BufRead,BufReader};
+#[cfg(feature = "display")]
+use std::path::PathBuf;
+#[cfg(feature = "display")]
+use std::sync::RwLock;
+
+#[cfg(feature = "display")]
+use {ErrorInfoTrait,ErrorMetadata,ErrorTrail,Severity};
+#[cfg(feature = "display")]
+use aggregate::Indented;
+#[cfg(feature = "display")]
+use context::ContextValue;
+#[cfg(feature = "display")]
+use section::SectionKind;
+
+thread_local! {
+    #[cfg(feature = "display")]
+    static SHOW_SOURCE_LINES: Cell<bool> = const { Cell::new(false) };
+    static SNAPSHOT_MODE: Cell<bool> = const { Cell::new(false) };
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+    static NUMBERED: Cell<bool> = const { Cell::new(false) };
+    #[cfg(feature = "display")]
+    static VERBOSE: Cell<bool> = const { Cell::new(false) };
+}
+
+static MAX_DEPTH:AtomicUsize = AtomicUsize::new(128);
+
+///Display of this wrapper shows the lines of source code under the locations of the error chain.
+#[cfg(feature = "display")]
+pub struct SourceReport<'a, E:'a>(&'a E);
+
+#[cfg(feature = "display")]
+impl<'a, E:fmt::Display> SourceReport<'a, E> {
+    pub fn new(error:&'a E) -> Self {
+        SourceReport(error)
+    }
+}
+
+#[cfg(feature = "display")]
+impl<'a, E:fmt::Display> fmt::Display for SourceReport<'a, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let previous=SHOW_SOURCE_LINES.with(|show| show.replace(true));
+        let result=write!(f, "{}", self.0);
+        SHOW_SOURCE_LINES.with(|show| show.set(previous));
+
+        result
+    }
+}
+
+///Display of this wrapper shows the chain with lines of source code, like SourceReport, and sections after it: note, help and warning
+///of variant and sections, that are added at runtime, see module nes::section.
+#[cfg(feature = "display")]
+pub struct VerboseReport<'a, E:'a>(&'a E);
+
+#[cfg(feature = "display")]
+impl<'a, E:fmt::Display + ErrorMetadata> VerboseReport<'a, E> {
+    pub fn new(error:&'a E) -> Self {
+        VerboseReport(error)
+    }
+}
+
+#[cfg(feature = "display")]
+impl<'a, E:fmt::Display + ErrorMetadata> fmt::Display for VerboseReport<'a, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        //runtime sections are written after the chain, not by Display of WithSections
+        let previous=VERBOSE.with(|verbose| verbose.replace(true));
+        let result=write!(f, "{}", SourceReport(self.0));
+        VERBOSE.with(|verbose| verbose.set(previous));
+        result?;
+
+        let metadata=[
+            (SectionKind::Note, self.0.note()),
+            (SectionKind::Help, self.0.help()),
+            (SectionKind::Warning, self.0.warning())
+        ];
+
+        let sections=self.0.sections();
+
+        if metadata.iter().all(|&(_, text)| text.is_none()) && sections.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(f)?;
+
+        for &(kind, text) in metadata.iter() {
+            if let Some( text )=text {
+                write!(f, "\n{}: {}", kind.label(), text)?;
+            }
+        }
+
+        for section in sections.iter() {
+            write!(f, "\n{}", section)?;
+        }
+
+        Ok(())
+    }
+}
+
+//true while VerboseReport writes the chain
+pub(crate) fn is_verbose() -> bool {
+    #[cfg(feature = "display")]
+    {
+        VERBOSE.with(|verbose| verbose.get())
+    }
+
+    #[cfg(not(feature = "display"))]
+    {
+        false
+    }
+}
+
+///Display of this wrapper replaces line and column numbers of locations with placeholders `LL:CC`, see `set_snapshot_mode()`.
+pub struct Snapshot<'a, E:'a>(&'a E);
+
+impl<'a, E:fmt::Display> Snapshot<'a, E> {
+    pub fn new(error:&'a E) -> Self {
+        Snapshot(error)
+    }
+}
+
+impl<'a, E:fmt::Display> fmt::Display for Snapshot<'a, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let previous=SNAPSHOT_MODE.with(|mode| mode.replace(true));
+        let result=write!(f, "{}", self.0);
+        SNAPSHOT_MODE.with(|mode| mode.set(previous));
+
+        result
+    }
+}
+
+///Enables or disables snapshot mode for current thread, in this mode Display of errors writes placeholders `LL:CC` instead of line and column numbers.
+pub fn set_snapshot_mode(enabled:bool) {
+    SNAPSHOT_MODE.with(|mode| mode.set(enabled));
+}
+
+///Returns true, if snapshot mode is enabled in current thread or Snapshot is being displayed now.
+pub fn is_snapshot_mode() -> bool {
+    SNAPSHOT_MODE.with(|mode| mode.get())
+}
+
+///Function, that writes location of error, see `set_location_formatter()`.
+#[cfg(feature = "display")]
+pub type LocationFormatter = fn(f: &mut fmt::Formatter, file:&str, line:u32, col:u32) -> fmt::Result;
+
+#[cfg(feature = "display")]
+static LOCATION_FORMATTER:RwLock<Option<LocationFormatter>> = RwLock::new(None);
+
+///Sets function, that writes locations in Display of all errors instead of `file line:col`, for example, `file#line`
+///or `hyperlink_location` for terminals, that support hyperlinks. None restores default layout.
+///Snapshot mode ignores this function.
+#[cfg(feature = "display")]
+pub fn set_location_formatter(formatter:Option<LocationFormatter>) {
+    match LOCATION_FORMATTER.write() {
+        Ok( mut location_formatter ) => *location_formatter=formatter,
+        Err( poisoned ) => *poisoned.into_inner()=formatter,
+    }
+}
+
+///Writes location like `file line:col`, or `file LL:CC` in snapshot mode, or by function, that is set by `set_location_formatter()`.
+///It is called by Display of standard ErrorInfo, you may call it in Display of your own ErrorInfo.
+pub fn fmt_location(f: &mut fmt::Formatter, file:&str, line:u32, col:u32) -> fmt::Result {
+    if is_snapshot_mode() {
+        return write!(f, "{} LL:CC", file);
+    }
+
+    #[cfg(feature = "display")]
+    {
+        let formatter=match LOCATION_FORMATTER.read() {
+            Ok( formatter ) => *formatter,
+            Err( poisoned ) => *poisoned.into_inner(),
+        };
+
+        if let Some( formatter )=formatter {
+            return formatter(f, file, line, col);
+        }
+    }
+
+    write!(f, "{} {}:{}", file, line, col)
+}
+
+///Location formatter, that writes `file line:col` as OSC 8 hyperlink to the file, so terminals open it by click.
+#[cfg(feature = "display")]
+pub fn hyperlink_location(f: &mut fmt::Formatter, file:&str, line:u32, col:u32) -> fmt::Result {
+    let dir=std::env::current_dir().unwrap_or_default();
+    //file of error_info!(caller) has no module path, so the path is used as is, if it exists, like in source_line()
+    let path=match dir.join(file) {
+        ref path if !path.exists() && dir.join(strip_module_path(file)).exists() => dir.join(strip_module_path(file)),
+        path => path,
+    };
+
+    write!(f, "\x1b]8;;file://{}#{}\x1b\\{} {}:{}\x1b]8;;\x1b\\", PercentEncoded(&path.to_string_lossy()), line, file, line, col)
+}
+
+//path in URI, bytes except unreserved characters, '/' and ':' are written as %XX
+#[cfg(feature = "display")]
+struct PercentEncoded<'a>(&'a str);
+
+#[cfg(feature = "display")]
+impl<'a> fmt::Display for PercentEncoded<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in self.0.bytes() {
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => write!(f, "{}", byte as char)?,
+                _ => write!(f, "%{:02X}", byte)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+//error_info!() writes module path before file name
+#[cfg(feature = "display")]
+fn strip_module_path(file:&str) -> &str {
+    if let Some(pos)=file.find('/') {
+        if file[..pos].chars().all(|c| c.is_alphanumeric() || c=='_' || c==':') {
+            return &file[pos+1..];
+        }
+    }
+
+    file
+}
+
+///Sets max depth of nested errors, that are written by Display and Debug, deeper errors are replaced by `... (truncated)`. Default is 128.
+pub fn set_max_depth(max_depth:usize) {
+    MAX_DEPTH.store(max_depth, Ordering::Relaxed);
+}
+
+pub fn max_depth() -> usize {
+    MAX_DEPTH.load(Ordering::Relaxed)
+}
+
+struct DepthGuard(usize);
+
+impl Drop for DepthGuard {
+    fn drop(&mut self) {
+        DEPTH.with(|depth| depth.set(self.0));
+    }
+}
+
+///Writes the error by fmt, if depth of nested errors, that are being written now, is less than max depth, else writes `... (truncated)`.
+///It is called by Display and Debug of errors, that are defined by define_error!().
+pub fn fmt_limited<F:FnOnce(&mut fmt::Formatter) -> fmt::Result>(f: &mut fmt::Formatter, fmt:F) -> fmt::Result {
+    let depth=DEPTH.with(|depth| depth.replace(depth.get()+1));
+    let _guard=DepthGuard(depth);
+
+    if depth>0 && NUMBERED.with(|numbered| numbered.get()) {
+        //Numbered writes nested errors as separate levels
+        Ok(())
+    }else if depth>=max_depth() {
+        write!(f, "... (truncated)")
+    }else{
+        fmt(f)
+    }
+}
+
+///Display of this wrapper writes each error of the chain as separate level with its number, so layers may be referenced in tickets.
+///
+/// # Example
+///
+/// ```
+///#[macro_use]
+///extern crate nes;
+///use nes::{ErrorInfo,ErrorInfoTrait};
+///use nes::report::Numbered;
+///
+///define_error!( ReadFileError,
+///    IOError(io_error:Box<std::io::Error>, file:String) => "can not read file \"{2}\": {1}"
+///);
+///
+///define_error!( ConfigError,
+///    ReadFileError(error:Box<ReadFileError>) => "can not load config {}"
+///);
+///
+///impl_from_error!(ReadFileError => ConfigError);
+///
+///fn read(file:&str) -> result![String,ReadFileError] {
+///    ok!(try!(std::fs::read_to_string(file), ReadFileError::IOError, file.to_string()))
+///}
+///
+///fn load(file:&str) -> result![String,ConfigError] {
+///    ok!(read(file)?)
+///}
+///
+///fn main() {
+///    let report=Numbered::new(&load("no_file.rs").unwrap_err()).to_string();
+///    let lines:Vec<&str>=report.lines().collect();
+///
+///    assert!(lines[0].starts_with("[0] "));
+///    assert_eq!(lines[1], "can not load config");
+///    assert!(lines[2].starts_with("[1] caused by "));
+///    assert!(lines[3].starts_with("can not read file \"no_file.rs\""));
+///}
+/// ```
+///
+///Output:
+///
+/// ```text
+///[0] main/src/main.rs 16:0
+///can not load config
+///[1] caused by main/src/main.rs 20:8
+///can not read file "no_file.rs": No such file or directory (os error 2)
+/// ```
+#[cfg(feature = "display")]
+pub struct Numbered<'a, E:'a>(&'a E);
+
+#[cfg(feature = "display")]
+impl<'a, E:Error + ErrorTrail> Numbered<'a, E> {
+    pub fn new(error:&'a E) -> Self {
+        Numbered(error)
+    }
+}
+
+#[cfg(feature = "display")]
+impl<'a, E:Error + ErrorTrail> fmt::Display for Numbered<'a, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        //sources, that are not in the trail, are foreign errors, they are written by messages of their owners
+        let levels=self.0.error_trail().len().max(1);
+        let mut error:Option<&dyn Error>=Some(self.0);
+
+        for level in 0..levels {
+            let current=match error {
+                Some( current ) => current,
+                None => break,
+            };
+
+            let previous=NUMBERED.with(|numbered| numbered.replace(true));
+            let text=current.to_string();
+            NUMBERED.with(|numbered| numbered.set(previous));
+
+            if level==0 {
+                write!(f, "[0] {}", text.trim_end())?;
+            }else{
+                write!(f, "\n[{}] caused by {}", level, text.trim_end())?;
+            }
+
+            error=current.source();
+        }
+
+        Ok(())
+    }
+}
+
+///Display of this wrapper writes errors of batch(nes errors or `Box<dyn NesError>`) as numbered list with locations,
+///for summary of failures at exit of batch run.
+///
+/// # Example
+///
+/// ```
+///#[macro_use]
+///extern crate nes;
+///use nes::{ErrorInfo,ErrorInfoTrait};
+///use nes::report::DisplayList;
+///
+///define_error!( ImportError,
+///    BadRow(row:usize) => "bad row {}"
+///);
+///
+///fn main() {
+///    let errors:Vec<ImportError>=vec![create_err!(ImportError::BadRow, 3), create_err!(ImportError::BadRow, 7)];
+///    let text=DisplayList::new(&errors).to_string();
+///
+///    assert!(text.starts_with("2 errors:\n  [1] "));
+///    assert!(text.contains("\n      bad row 3\n  [2] "));
+///    assert!(text.ends_with("\n      bad row 7"));
+///}
+/// ```
+///
+///Output:
+///
+/// ```text
+///2 errors:
+///  [1] main/src/main.rs 11:30
+///      bad row 3
+///  [2] main/src/main.rs 11:69
+///      bad row 7
+/// ```
+#[cfg(feature = "display")]
+pub struct DisplayList<'a, E:'a>(&'a [E]);
+
+#[cfg(feature = "display")]
+impl<'a, E:fmt::Display> DisplayList<'a, E> {
+    pub fn new(errors:&'a [E]) -> Self {
+        DisplayList(errors)
+    }
+}
+
+#[cfg(feature = "display")]
+impl<'a, E:fmt::Display> fmt::Display for DisplayList<'a, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0.len() {
+            0 => return write!(f, "no errors"),
+            1 => write!(f, "1 error:")?,
+            count => write!(f, "{} errors:", count)?,
+        }
+
+        for (index, error) in self.0.iter().enumerate() {
+            write!(f, "\n  [{}] ", index+1)?;
+            write!(Indented { f, indent:"      " }, "{}", error)?;
+        }
+
+        Ok(())
+    }
+}
+
+///Display of this wrapper writes the error as one line of logfmt, code is skipped if variant has no code.
+///
+/// # Example
+///
+/// ```
+///#[macro_use]
+///extern crate nes;
+///use nes::{ErrorInfo,ErrorInfoTrait};
+///use nes::report::Logfmt;
+///
+///define_error!( CommonError,
+///    #[nes(code = "E1203")]
+///    IncorrectExtension(file_name:String) => "file \"{}\" has incorrect extension"
+///);
+///
+///fn main() {
+///    let error:CommonError=create_err!(CommonError::IncorrectExtension, "a.rs".to_string());
+///    println!("{}",Logfmt::new(&error));
+///}
+/// ```
+#[cfg(feature = "display")]
+pub struct Logfmt<'a, E:'a>(&'a E);
+
+#[cfg(feature = "display")]
+impl<'a, E:ErrorMetadata> Logfmt<'a, E> {
+    pub fn new(error:&'a E) -> Self {
+        Logfmt(error)
+    }
+}
+
+#[cfg(feature = "display")]
+impl<'a, E:ErrorMetadata> fmt::Display for Logfmt<'a, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let level=match self.0.severity() {
+            Severity::Error => "error",
+            Severity::Warning => "warn",
+            Severity::Advice => "info",
+        };
+
+        write!(f, "level={}", level)?;
+
+        if let Some(code)=self.0.code() {
+            write!(f, " code={}", LogfmtValue(code))?;
+        }
+
+        let location=self.0.location();
+
+        write!(
+            f, " error={} variant={} file={} line={} col={}",
+            self.0.error_name(), self.0.variant_name(), LogfmtValue(location.file()), location.line(), location.col()
+        )?;
+
+        if let Some(span_id)=location.span_id() {
+            write!(f, " span_id={}", span_id)?;
+        }
+
+        if let Some(trace_id)=location.trace_id() {
+            write!(f, " trace_id={:032x}", trace_id)?;
+        }
+
+        for item in self.0.context() {
+            match item.value {
+                ContextValue::String(ref value) => write!(f, " {}={}", item.key, LogfmtValue(value))?,
+                ref value => write!(f, " {}={}", item.key, value)?,
+            }
+        }
+
+        write!(f, " msg={}", LogfmtValue(&Message(self.0).to_string()))
+    }
+}
+
+//message of variant without ErrorInfo
+#[cfg(feature = "display")]
+struct Message<'a, E:'a>(&'a E);
+
+#[cfg(feature = "display")]
+impl<'a, E:ErrorMetadata> fmt::Display for Message<'a, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt_message(f)
+    }
+}
+
+//value of logfmt, that is quoted, if it is empty or has spaces, quotes, '=' or control characters
+#[cfg(feature = "display")]
+struct LogfmtValue<'a>(&'a str);
+
+#[cfg(feature = "display")]
+impl<'a> fmt::Display for LogfmtValue<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let quote=self.0.is_empty() || self.0.chars().any(|c| c<=' ' || c=='"' || c=='=' || c=='\\');
+
+        if !quote {
+            return write!(f, "{}", self.0);
+        }
+
+        write!(f, "\"")?;
+
+        for c in self.0.chars() {
+            match c {
+                '"' => write!(f, "\\\"")?,
+                '\\' => write!(f, "\\\\")?,
+                '\n' => write!(f, "\\n")?,
+                '\r' => write!(f, "\\r")?,
+                '\t' => write!(f, "\\t")?,
+                c => write!(f, "{}", c)?,
+            }
+        }
+
+        write!(f, "\"")
+    }
+}
+
+///Writes the line of source code with caret under column, if SourceReport is being displayed now and snapshot mode is disabled.
+///It is called by Display of standard ErrorInfo, you may call it in Display of your own ErrorInfo.
+#[cfg(feature = "display")]
+pub fn fmt_source_line(f: &mut fmt::Formatter, file:&str, line:u32, col:u32) -> fmt::Result {
+    if !SHOW_SOURCE_LINES.with(|show| show.get()) || is_snapshot_mode() {
+        return Ok(());
+    }
+
+    let code=match source_line(file, line) {
+        Some( code ) => code,
+        None => return Ok(()),
+    };
+
+    let caret_offset=code.chars().take(col.saturating_sub(1) as usize).map(|c| if c=='\t' {'\t'} else {' '}).collect::<String>();
+
+    write!(f, "\n{:>5} | {}\n{:>5} | {}^", line, code, "", caret_offset)
+}
+
+#[cfg(not(feature = "display"))]
+#[inline(always)]
+pub fn fmt_source_line(_f: &mut fmt::Formatter, _file:&str, _line:u32, _col:u32) -> fmt::Result {
+    Ok(())
+}
+
+///Reads the line(starts from 1) of source file. File may be given like in ErrorInfo, with module path before "/".
+///It looks for the file in current directory and in CARGO_MANIFEST_DIR.
+#[cfg(feature = "display")]
+pub fn source_line(file:&str, line:u32) -> Option<String> {
+    if line==0 {
+        return None;
+    }
+
+    let mut candidates=vec![file];
+
+    if strip_module_path(file)!=file {
+        candidates.push(strip_module_path(file));
+    }
+
+    let mut roots=vec![PathBuf::new()];
+
+    if let Some(manifest_dir)=std::env::var_os("CARGO_MANIFEST_DIR") {
+        roots.push(PathBuf::from(manifest_dir));
+    }
+
+    for root in roots.iter() {
+        for candidate in candidates.iter() {
+            let file=match File::open(root.join(candidate)) {
+                Ok( file ) => file,
+                Err(_) => continue,
+            };
+
+            return match BufReader::new(file).lines().nth(line as usize - 1) {
+                Some(Ok(code)) => Some(code),
+                _ => None,
+            };
+        }
+    }
+
+    None
+}
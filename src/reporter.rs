@@ -0,0 +1,555 @@
+//!Reporters are sinks of errors(stderr, files, syslog, Sentry), that are registered once and receive all reported errors.
+//!
+//!`nes::add_reporter(reporter)` registers the reporter, `error.report()` of errors, that are defined by define_error!(),
+//!or `nes::reporter::report(&error)` sends the error to all registered reporters. Reporter gets the error as `&dyn ReportedError`,
+//!so it may render it by Display, Logfmt or serde and read its trail and metadata.
+//!
+//!Built-in reporters are `StderrReporter` and `FileReporter`, that appends errors with timestamps to the file and rotates it.
+//!Reporters may be wrapped by `nes::sampling::SampledReporter`, that passes only sampled errors, and by
+//!`nes::background::BackgroundReporter`, that reports errors in background thread.
+//!Reporters, that buffer or queue errors, write them by `flush`. Before exit main() should call `nes::flush_reporters(timeout)`
+//!or return `nes::FlushOnExit(result)`, else the last errors, that are in queues, are lost. The panic hook of
+//!`nes::install_panic_hook()` flushes reporters after reporting of the panic. If the reporter panics, the panic is written
+//!to stderr, reporters are not called again.
+//!Registry and built-in reporters are compiled with feature "reporters"(enabled by default), traits are always available.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use nes::reporter::{Reporter,ReportedError,StderrReporter};
+//!use std::sync::atomic::{AtomicUsize,Ordering};
+//!
+//!define_error!( WorkerError,
+//!    QueueIsFull(size:usize) => "queue is full, size is {}"
+//!);
+//!
+//!static REPORTED:AtomicUsize=AtomicUsize::new(0);
+//!
+//!struct CountingReporter;
+//!
+//!impl Reporter for CountingReporter {
+//!    fn report(&self, error:&dyn ReportedError) {
+//!        assert_eq!(error.variant_name(), "QueueIsFull");
+//!        REPORTED.fetch_add(1, Ordering::Relaxed);
+//!    }
+//!}
+//!
+//!fn main() {
+//!    nes::add_reporter(StderrReporter);
+//!    nes::add_reporter(CountingReporter);
+//!
+//!    let error:WorkerError=create_err!(WorkerError::QueueIsFull, 1024);
+//!    error.report();
+//!    assert_eq!(REPORTED.load(Ordering::Relaxed), 1);
+//!}
+//! ```
+//!
+//!`FileReporter` gives small daemons persistent log of errors without logging framework. Each entry is the timestamp(UTC)
+//!and the full chain of the error. When the file exceeds max size, it is renamed to `file.1`, `file.1` to `file.2` and so on,
+//!the oldest file is removed.
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use nes::reporter::{Reporter,FileReporter};
+//!
+//!define_error!( WorkerError,
+//!    QueueIsFull(size:usize) => "queue is full, size is {}"
+//!);
+//!
+//!fn main() {
+//!    let path=std::env::temp_dir().join("nes_file_reporter_example.log");
+//!    let reporter=FileReporter::new(&path).max_size(256).rotations(2);
+//!
+//!    for size in 0..20 {
+//!        let error:WorkerError=create_err!(WorkerError::QueueIsFull, size);
+//!        reporter.report(&error);
+//!    }
+//!
+//!    let log=std::fs::read_to_string(&path).unwrap();
+//!    assert!(log.contains("queue is full, size is 19"));
+//!    assert!(path.with_extension("log.2").exists());
+//!    assert!(!path.with_extension("log.3").exists());
+//!}
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+#[cfg(feature = "reporters")]
+use std::fs::{self,File,OpenOptions};
+#[cfg(feature = "reporters")]
+use std::io::Write;
+#[cfg(feature = "reporters")]
+use std::path::{Path,PathBuf};
+#[cfg(feature = "reporters")]
+use std::cell::Cell;
+#[cfg(feature = "reporters")]
+use std::process::{ExitCode,Termination};
+#[cfg(feature = "reporters")]
+use std::sync::{Mutex,RwLock};
+#[cfg(feature = "reporters")]
+use std::time::{Instant,SystemTime,UNIX_EPOCH};
+
+use {ErrorInfoTrait,ErrorMetadata,ErrorTrail};
+
+///Error, that may be reported. It is implemented for all errors, that are defined by define_error!().
+pub trait ReportedError: ErrorMetadata + ErrorTrail + fmt::Display {}
+
+impl<E:ErrorMetadata + ErrorTrail + fmt::Display> ReportedError for E {}
+
+///Location of error as trait object, it is implemented for all types, that implement ErrorInfoTrait.
+///Unlike ErrorInfoTrait it has no constructor, so `&dyn ErrorInfoDyn` may point to any ErrorInfo.
+pub trait ErrorInfoDyn: fmt::Display {
+    fn file(&self) -> &'static str;
+    fn line(&self) -> u32;
+    fn col(&self) -> u32;
+    fn span_id(&self) -> Option<u64>;
+    fn trace_id(&self) -> Option<u128>;
+    fn host(&self) -> Option<&'static str>;
+    fn pid(&self) -> Option<u32>;
+}
+
+impl<I:ErrorInfoTrait> ErrorInfoDyn for I {
+    fn file(&self) -> &'static str { ErrorInfoTrait::file(self) }
+    fn line(&self) -> u32 { ErrorInfoTrait::line(self) }
+    fn col(&self) -> u32 { ErrorInfoTrait::col(self) }
+    fn span_id(&self) -> Option<u64> { ErrorInfoTrait::span_id(self) }
+    fn trace_id(&self) -> Option<u128> { ErrorInfoTrait::trace_id(self) }
+    fn host(&self) -> Option<&'static str> { ErrorInfoTrait::host(self) }
+    fn pid(&self) -> Option<u32> { ErrorInfoTrait::pid(self) }
+}
+
+///Any nes error, this trait is object safe, so errors of different types may be stored together in `Vec<Box<dyn NesError>>`.
+///It is implemented by define_error!(), names of error and variant and other metadata come from ErrorMetadata,
+///`&dyn NesError` is coerced to `&dyn ReportedError` for reporters.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///use nes::NesError;
+///
+///define_error!( ReadError,
+///    NotFound(path:String) => "{} is not found"
+///);
+///
+///define_error!( ParseError,
+///    BadNumber(line:usize) => "bad number at line {}"
+///);
+///
+/// # fn main() {
+///let errors:Vec<Box<dyn NesError>>=vec![
+///    Box::new(create_err!(ReadError::NotFound, "a.txt".to_string()) as ReadError),
+///    Box::new(create_err!(ParseError::BadNumber, 3) as ParseError),
+///];
+///
+///for error in errors.iter() {
+///    assert!(error.error_info().file().ends_with(".rs"));
+///    nes::reporter::report(&**error);
+///}
+///
+///assert_eq!(errors[1].variant_name(), "BadNumber");
+/// # }
+/// ```
+pub trait NesError: Error + ReportedError {
+    ///Returns ErrorInfo of this error(not of the nested errors).
+    fn error_info(&self) -> &dyn ErrorInfoDyn;
+}
+
+impl<T:NesError> NesError for Box<T> {
+    fn error_info(&self) -> &dyn ErrorInfoDyn { (**self).error_info() }
+}
+
+///Sink of reported errors.
+pub trait Reporter: Send + Sync {
+    fn report(&self, error:&dyn ReportedError);
+
+    ///Writes errors, that are buffered or queued, waits not longer than timeout, returns false, if not all errors are written.
+    ///Reporters, that write each error at once, do not implement it.
+    fn flush(&self, _timeout:Duration) -> bool {
+        true
+    }
+}
+
+impl<R:Reporter + ?Sized> Reporter for &'static R {
+    fn report(&self, error:&dyn ReportedError) {
+        (**self).report(error)
+    }
+
+    fn flush(&self, timeout:Duration) -> bool {
+        (**self).flush(timeout)
+    }
+}
+
+impl<R:Reporter + ?Sized> Reporter for Arc<R> {
+    fn report(&self, error:&dyn ReportedError) {
+        (**self).report(error)
+    }
+
+    fn flush(&self, timeout:Duration) -> bool {
+        (**self).flush(timeout)
+    }
+}
+
+//reporters are called without lock of registry, so they may report errors and register reporters
+#[cfg(feature = "reporters")]
+type SharedReporter = Arc<dyn Reporter>;
+
+#[cfg(feature = "reporters")]
+static REPORTERS:RwLock<Vec<SharedReporter>> = RwLock::new(Vec::new());
+
+#[cfg(feature = "reporters")]
+thread_local! {
+    //set, while the thread calls reporters, so the panic hook does not call them again, if the reporter panics
+    static REPORTING:Cell<bool> = const { Cell::new(false) };
+}
+
+//calls f with set flag REPORTING, previous value is restored after f, also if f panics
+#[cfg(feature = "reporters")]
+pub(crate) fn reporting<T, F:FnOnce() -> T>(f:F) -> T {
+    struct Restore(bool);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            REPORTING.with(|reporting| reporting.set(self.0));
+        }
+    }
+
+    let _restore=Restore(REPORTING.with(|reporting| reporting.replace(true)));
+    f()
+}
+
+//returns true, if the thread calls reporters or it is thread of BackgroundReporter
+#[cfg(feature = "reporters")]
+pub(crate) fn is_reporting() -> bool {
+    REPORTING.with(|reporting| reporting.get())
+}
+
+///Registers the reporter, it will receive all reported errors.
+#[cfg(feature = "reporters")]
+pub fn add_reporter<R:Reporter + 'static>(reporter:R) {
+    match REPORTERS.write() {
+        Ok( mut reporters ) => reporters.push(Arc::new(reporter)),
+        Err( poisoned ) => poisoned.into_inner().push(Arc::new(reporter)),
+    }
+}
+
+///Removes all registered reporters.
+#[cfg(feature = "reporters")]
+pub fn clear_reporters() {
+    match REPORTERS.write() {
+        Ok( mut reporters ) => reporters.clear(),
+        Err( poisoned ) => poisoned.into_inner().clear(),
+    }
+}
+
+///Returns true, if at least one reporter is registered.
+#[cfg(feature = "reporters")]
+pub fn has_reporters() -> bool {
+    match REPORTERS.read() {
+        Ok( reporters ) => !reporters.is_empty(),
+        Err( poisoned ) => !poisoned.into_inner().is_empty(),
+    }
+}
+
+//returns handles of registered reporters, the lock is released before they are called
+#[cfg(feature = "reporters")]
+fn registered() -> Vec<SharedReporter> {
+    match REPORTERS.read() {
+        Ok( reporters ) => reporters.clone(),
+        Err( poisoned ) => poisoned.into_inner().clone(),
+    }
+}
+
+///Sends the error to all registered reporters. Errors, that are reported by reporters themselves(for example, by alarm
+///of `ErrorStats`), are not sent, else they would be reported recursively.
+#[cfg(feature = "reporters")]
+pub fn report(error:&dyn ReportedError) {
+    if is_reporting() {
+        return;
+    }
+
+    let reporters=registered();
+
+    reporting(|| {
+        for reporter in reporters.iter() {
+            reporter.report(error);
+        }
+    })
+}
+
+///Flushes all registered reporters, so errors in buffers and queues of reporters are not lost, when the process exits.
+///All reporters are flushed not longer than timeout, returns false, if some reporter has not written all errors.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///use nes::background::BackgroundReporter;
+///use nes::reporter::FileReporter;
+///use std::time::Duration;
+///
+///define_error!( WorkerError,
+///    QueueIsFull(size:usize) => "queue is full, size is {}"
+///);
+///
+/// # fn main() {
+///let path=std::env::temp_dir().join("nes_flush_reporters_example.log");
+/// # let _=std::fs::remove_file(&path);
+///nes::add_reporter(BackgroundReporter::new(FileReporter::new(&path), 1024));
+///
+///let error:WorkerError=create_err!(WorkerError::QueueIsFull, 1024);
+///error.report();
+///
+///assert!(nes::flush_reporters(Duration::from_secs(5)));
+///assert!(std::fs::read_to_string(&path).unwrap().contains("queue is full, size is 1024"));
+/// # }
+/// ```
+#[cfg(feature = "reporters")]
+pub fn flush_reporters(timeout:Duration) -> bool {
+    let deadline=Instant::now()+timeout;
+    let reporters=registered();
+
+    reporting(|| {
+        let mut flushed=true;
+
+        for reporter in reporters.iter() {
+            flushed&=reporter.flush(deadline.saturating_duration_since(Instant::now()));
+        }
+
+        flushed
+    })
+}
+
+///Time, that reporters are flushed before exit by `FlushOnExit` and by the panic hook.
+#[cfg(feature = "reporters")]
+pub const EXIT_FLUSH_TIMEOUT:Duration = Duration::from_secs(2);
+
+///Result of main(), that flushes registered reporters, when main() returns, so errors in queues of reporters are not lost.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate nes;
+/// # use nes::{ErrorInfo,ErrorInfoTrait};
+///use nes::FlushOnExit;
+///use nes::background::BackgroundReporter;
+///use nes::reporter::StderrReporter;
+///
+///define_error!( WorkerError,
+///    QueueIsFull(size:usize) => "queue is full, size is {}"
+///);
+///
+///fn run() -> result![WorkerError] {
+///    let error:WorkerError=create_err!(WorkerError::QueueIsFull, 1024);
+///    error.report();
+///    ok!()
+///}
+///
+///fn main() -> FlushOnExit<result![WorkerError]> {
+///    nes::add_reporter(BackgroundReporter::new(StderrReporter, 1024));
+///    FlushOnExit(run())
+///}
+/// ```
+#[cfg(feature = "reporters")]
+pub struct FlushOnExit<T:Termination>(pub T);
+
+#[cfg(feature = "reporters")]
+impl<T:Termination> Termination for FlushOnExit<T> {
+    fn report(self) -> ExitCode {
+        //error of main() is written by T, then it is flushed with reported errors
+        let code=self.0.report();
+        flush_reporters(EXIT_FLUSH_TIMEOUT);
+        code
+    }
+}
+
+///Reporter, that writes errors to stderr by Display.
+#[cfg(feature = "reporters")]
+pub struct StderrReporter;
+
+#[cfg(feature = "reporters")]
+impl Reporter for StderrReporter {
+    fn report(&self, error:&dyn ReportedError) {
+        eprintln!("{}", error);
+    }
+}
+
+///Reporter, that appends errors with timestamps to the file and rotates it, when it exceeds max size.
+#[cfg(feature = "reporters")]
+pub struct FileReporter {
+    path:PathBuf,
+    max_size:u64,
+    rotations:usize,
+    file:Mutex<Option<File>>
+}
+
+#[cfg(feature = "reporters")]
+impl FileReporter {
+    ///Creates reporter, that writes to the file at path, default max size is 10 MiB, default count of rotated files is 5.
+    pub fn new<P:AsRef<Path>>(path:P) -> Self {
+        FileReporter {
+            path:path.as_ref().to_path_buf(),
+            max_size:10*1024*1024,
+            rotations:5,
+            file:Mutex::new(None)
+        }
+    }
+
+    ///Sets max size of the file in bytes, 0 disables rotation.
+    pub fn max_size(mut self, max_size:u64) -> Self {
+        self.max_size=max_size;
+        self
+    }
+
+    ///Sets count of rotated files(`file.1` .. `file.N`), that are kept. If it is 0, the file is truncated instead of rotation.
+    pub fn rotations(mut self, rotations:usize) -> Self {
+        self.rotations=rotations;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn rotated_path(&self, index:usize) -> PathBuf {
+        let mut path=self.path.clone().into_os_string();
+        path.push(format!(".{}", index));
+        PathBuf::from(path)
+    }
+
+    fn rotate(&self) {
+        if self.rotations==0 {
+            let _=fs::remove_file(&self.path);
+            return;
+        }
+
+        let _=fs::remove_file(self.rotated_path(self.rotations));
+
+        for index in (1..self.rotations).rev() {
+            let _=fs::rename(self.rotated_path(index), self.rotated_path(index+1));
+        }
+
+        let _=fs::rename(&self.path, self.rotated_path(1));
+    }
+
+    fn write(&self, file:&mut Option<File>, entry:&str) -> std::io::Result<()> {
+        if self.max_size>0 {
+            let size=match *file {
+                Some( ref file ) => file.metadata()?.len(),
+                None => fs::metadata(&self.path).map(|metadata| metadata.len()).unwrap_or(0),
+            };
+
+            if size>0 && size+entry.len() as u64>self.max_size {
+                *file=None;
+                self.rotate();
+            }
+        }
+
+        if file.is_none() {
+            *file=Some(OpenOptions::new().create(true).append(true).open(&self.path)?);
+        }
+
+        match *file {
+            Some( ref mut file ) => file.write_all(entry.as_bytes()),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "reporters")]
+impl Reporter for FileReporter {
+    fn report(&self, error:&dyn ReportedError) {
+        let entry=format!("[{}] {}\n", Timestamp(SystemTime::now()), error);
+
+        let mut file=match self.file.lock() {
+            Ok( file ) => file,
+            Err( poisoned ) => poisoned.into_inner(),
+        };
+
+        if self.write(&mut file, &entry).is_err() {
+            //file will be reopened by next report
+            *file=None;
+        }
+    }
+}
+
+//RFC 3339 timestamp in UTC, like 2019-03-02T14:05:09.123Z
+#[cfg(feature = "reporters")]
+pub(crate) struct Timestamp(pub(crate) SystemTime);
+
+#[cfg(feature = "reporters")]
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let duration=self.0.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let seconds=duration.as_secs();
+        let (days, time)=((seconds/86400) as i64, seconds%86400);
+
+        //days to civil date, see http://howardhinnant.github.io/date_algorithms.html
+        let z=days+719468;
+        let era=z.div_euclid(146097);
+        let day_of_era=z-era*146097;
+        let year_of_era=(day_of_era-day_of_era/1460+day_of_era/36524-day_of_era/146096)/365;
+        let day_of_year=day_of_era-(365*year_of_era+year_of_era/4-year_of_era/100);
+        let mp=(5*day_of_year+2)/153;
+        let day=day_of_year-(153*mp+2)/5+1;
+        let month=if mp<10 { mp+3 } else { mp-9 };
+        let year=year_of_era+era*400+if month<=2 { 1 } else { 0 };
+
+        write!(f, "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+            year, month, day, time/3600, time%3600/60, time%60, duration.subsec_millis())
+    }
+}
+
+//message of variant without ErrorInfo, for reporters, that write message and location separately
+#[cfg(feature = "reporters")]
+pub(crate) struct Message<'a>(pub(crate) &'a dyn ReportedError);
+
+#[cfg(feature = "reporters")]
+impl<'a> fmt::Display for Message<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt_message(f)
+    }
+}
+
+//locks mutex, that may be poisoned by panic of other thread, guarded data is used anyway
+#[cfg(any(feature = "reporters", feature = "symbolize"))]
+pub(crate) fn lock<T>(mutex:&::std::sync::Mutex<T>) -> ::std::sync::MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok( guard ) => guard,
+        Err( poisoned ) => poisoned.into_inner(),
+    }
+}
+
+//string literal of JSON with quotes, for reporters, that write JSON documents
+#[cfg(any(feature = "ecs", feature = "gelf"))]
+pub(crate) struct JsonString<'a>(pub(crate) &'a str);
+
+#[cfg(any(feature = "ecs", feature = "gelf"))]
+impl<'a> fmt::Display for JsonString<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "\"")?;
+
+        for c in self.0.chars() {
+            match c {
+                '"' => write!(f, "\\\"")?,
+                '\\' => write!(f, "\\\\")?,
+                '\n' => write!(f, "\\n")?,
+                '\r' => write!(f, "\\r")?,
+                '\t' => write!(f, "\\t")?,
+                c if (c as u32)<0x20 => write!(f, "\\u{:04x}", c as u32)?,
+                c => write!(f, "{}", c)?,
+            }
+        }
+
+        write!(f, "\"")
+    }
+}
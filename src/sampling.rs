@@ -0,0 +1,185 @@
+//!Sampling of reported errors, so frequent recoverable errors do not overwhelm external services like Sentry.
+//!
+//!`SampledReporter` wraps the reporter and passes to it only sampled errors. Rule of sampling is chosen by variant of the error,
+//!then by its severity, then default rule is used. Errors are counted per variant, so `Sampling::FirstThenOneIn { first:10, one_in:100 }`
+//!reports the first 10 errors of each variant and then each 100th. Count of errors, that are not passed, is returned by `dropped()`.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait,Severity};
+//!use nes::reporter::{Reporter,ReportedError};
+//!use nes::sampling::{SampledReporter,Sampling};
+//!use std::sync::atomic::{AtomicUsize,Ordering};
+//!
+//!define_error!( CacheError,
+//!    #[nes(severity = Warning)]
+//!    Miss(key:String) => "key {} is not cached",
+//!    Corrupted(key:String) => "entry {} is corrupted"
+//!);
+//!
+//!static SENT:AtomicUsize=AtomicUsize::new(0);
+//!
+//!struct SentryReporter;
+//!
+//!impl Reporter for SentryReporter {
+//!    fn report(&self, _error:&dyn ReportedError) {
+//!        SENT.fetch_add(1, Ordering::Relaxed);
+//!    }
+//!}
+//!
+//!fn main() {
+//!    let reporter=SampledReporter::new(SentryReporter)
+//!        .severity(Severity::Warning, Sampling::OneIn(100))
+//!        .variant("CacheError", "Corrupted", Sampling::FirstThenOneIn { first:3, one_in:10 });
+//!
+//!    for index in 0..1000 {
+//!        let miss:CacheError=create_err!(CacheError::Miss, index.to_string());
+//!        reporter.report(&miss);
+//!
+//!        let corrupted:CacheError=create_err!(CacheError::Corrupted, index.to_string());
+//!        reporter.report(&corrupted);
+//!    }
+//!
+//!    //10 misses, 3 first and then 99 of 997 corrupted entries
+//!    assert_eq!(SENT.load(Ordering::Relaxed), 10+3+99);
+//!    assert_eq!(reporter.dropped(), 2000-112);
+//!}
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64,Ordering};
+use std::time::Duration;
+
+use Severity;
+use reporter::{Reporter,ReportedError};
+
+///Rule of sampling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sampling {
+    ///Each error is reported.
+    All,
+    ///Errors are not reported.
+    Nothing,
+    ///The first error and then each Nth error of variant are reported.
+    OneIn(u64),
+    ///The first K errors of variant are reported, then each Nth of the rest.
+    FirstThenOneIn {
+        first:u64,
+        one_in:u64
+    }
+}
+
+impl Sampling {
+    //returns true, if the error with index among errors of its variant is sampled
+    fn is_sampled(self, index:u64) -> bool {
+        match self {
+            Sampling::All => true,
+            Sampling::Nothing => false,
+            Sampling::OneIn(one_in) => index.is_multiple_of(one_in.max(1)),
+            Sampling::FirstThenOneIn { first, one_in } => index<first || (index-first)%one_in.max(1)==one_in.max(1)-1,
+        }
+    }
+}
+
+///Reporter, that passes to the wrapped reporter only sampled errors.
+pub struct SampledReporter<R> {
+    reporter:R,
+    default:Sampling,
+    by_severity:Vec<(Severity, Sampling)>,
+    by_variant:Vec<(&'static str, &'static str, Sampling)>,
+    counts:Mutex<HashMap<(&'static str, &'static str), u64>>,
+    dropped:AtomicU64
+}
+
+impl<R:Reporter> SampledReporter<R> {
+    ///Wraps the reporter, by default all errors are reported.
+    pub fn new(reporter:R) -> Self {
+        SampledReporter {
+            reporter,
+            default:Sampling::All,
+            by_severity:Vec::new(),
+            by_variant:Vec::new(),
+            counts:Mutex::new(HashMap::new()),
+            dropped:AtomicU64::new(0)
+        }
+    }
+
+    ///Sets rule for errors, that have no rule for their variant and severity.
+    pub fn default(mut self, sampling:Sampling) -> Self {
+        self.default=sampling;
+        self
+    }
+
+    ///Sets rule for errors of the severity, that have no rule for their variant.
+    pub fn severity(mut self, severity:Severity, sampling:Sampling) -> Self {
+        self.by_severity.retain(|rule| rule.0!=severity);
+        self.by_severity.push((severity, sampling));
+        self
+    }
+
+    ///Sets rule for errors of the variant.
+    pub fn variant(mut self, error_name:&'static str, variant_name:&'static str, sampling:Sampling) -> Self {
+        self.by_variant.retain(|rule| (rule.0, rule.1)!=(error_name, variant_name));
+        self.by_variant.push((error_name, variant_name, sampling));
+        self
+    }
+
+    ///Returns count of errors, that are not passed to the reporter.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    pub fn reporter(&self) -> &R {
+        &self.reporter
+    }
+
+    fn sampling(&self, error:&dyn ReportedError) -> Sampling {
+        if let Some( rule )=self.by_variant.iter().find(|rule| rule.0==error.error_name() && rule.1==error.variant_name()) {
+            return rule.2;
+        }
+
+        let severity=error.severity();
+
+        match self.by_severity.iter().find(|rule| rule.0==severity) {
+            Some( rule ) => rule.1,
+            None => self.default,
+        }
+    }
+}
+
+impl<R:Reporter> Reporter for SampledReporter<R> {
+    fn report(&self, error:&dyn ReportedError) {
+        let sampling=self.sampling(error);
+
+        let sampled=match sampling {
+            Sampling::All => true,
+            Sampling::Nothing => false,
+            sampling => {
+                let mut counts=match self.counts.lock() {
+                    Ok( counts ) => counts,
+                    Err( poisoned ) => poisoned.into_inner(),
+                };
+
+                let count=counts.entry((error.error_name(), error.variant_name())).or_insert(0);
+                let index=*count;
+                *count+=1;
+
+                sampling.is_sampled(index)
+            },
+        };
+
+        if sampled {
+            self.reporter.report(error);
+        }else{
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn flush(&self, timeout:Duration) -> bool {
+        self.reporter.flush(timeout)
+    }
+}
@@ -0,0 +1,258 @@
+//!Serialization of errors and JSON Schema of serialized errors(feature "serde").
+//!
+//!Errors, that are defined by define_error!(), implement serde::Serialize, so service can return them in responses.
+//!Each error is serialized as object:
+//!
+//! ```text
+//!{
+//!  "error": "CommonError",
+//!  "variant": "IncorrectExtension",
+//!  "code": null,
+//!  "location": { "file": "main/src/main.rs", "line": 12, "col": 5 },
+//!  "message": "main/src/main.rs 12:5\nfile \"a.txt\" has incorrect extension \"txt\"",
+//!  "fields": { "file_name": "a.txt", "extension": "txt" }
+//!}
+//! ```
+//!
+//!Fields, that implement Serialize, are serialized by it(nes errors in fields become nested objects),
+//!other fields are serialized as strings by Display or, if they have no Display, by Debug.
+//!
+//!`schema()` of error returns JSON Schema(draft 2020-12) of this object, consumers of your API can validate error payloads by it.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!extern crate serde_json;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!
+//!define_error!( ReadFileError,
+//!    IOError(io_error:Box<std::io::Error>) => "I/O Error: {}"
+//!);
+//!
+//!define_error!( CommonError,
+//!    ReadFileError(read_file_error:Box<ReadFileError>) => "read file error {}",
+//!    IncorrectExtension(file_name:String, extension:String) => "file \"{}\" has incorrect extension \"{}\""
+//!);
+//!
+//!fn main() {
+//!    let error:CommonError=create_err!(CommonError::IncorrectExtension, "a.txt".to_string(), "txt".to_string());
+//!    println!("{}",serde_json::to_string(&error).unwrap());
+//!
+//!    println!("{}",CommonError::schema());
+//!}
+//! ```
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap,HashMap};
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Arc;
+
+///This trait is implemented by define_error!() for all errors.
+pub trait ErrorSchema {
+    ///Pushes definitions of this error and of nes errors in its fields to `defs`, if they are not pushed yet.
+    fn push_schema_defs(defs:&mut Vec<(&'static str, String)>);
+
+    fn error_name() -> &'static str;
+
+    ///Returns JSON Schema of serialized error.
+    fn schema() -> String {
+        let mut defs=vec![("Location", LOCATION_SCHEMA.to_string())];
+        Self::push_schema_defs(&mut defs);
+
+        let defs=defs.iter().map(|def| format!("{}:{}", escape(def.0), def.1)).collect::<Vec<String>>();
+
+        format!(
+            "{{\"$schema\":\"https://json-schema.org/draft/2020-12/schema\",\"title\":{},\"$ref\":\"#/$defs/{}\",\"$defs\":{{{}}}}}",
+            escape(Self::error_name()), Self::error_name(), defs.join(",")
+        )
+    }
+}
+
+impl<T:ErrorSchema> ErrorSchema for Box<T> {
+    fn push_schema_defs(defs:&mut Vec<(&'static str, String)>) { T::push_schema_defs(defs) }
+    fn error_name() -> &'static str { T::error_name() }
+}
+
+impl<T:ErrorSchema> ErrorSchema for Arc<T> {
+    fn push_schema_defs(defs:&mut Vec<(&'static str, String)>) { T::push_schema_defs(defs) }
+    fn error_name() -> &'static str { T::error_name() }
+}
+
+///JSON Schema of types, that are often used as fields. Implement it for your types to get precise schema of fields.
+pub trait JsonType {
+    fn json_schema() -> String;
+}
+
+macro_rules! impl_json_type{
+    ( $schema:expr; $( $t:ty ),* ) => {
+        $(
+            impl JsonType for $t {
+                fn json_schema() -> String { $schema.to_string() }
+            }
+        )*
+    };
+}
+
+impl_json_type!("{\"type\":\"string\"}"; String, str, char, PathBuf);
+impl_json_type!("{\"type\":\"integer\"}"; u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+impl_json_type!("{\"type\":\"number\"}"; f32, f64);
+impl_json_type!("{\"type\":\"boolean\"}"; bool);
+impl_json_type!("{\"type\":\"null\"}"; ());
+
+impl<'a, T:JsonType + ToOwned + ?Sized> JsonType for Cow<'a, T> {
+    fn json_schema() -> String { T::json_schema() }
+}
+
+impl<T:JsonType + ?Sized> JsonType for &T {
+    fn json_schema() -> String { T::json_schema() }
+}
+
+impl<T:JsonType + ?Sized> JsonType for Box<T> {
+    fn json_schema() -> String { T::json_schema() }
+}
+
+impl<T:JsonType + ?Sized> JsonType for Rc<T> {
+    fn json_schema() -> String { T::json_schema() }
+}
+
+impl<T:JsonType + ?Sized> JsonType for Arc<T> {
+    fn json_schema() -> String { T::json_schema() }
+}
+
+impl<T:JsonType> JsonType for Option<T> {
+    fn json_schema() -> String { format!("{{\"anyOf\":[{},{{\"type\":\"null\"}}]}}", T::json_schema()) }
+}
+
+impl<T:JsonType> JsonType for Vec<T> {
+    fn json_schema() -> String { format!("{{\"type\":\"array\",\"items\":{}}}", T::json_schema()) }
+}
+
+impl<T:JsonType> JsonType for [T] {
+    fn json_schema() -> String { format!("{{\"type\":\"array\",\"items\":{}}}", T::json_schema()) }
+}
+
+impl<K, V:JsonType> JsonType for HashMap<K, V> {
+    fn json_schema() -> String { format!("{{\"type\":\"object\",\"additionalProperties\":{}}}", V::json_schema()) }
+}
+
+impl<K, V:JsonType> JsonType for BTreeMap<K, V> {
+    fn json_schema() -> String { format!("{{\"type\":\"object\",\"additionalProperties\":{}}}", V::json_schema()) }
+}
+
+impl<T:JsonType> JsonType for Range<T> {
+    fn json_schema() -> String {
+        format!(
+            "{{\"type\":\"object\",\"properties\":{{\"start\":{0},\"end\":{0}}},\"required\":[\"start\",\"end\"]}}",
+            T::json_schema()
+        )
+    }
+}
+
+const LOCATION_SCHEMA:&str = "{\"type\":\"object\",\"properties\":{\"file\":{\"type\":\"string\"},\"line\":{\"type\":\"integer\"},\"col\":{\"type\":\"integer\"},\"span_id\":{\"type\":\"integer\"},\"trace_id\":{\"type\":\"string\"},\"host\":{\"type\":\"string\"},\"pid\":{\"type\":\"integer\"}},\"required\":[\"file\",\"line\",\"col\"]}";
+
+///Returns JSON string literal with escaped characters.
+pub fn escape(text:&str) -> String {
+    let mut escaped=String::with_capacity(text.len()+2);
+    escaped.push('"');
+
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32)<0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+///Returns schema of variant, `fields` are names of fields with their schemas. It is called by define_error!().
+#[doc(hidden)]
+pub fn variant_schema(error_name:&str, var_name:&str, fields:&[(&str, String)]) -> String {
+    let properties=fields.iter().map(|field| format!("{}:{}", escape(field.0), field.1)).collect::<Vec<String>>();
+    let required=fields.iter().map(|field| escape(field.0)).collect::<Vec<String>>();
+
+    format!(
+        concat!(
+            "{{\"type\":\"object\",\"properties\":{{",
+            "\"error\":{{\"const\":{}}},",
+            "\"variant\":{{\"const\":{}}},",
+            "\"code\":{{\"type\":[\"string\",\"null\"]}},",
+            "\"location\":{{\"$ref\":\"#/$defs/Location\"}},",
+            "\"message\":{{\"type\":\"string\"}},",
+            "\"fields\":{{\"type\":\"object\",\"properties\":{{{}}},\"required\":[{}],\"additionalProperties\":false}}",
+            "}},\"required\":[\"error\",\"variant\",\"code\",\"location\",\"message\",\"fields\"]}}"
+        ),
+        escape(error_name), escape(var_name), properties.join(","), required.join(",")
+    )
+}
+
+//Schema of field is selected by autoref: (&&&SchemaField::<T>::new()).nes_schema(defs) calls
+//SchemaViaNes if T is nes error, else SchemaViaJsonType, else SchemaViaSerialize(any value), else SchemaViaString,
+//because fields without Serialize are serialized as strings.
+//Method resolution tries &&SchemaField, &&&SchemaField, &SchemaField, then SchemaField as Self.
+#[doc(hidden)]
+pub struct SchemaField<T:?Sized>(PhantomData<Box<T>>);
+
+impl<T:?Sized> SchemaField<T> {
+    pub fn new() -> Self {
+        SchemaField(PhantomData)
+    }
+}
+
+impl<T:?Sized> Default for SchemaField<T> {
+    fn default() -> Self {
+        SchemaField::new()
+    }
+}
+
+#[doc(hidden)]
+pub trait SchemaViaNes {
+    fn nes_schema(&self, defs:&mut Vec<(&'static str, String)>) -> String;
+}
+
+impl<T:ErrorSchema> SchemaViaNes for &&SchemaField<T> {
+    fn nes_schema(&self, defs:&mut Vec<(&'static str, String)>) -> String {
+        T::push_schema_defs(defs);
+        format!("{{\"$ref\":\"#/$defs/{}\"}}", T::error_name())
+    }
+}
+
+#[doc(hidden)]
+pub trait SchemaViaJsonType {
+    fn nes_schema(&self, defs:&mut Vec<(&'static str, String)>) -> String;
+}
+
+impl<T:JsonType + ?Sized> SchemaViaJsonType for &&&SchemaField<T> {
+    fn nes_schema(&self, _defs:&mut Vec<(&'static str, String)>) -> String {
+        T::json_schema()
+    }
+}
+
+#[doc(hidden)]
+pub trait SchemaViaSerialize {
+    fn nes_schema(&self, _defs:&mut Vec<(&'static str, String)>) -> String {
+        "{}".to_string()
+    }
+}
+
+impl<T: ::__private::serde::Serialize + ?Sized> SchemaViaSerialize for &SchemaField<T> {}
+
+#[doc(hidden)]
+pub trait SchemaViaString {
+    fn nes_schema(&self, _defs:&mut Vec<(&'static str, String)>) -> String {
+        "{\"type\":\"string\"}".to_string()
+    }
+}
+
+impl<T:?Sized> SchemaViaString for SchemaField<T> {}
@@ -0,0 +1,76 @@
+//!Error of `error_scope!()`, that adds located context to any error, that escapes the block.
+
+use std::error::Error;
+use std::fmt;
+
+use {ErrorInfo,ErrorTrail,TrailItem};
+
+///Boxed error, that is stored by ScopeError by default.
+pub type BoxError = Box<dyn Error + Send + Sync>;
+
+///Error, that escapes `error_scope!()`, with the message of scope and the location of error_scope!().
+pub struct ScopeError<E = BoxError> {
+    error_info:ErrorInfo,
+    message:String,
+    error:E
+}
+
+impl<E> ScopeError<E> {
+    pub fn new(error_info:ErrorInfo, message:String, error:E) -> Self {
+        ScopeError {
+            error_info,
+            message,
+            error
+        }
+    }
+
+    pub fn error_info(&self) -> &ErrorInfo { &self.error_info }
+    pub fn message(&self) -> &str { &self.message }
+    pub fn error(&self) -> &E { &self.error }
+    pub fn into_error(self) -> E { self.error }
+
+    fn trail_item(&self) -> TrailItem {
+        TrailItem {
+            error_name:"ScopeError",
+            variant_name:"Scope",
+            error_info:self.error_info
+        }
+    }
+}
+
+impl<E:fmt::Display> fmt::Display for ScopeError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}\n{}\n{}", self.error_info, self.message, self.error)
+    }
+}
+
+impl<E:fmt::Debug> fmt::Debug for ScopeError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}\nScopeError message:{:?} error:{:?}", self.error_info, self.message, self.error)
+    }
+}
+
+impl Error for ScopeError<BoxError> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&*self.error)
+    }
+}
+
+impl<E:Error + ErrorTrail + 'static> Error for ScopeError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl ErrorTrail for ScopeError<BoxError> {
+    fn push_error_trail(&self, trail:&mut Vec<TrailItem>) {
+        trail.push(self.trail_item());
+    }
+}
+
+impl<E:ErrorTrail> ErrorTrail for ScopeError<E> {
+    fn push_error_trail(&self, trail:&mut Vec<TrailItem>) {
+        trail.push(self.trail_item());
+        self.error.push_error_trail(trail);
+    }
+}
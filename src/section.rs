@@ -0,0 +1,248 @@
+//!Sections of reports: notes, suggestions, warnings and related locations, like sections of color-eyre.
+//!
+//!Variants get sections by metadata `#[nes(note = "...", help = "...", warning = "...")]`, code, that handles the error,
+//!adds sections at runtime by `error.with_note(..)`, `with_help(..)`, `with_warning(..)` and `with_related(error_info, ..)`,
+//!they wrap error into `WithSections`, that derefs to the error. `nes::report::VerboseReport` writes the chain with lines
+//!of source code and all sections after it.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use nes::report::VerboseReport;
+//!use nes::section::{AddSection,WithSections};
+//!
+//!define_error!( ServerError,
+//!    #[nes(help = "stop other server or change port in config", note = "ports below 1024 need root")]
+//!    PortIsBusy(port:u16) => "port {} is busy"
+//!);
+//!
+//!fn bind(port:u16, config:&ErrorInfo) -> Result<(), WithSections<ServerError>> {
+//!    let error:ServerError=create_err!(ServerError::PortIsBusy, port);
+//!    Err(error.with_warning("server is not started").with_related(*config, "port is set here"))
+//!}
+//!
+//!fn main() {
+//!    let config=error_info!();
+//!    let error=bind(80, &config).unwrap_err();
+//!    let report=VerboseReport::new(&error).to_string();
+//!
+//!    assert!(report.contains("port 80 is busy\n"));
+//!    assert!(report.contains("\nnote: ports below 1024 need root"));
+//!    assert!(report.contains("\nhelp: stop other server or change port in config"));
+//!    assert!(report.contains("\nwarning: server is not started"));
+//!    assert!(report.contains("\nrelated: port is set here\n  at "));
+//!}
+//! ```
+//!
+//!Output:
+//!
+//! ```text
+//!main/src/main.rs 13:27
+//!   13 |     let error:ServerError=create_err!(ServerError::PortIsBusy, port);
+//!      |                           ^
+//!port 80 is busy
+//!
+//!note: ports below 1024 need root
+//!help: stop other server or change port in config
+//!warning: server is not started
+//!related: port is set here
+//!  at main/src/main.rs 18:16
+//! ```
+
+use std::any::{Any,TypeId};
+use std::error::Error;
+use std::fmt;
+use std::ops::{Deref,DerefMut};
+
+use {ErrorInfo,ErrorMetadata,ErrorTrail,Severity,TrailItem};
+use context::ContextItem;
+use report;
+
+///Label of section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SectionKind {
+    Note,
+    Help,
+    Warning,
+    Related
+}
+
+impl SectionKind {
+    pub fn label(&self) -> &'static str {
+        match *self {
+            SectionKind::Note => "note",
+            SectionKind::Help => "help",
+            SectionKind::Warning => "warning",
+            SectionKind::Related => "related",
+        }
+    }
+}
+
+///Labeled text of report, related section has location.
+#[derive(Clone)]
+pub struct Section {
+    pub kind:SectionKind,
+    pub text:String,
+    pub location:Option<ErrorInfo>
+}
+
+impl fmt::Display for Section {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.kind.label(), self.text)?;
+
+        match self.location {
+            Some( ref location ) => write!(f, "\n  at {}", location),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Debug for Section {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut debug=f.debug_struct("Section");
+        debug.field("kind", &self.kind).field("text", &self.text);
+
+        if let Some( ref location )=self.location {
+            debug.field("location", &::__private::DisplayAsDebug(location));
+        }
+
+        debug.finish()
+    }
+}
+
+///Error with sections, that are added at runtime, it is created by methods of `AddSection`.
+pub struct WithSections<E> {
+    error:E,
+    sections:Vec<Section>
+}
+
+impl<E> WithSections<E> {
+    pub fn new(error:E) -> Self {
+        WithSections {
+            error,
+            sections:Vec::new()
+        }
+    }
+
+    pub fn with_section<T:Into<String>>(mut self, kind:SectionKind, text:T) -> Self {
+        self.sections.push(Section { kind, text:text.into(), location:None });
+        self
+    }
+
+    pub fn with_note<T:Into<String>>(self, text:T) -> Self {
+        self.with_section(SectionKind::Note, text)
+    }
+
+    pub fn with_help<T:Into<String>>(self, text:T) -> Self {
+        self.with_section(SectionKind::Help, text)
+    }
+
+    pub fn with_warning<T:Into<String>>(self, text:T) -> Self {
+        self.with_section(SectionKind::Warning, text)
+    }
+
+    ///Adds related location, for example, where the value, that has caused the error, is set.
+    pub fn with_related<T:Into<String>>(mut self, location:ErrorInfo, text:T) -> Self {
+        self.sections.push(Section { kind:SectionKind::Related, text:text.into(), location:Some(location) });
+        self
+    }
+
+    pub fn sections(&self) -> &[Section] { &self.sections }
+    pub fn error(&self) -> &E { &self.error }
+    pub fn into_error(self) -> E { self.error }
+}
+
+impl<E> Deref for WithSections<E> {
+    type Target = E;
+
+    fn deref(&self) -> &E { &self.error }
+}
+
+impl<E> DerefMut for WithSections<E> {
+    fn deref_mut(&mut self) -> &mut E { &mut self.error }
+}
+
+impl<E:fmt::Display> fmt::Display for WithSections<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.error)?;
+
+        if report::is_verbose() {
+            return Ok(());
+        }
+
+        for section in self.sections.iter() {
+            write!(f, "\n{}", section)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<E:fmt::Debug> fmt::Debug for WithSections<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.error)?;
+
+        for section in self.sections.iter() {
+            write!(f, "\n{}:{:?}", section.kind.label(), section.text)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<E:Error> Error for WithSections<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.error.source()
+    }
+}
+
+impl<E:ErrorTrail> ErrorTrail for WithSections<E> {
+    fn push_error_trail(&self, trail:&mut Vec<TrailItem>) {
+        self.error.push_error_trail(trail)
+    }
+}
+
+impl<E:ErrorMetadata> ErrorMetadata for WithSections<E> {
+    fn error_name(&self) -> &'static str { self.error.error_name() }
+    fn variant_name(&self) -> &'static str { self.error.variant_name() }
+    fn location(&self) -> ErrorInfo { self.error.location() }
+    fn code(&self) -> Option<&'static str> { self.error.code() }
+    fn help(&self) -> Option<&'static str> { self.error.help() }
+    fn url(&self) -> Option<&'static str> { self.error.url() }
+    fn severity(&self) -> Severity { self.error.severity() }
+    fn fmt_message(&self, f: &mut fmt::Formatter) -> fmt::Result { self.error.fmt_message(f) }
+    fn context(&self) -> &[ContextItem] { self.error.context() }
+    fn note(&self) -> Option<&'static str> { self.error.note() }
+    fn warning(&self) -> Option<&'static str> { self.error.warning() }
+    fn sections(&self) -> &[Section] { &self.sections }
+    fn typed_context(&self, type_id:TypeId) -> Option<&(dyn Any + Send + Sync)> { self.error.typed_context(type_id) }
+}
+
+///Adds sections to nes errors.
+pub trait AddSection: Sized {
+    fn with_note<T:Into<String>>(self, text:T) -> WithSections<Self>;
+    fn with_help<T:Into<String>>(self, text:T) -> WithSections<Self>;
+    fn with_warning<T:Into<String>>(self, text:T) -> WithSections<Self>;
+    fn with_related<T:Into<String>>(self, location:ErrorInfo, text:T) -> WithSections<Self>;
+}
+
+impl<E:ErrorMetadata> AddSection for E {
+    fn with_note<T:Into<String>>(self, text:T) -> WithSections<Self> {
+        WithSections::new(self).with_note(text)
+    }
+
+    fn with_help<T:Into<String>>(self, text:T) -> WithSections<Self> {
+        WithSections::new(self).with_help(text)
+    }
+
+    fn with_warning<T:Into<String>>(self, text:T) -> WithSections<Self> {
+        WithSections::new(self).with_warning(text)
+    }
+
+    fn with_related<T:Into<String>>(self, location:ErrorInfo, text:T) -> WithSections<Self> {
+        WithSections::new(self).with_related(location, text)
+    }
+}
@@ -0,0 +1,313 @@
+//!ErrorInfo with bounded stack capture(feature "stack"), middle ground between file:line and full Backtrace.
+//!
+//!`nes::stack::ErrorInfo<N>` stores location and up to N(16 by default) raw return addresses, that are read from the chain
+//!of frame pointers at creation. It does not unwind and does not allocate, capture costs about the same as copying of N words,
+//!so it may be used in hot code. Addresses are resolved only by Display: each frame is written as address, module and offset in it
+//!(and name of symbol, if it is exported), so the report is enough for `addr2line -e module offset` on the machine with debug info.
+//!With feature "symbolize" `symbolize()` resolves frames to functions, files and lines, see `nes::symbolize`, and
+//!`nes::report::VerboseReport` writes resolved frames under location of each error of the chain, like backtrace of panic.
+//!
+//!Frame pointers are walked on x86_64 and aarch64 Linux, on other targets frames are empty. Build with
+//!`RUSTFLAGS="-C force-frame-pointers=yes"`, else functions without frame pointers are skipped and the chain may be cut.
+//!Walking never leaves the stack of current thread, so it is safe in both cases.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::ErrorInfoTrait;
+//!use nes::stack::ErrorInfo;
+//!
+//!define_error!( WorkerError,
+//!    QueueIsFull(size:usize) => "queue is full, size is {}"
+//!);
+//!
+//!fn main() {
+//!    let mut error:WorkerError=create_err!(WorkerError::QueueIsFull, 1024);
+//!    assert!(error.get_error_info().frames().len()<=16);
+//!    println!("{}",error);
+//!}
+//! ```
+//!
+//!Output:
+//!
+//! ```text
+//!main/src/main.rs 13:31
+//!    #0 0x000055d0c3a1b2c4 worker+0x1b2c4
+//!    #1 0x000055d0c3a1a0f7 worker+0x1a0f7
+//!    #2 0x00007f3e2c829d90 libc.so.6+0x29d90 __libc_start_call_main+0x80
+//!queue is full, size is 1024
+//! ```
+
+use std::fmt;
+
+use {report,ErrorInfoTrait};
+#[cfg(feature = "symbolize")]
+use symbolize;
+
+///Default count of frames, that are captured.
+pub const DEFAULT_DEPTH:usize = 16;
+
+///ErrorInfo, that stores location and up to N return addresses of the stack.
+#[derive(Clone, Copy)]
+pub struct ErrorInfo<const N:usize = DEFAULT_DEPTH> {
+    file:&'static str,
+    line:u32,
+    col:u32,
+    frames:[usize; N],
+    len:usize
+}
+
+impl<const N:usize> ErrorInfo<N> {
+    ///Returns captured return addresses, the first is the nearest to the place, where the error has been created.
+    pub fn frames(&self) -> &[usize] {
+        &self.frames[..self.len]
+    }
+
+    ///Resolves frames to functions, files and lines by debug info, frames of capture and constructors of nes are skipped.
+    #[cfg(feature = "symbolize")]
+    pub fn symbolize(&self) -> Vec<symbolize::Frame> {
+        let frames=symbolize::resolve_all(self.frames());
+        //err!() creates ErrorInfo in closure, that is called by nes::__private::construct, so the closure is skipped too
+        let constructor=frames.iter().take(8).position(|frame| frame.symbols().first().is_some_and(|symbol| symbol.name().starts_with("nes::__private::construct")));
+
+        frames.into_iter()
+            .skip(constructor.map_or(0, |index| index+1))
+            .skip_while(|frame| frame.symbols().first().is_some_and(|symbol| is_capture(symbol.name())))
+            .collect()
+    }
+}
+
+#[cfg(feature = "symbolize")]
+fn is_capture(name:&str) -> bool {
+    name.starts_with("nes::stack::") || name.starts_with("<nes::stack::") || name.starts_with("nes::__private::")
+}
+
+//writes frames like backtrace of panic, frames of runtime before main are skipped,
+//returns false without writing, if there is no debug info
+#[cfg(feature = "symbolize")]
+fn fmt_symbolized(f: &mut fmt::Formatter, frames:&[symbolize::Frame]) -> Result<bool, fmt::Error> {
+    if !frames.iter().any(|frame| frame.is_resolved()) {
+        return Ok(false);
+    }
+
+    for (index, frame) in frames.iter().enumerate() {
+        if frame.symbols().iter().any(|symbol| symbol.name().contains("__rust_begin_short_backtrace")) {
+            break;
+        }
+
+        if !frame.is_resolved() {
+            write!(f, "\n    #{} {}", index, Frame(frame.address()))?;
+        }
+
+        for symbol in frame.symbols() {
+            write!(f, "\n    #{} {}", index, symbol.name())?;
+
+            if let (Some( file ), Some( line ))=(symbol.file(), symbol.line()) {
+                write!(f, "\n           at {}:{}:{}", file.display(), line, symbol.col().unwrap_or(0))?;
+            }
+        }
+    }
+
+    Ok(true)
+}
+
+impl<const N:usize> ErrorInfoTrait for ErrorInfo<N> {
+    fn new(file:&'static str, line:u32, col:u32 ) -> Self{
+        let mut frames=[0; N];
+        let len=capture(&mut frames);
+
+        ErrorInfo {
+            file,
+            line,
+            col,
+            frames,
+            len
+        }
+    }
+
+    fn file(&self) -> &'static str { self.file }
+    fn line(&self) -> u32 { self.line }
+    fn col(&self) -> u32 { self.col }
+}
+
+impl<const N:usize> fmt::Display for ErrorInfo<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        report::fmt_location(f, self.file, self.line, self.col)?;
+        report::fmt_source_line(f, self.file, self.line, self.col)?;
+
+        //addresses are different in each run
+        if report::is_snapshot_mode() {
+            return Ok(());
+        }
+
+        #[cfg(feature = "symbolize")]
+        {
+            if report::is_verbose() && fmt_symbolized(f, &self.symbolize())? {
+                return Ok(());
+            }
+        }
+
+        for (index, address) in self.frames().iter().enumerate() {
+            write!(f, "\n    #{} {}", index, Frame(*address))?;
+        }
+
+        Ok(())
+    }
+}
+
+///Reads return addresses from the chain of frame pointers into frames and returns their count.
+#[inline(never)]
+pub fn capture(frames:&mut [usize]) -> usize {
+    imp::capture(frames)
+}
+
+//frame is written as address, module with offset and exported symbol with offset
+struct Frame(usize);
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "0x{:016x}", self.0)?;
+
+        if let Some( module )=imp::module(self.0) {
+            write!(f, " {}+0x{:x}", module.name, self.0-module.base)?;
+
+            if let Some( (symbol, address) )=module.symbol {
+                write!(f, " {}+0x{:x}", symbol, self.0-address)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[allow(dead_code)]
+struct Module {
+    name:String,
+    base:usize,
+    symbol:Option<(String, usize)>
+}
+
+#[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+mod imp {
+    use std::cell::Cell;
+    use std::ffi::CStr;
+    use std::mem;
+    use std::path::Path;
+    use std::ptr;
+
+    use super::Module;
+    use super::super::__private::libc;
+
+    thread_local! {
+        static STACK_TOP:Cell<usize> = const { Cell::new(0) };
+    }
+
+    pub fn capture(frames:&mut [usize]) -> usize {
+        //all frames of callers are between current frame and the top of the stack
+        let marker=0u8;
+        let bottom=&marker as *const u8 as usize;
+        let top=stack_top();
+        let word=mem::size_of::<usize>();
+
+        let mut fp=frame_pointer();
+        let mut len=0;
+
+        while len<frames.len() {
+            if fp<bottom || !fp.is_multiple_of(word) || fp.saturating_add(2*word)>top {
+                break;
+            }
+
+            //frame record is saved frame pointer of caller and return address
+            let (next, address)=unsafe {
+                (ptr::read_volatile(fp as *const usize), ptr::read_volatile((fp+word) as *const usize))
+            };
+
+            if address==0 {
+                break;
+            }
+
+            frames[len]=address;
+            len+=1;
+
+            //stack grows down, so frames of callers have greater addresses
+            if next<=fp {
+                break;
+            }
+
+            fp=next;
+        }
+
+        len
+    }
+
+    #[inline(always)]
+    fn frame_pointer() -> usize {
+        let fp:usize;
+
+        #[cfg(target_arch = "x86_64")]
+        unsafe { ::std::arch::asm!("mov {}, rbp", out(reg) fp, options(nomem, nostack, preserves_flags)); }
+        #[cfg(target_arch = "aarch64")]
+        unsafe { ::std::arch::asm!("mov {}, x29", out(reg) fp, options(nomem, nostack, preserves_flags)); }
+
+        fp
+    }
+
+    //top of the stack of current thread, it is got once for each thread, 0 if it is unknown
+    fn stack_top() -> usize {
+        STACK_TOP.with(|top| {
+            if top.get()==0 {
+                top.set(get_stack_top());
+            }
+
+            top.get()
+        })
+    }
+
+    fn get_stack_top() -> usize {
+        unsafe {
+            let mut attr:libc::pthread_attr_t=mem::zeroed();
+
+            if libc::pthread_getattr_np(libc::pthread_self(), &mut attr)!=0 {
+                return 0;
+            }
+
+            let mut address=ptr::null_mut();
+            let mut size=0;
+            let result=libc::pthread_attr_getstack(&attr, &mut address, &mut size);
+            libc::pthread_attr_destroy(&mut attr);
+
+            if result!=0 { 0 } else { address as usize+size }
+        }
+    }
+
+    pub fn module(address:usize) -> Option<Module> {
+        let mut info:libc::Dl_info=unsafe { mem::zeroed() };
+
+        if unsafe { libc::dladdr(address as *const libc::c_void, &mut info) }==0 || info.dli_fname.is_null() {
+            return None;
+        }
+
+        let path=unsafe { CStr::from_ptr(info.dli_fname) }.to_string_lossy();
+        let name=Path::new(&*path).file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_else(|| path.to_string());
+
+        let symbol=if info.dli_sname.is_null() {
+            None
+        }else{
+            let symbol=unsafe { CStr::from_ptr(info.dli_sname) }.to_string_lossy().into_owned();
+            Some((symbol, info.dli_saddr as usize))
+        };
+
+        Some(Module { name, base:info.dli_fbase as usize, symbol })
+    }
+}
+
+#[cfg(not(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64"))))]
+mod imp {
+    use super::Module;
+
+    pub fn capture(_frames:&mut [usize]) -> usize { 0 }
+
+    pub fn module(_address:usize) -> Option<Module> { None }
+}
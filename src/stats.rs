@@ -0,0 +1,283 @@
+//!Rates of errors over sliding window(feature "stats").
+//!
+//!`ErrorStats` counts occurrences of errors per variant and location, counts are kept in ring of buckets, so count in the window
+//!slides with time without storing each occurrence. Stats are queried at runtime, for example, by /health endpoint, that
+//!reports service as degraded, when some failure happens too often, and spikes call alarms, that are set by `on_spike`.
+//!
+//!Stats receive errors as reporter: `nes::add_reporter(nes::stats::global())` counts all reported errors in global stats
+//!with window of one minute, own stats with other window are shared by Arc. Errors may be counted without reporting by `record`.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use nes::stats::ErrorStats;
+//!use std::sync::Arc;
+//!use std::sync::atomic::{AtomicUsize,Ordering};
+//!use std::time::Duration;
+//!
+//!define_error!( DbError,
+//!    Timeout(millis:u64) => "query timed out after {} ms"
+//!);
+//!
+//!static ALARMS:AtomicUsize=AtomicUsize::new(0);
+//!
+//!fn main() {
+//!    let stats=Arc::new(ErrorStats::new(Duration::from_secs(60)));
+//!    stats.on_spike("DbError", "Timeout", 3, |spike| {
+//!        assert_eq!(spike.count, 3);
+//!        ALARMS.fetch_add(1, Ordering::Relaxed);
+//!    });
+//!    nes::add_reporter(stats.clone());
+//!
+//!    for _ in 0..5 {
+//!        let error:DbError=create_err!(DbError::Timeout, 500);
+//!        error.report();
+//!    }
+//!
+//!    assert_eq!(stats.count("DbError", "Timeout"), 5);
+//!    assert_eq!(stats.entries()[0].count, 5);
+//!    assert_eq!(ALARMS.load(Ordering::Relaxed), 1);
+//!}
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc,Mutex,MutexGuard,OnceLock};
+use std::time::{Duration,Instant};
+
+use ErrorInfoTrait;
+use reporter::{Reporter,ReportedError};
+
+//count of buckets of the window, count in the window is precise up to one bucket
+const BUCKETS:usize = 60;
+
+///Counts of errors with the same variant and location.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatsEntry {
+    pub error_name:&'static str,
+    pub variant_name:&'static str,
+    pub file:&'static str,
+    pub line:u32,
+    ///Count in the window.
+    pub count:u64,
+    ///Count since creation of stats or their clearing.
+    pub total:u64
+}
+
+///Count of errors of variant in the window reached threshold of alarm.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spike {
+    pub error_name:&'static str,
+    pub variant_name:&'static str,
+    pub count:u64,
+    pub window:Duration
+}
+
+type Key = (&'static str, &'static str, &'static str, u32);
+
+struct Counter {
+    total:u64,
+    //tick and count of each bucket, bucket is stale, if its tick is out of window
+    buckets:[(u64, u64); BUCKETS]
+}
+
+impl Counter {
+    fn add(&mut self, tick:u64) {
+        let bucket=&mut self.buckets[(tick%BUCKETS as u64) as usize];
+
+        if bucket.0!=tick {
+            *bucket=(tick, 0);
+        }
+
+        bucket.1+=1;
+        self.total+=1;
+    }
+
+    fn count(&self, tick:u64, ticks:u64) -> u64 {
+        self.buckets.iter().filter(|bucket| bucket.1>0 && bucket.0<=tick && tick-bucket.0<ticks).map(|bucket| bucket.1).sum()
+    }
+}
+
+struct Alarm {
+    error_name:&'static str,
+    variant_name:&'static str,
+    threshold:u64,
+    //alarm is called once, when count reaches threshold, and again, when count falls below threshold and reaches it again
+    raised:bool,
+    callback:Arc<dyn Fn(&Spike) + Send + Sync>
+}
+
+struct State {
+    counters:HashMap<Key, Counter>,
+    alarms:Vec<Alarm>
+}
+
+///Registry of counts of errors over sliding window.
+pub struct ErrorStats {
+    window:Duration,
+    start:Instant,
+    state:Mutex<State>
+}
+
+impl ErrorStats {
+    ///Creates stats, that count errors over the window, it is divided into 60 buckets.
+    pub fn new(window:Duration) -> Self {
+        ErrorStats {
+            window,
+            start:Instant::now(),
+            state:Mutex::new(State {
+                counters:HashMap::new(),
+                alarms:Vec::new()
+            })
+        }
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    ///Counts the error by its variant and location.
+    pub fn record(&self, error:&dyn ReportedError) {
+        let location=error.location();
+        let key=(error.error_name(), error.variant_name(), location.file(), location.line());
+        let tick=self.tick();
+
+        let spikes=self.with_state(|state| {
+            state.counters.entry(key).or_insert_with(|| Counter { total:0, buckets:[(0, 0); BUCKETS] }).add(tick);
+
+            let count=count_of(&state.counters, key.0, key.1, tick, BUCKETS as u64);
+            let mut spikes=Vec::new();
+
+            for alarm in state.alarms.iter_mut().filter(|alarm| (alarm.error_name, alarm.variant_name)==(key.0, key.1)) {
+                if count<alarm.threshold {
+                    alarm.raised=false;
+                }else if !alarm.raised {
+                    alarm.raised=true;
+
+                    let spike=Spike {
+                        error_name:alarm.error_name,
+                        variant_name:alarm.variant_name,
+                        count,
+                        window:self.window
+                    };
+
+                    spikes.push((alarm.callback.clone(), spike));
+                }
+            }
+
+            spikes
+        });
+
+        //callbacks are called without lock, so they may query stats
+        for (callback, spike) in spikes {
+            callback(&spike);
+        }
+    }
+
+    ///Returns count of errors of the variant in the window at all locations.
+    pub fn count(&self, error_name:&str, variant_name:&str) -> u64 {
+        self.count_within(error_name, variant_name, self.window)
+    }
+
+    ///Returns count of errors of the variant in the last period, that is rounded up to bucket and is not longer than the window.
+    pub fn count_within(&self, error_name:&str, variant_name:&str, period:Duration) -> u64 {
+        let tick=self.tick();
+        let ticks=self.ticks(period);
+
+        self.with_state(|state| count_of(&state.counters, error_name, variant_name, tick, ticks))
+    }
+
+    ///Returns count of all errors in the window.
+    pub fn total_count(&self) -> u64 {
+        let tick=self.tick();
+        self.with_state(|state| state.counters.values().map(|counter| counter.count(tick, BUCKETS as u64)).sum())
+    }
+
+    ///Returns counts of each variant and location, that occurred since creation, the most frequent in the window are first.
+    pub fn entries(&self) -> Vec<StatsEntry> {
+        let tick=self.tick();
+
+        let mut entries=self.with_state(|state| {
+            state.counters.iter().map(|(key, counter)| StatsEntry {
+                error_name:key.0,
+                variant_name:key.1,
+                file:key.2,
+                line:key.3,
+                count:counter.count(tick, BUCKETS as u64),
+                total:counter.total
+            }).collect::<Vec<StatsEntry>>()
+        });
+
+        entries.sort_by(|a, b| b.count.cmp(&a.count).then(b.total.cmp(&a.total)));
+        entries
+    }
+
+    ///Sets alarm, that is called, when count of errors of the variant in the window reaches threshold. It is called again only
+    ///after count falls below threshold.
+    pub fn on_spike<F:Fn(&Spike) + Send + Sync + 'static>(&self, error_name:&'static str, variant_name:&'static str, threshold:u64, callback:F) {
+        self.with_state(|state| state.alarms.push(Alarm {
+            error_name,
+            variant_name,
+            threshold,
+            raised:false,
+            callback:Arc::new(callback)
+        }))
+    }
+
+    ///Removes all counts, alarms are kept.
+    pub fn clear(&self) {
+        self.with_state(|state| {
+            state.counters.clear();
+
+            for alarm in state.alarms.iter_mut() {
+                alarm.raised=false;
+            }
+        })
+    }
+
+    //number of bucket since creation of stats
+    fn tick(&self) -> u64 {
+        (self.start.elapsed().as_nanos()/self.bucket()) as u64
+    }
+
+    //count of buckets, that cover the period
+    fn ticks(&self, period:Duration) -> u64 {
+        period.as_nanos().div_ceil(self.bucket()).min(BUCKETS as u128) as u64
+    }
+
+    fn bucket(&self) -> u128 {
+        (self.window.as_nanos()/BUCKETS as u128).max(1)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        match self.state.lock() {
+            Ok( state ) => state,
+            Err( poisoned ) => poisoned.into_inner(),
+        }
+    }
+
+    fn with_state<T, F:FnOnce(&mut State) -> T>(&self, f:F) -> T {
+        f(&mut self.lock())
+    }
+}
+
+fn count_of(counters:&HashMap<Key, Counter>, error_name:&str, variant_name:&str, tick:u64, ticks:u64) -> u64 {
+    counters.iter()
+        .filter(|&(key, _)| key.0==error_name && key.1==variant_name)
+        .map(|(_, counter)| counter.count(tick, ticks))
+        .sum()
+}
+
+impl Reporter for ErrorStats {
+    fn report(&self, error:&dyn ReportedError) {
+        self.record(error)
+    }
+}
+
+///Returns global stats with window of one minute, they count errors, if they are registered as reporter.
+pub fn global() -> &'static ErrorStats {
+    static GLOBAL:OnceLock<ErrorStats> = OnceLock::new();
+    GLOBAL.get_or_init(|| ErrorStats::new(Duration::from_secs(60)))
+}
@@ -0,0 +1,171 @@
+//!Symbolication of captured frames on demand(feature "symbolize").
+//!
+//!`nes::stack::ErrorInfo` captures only return addresses, it is cheap. Names of functions, files and lines are read from debug info
+//!only, when they are requested: by `error_info.symbolize()` or `nes::symbolize::resolve(address)`, for example, when the error is
+//!rendered verbosely. Debug info is loaded by backtrace crate(addr2line and gimli) at the first request, resolved addresses
+//!are cached, so errors, that are created in a loop at the same place, are resolved once. Inlined functions give several symbols
+//!for one address.
+//!
+//! # Example
+//!
+//! ```
+//!extern crate nes;
+//!use nes::symbolize;
+//!
+//!#[inline(never)]
+//!fn load() -> usize {
+//!    //return address points after the call, so address inside of function is address of its start plus one
+//!    load as usize+1
+//!}
+//!
+//!fn main() {
+//!    let frame=symbolize::resolve(load());
+//!    assert!(frame.symbols().iter().any(|symbol| symbol.name().ends_with("load")));
+//!    println!("{}", frame);
+//!}
+//! ```
+//!
+//!Frames of `nes::stack::ErrorInfo` are resolved by `symbolize()`, frames of nes itself are skipped:
+//!
+//! ```ignore
+//!for frame in error.get_error_info().symbolize() {
+//!    println!("{}", frame);
+//!}
+//! ```
+//!
+//!Output:
+//!
+//! ```text
+//!worker::queue::push
+//!    at src/queue.rs:42:13
+//!worker::main
+//!    at src/main.rs:18:5
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path,PathBuf};
+use std::sync::{Arc,Mutex,OnceLock};
+
+use super::__private::backtrace;
+use reporter::lock;
+
+//count of cached addresses, cache is cleared, when it is full
+const CACHE_SIZE:usize = 4096;
+
+///Function, that contains the address, with its location in the source code, if debug info has it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    name:String,
+    file:Option<PathBuf>,
+    line:Option<u32>,
+    col:Option<u32>
+}
+
+impl Symbol {
+    ///Returns demangled name of function without hash.
+    pub fn name(&self) -> &str { &self.name }
+    pub fn file(&self) -> Option<&Path> { self.file.as_deref() }
+    pub fn line(&self) -> Option<u32> { self.line }
+    pub fn col(&self) -> Option<u32> { self.col }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+
+        if let Some( ref file )=self.file {
+            write!(f, "\n    at {}", file.display())?;
+
+            if let Some( line )=self.line {
+                write!(f, ":{}", line)?;
+            }
+
+            if let Some( col )=self.col {
+                write!(f, ":{}", col)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+///Return address with its symbols, symbols are empty, if the address is not found in debug info.
+#[derive(Debug, Clone)]
+pub struct Frame {
+    address:usize,
+    symbols:Arc<[Symbol]>
+}
+
+impl Frame {
+    pub fn address(&self) -> usize { self.address }
+    ///Returns symbols of the address, the first is the innermost inlined function.
+    pub fn symbols(&self) -> &[Symbol] { &self.symbols }
+    pub fn is_resolved(&self) -> bool { !self.symbols.is_empty() }
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.symbols.is_empty() {
+            return write!(f, "0x{:016x} <unknown>", self.address);
+        }
+
+        for (index, symbol) in self.symbols.iter().enumerate() {
+            if index>0 {
+                writeln!(f)?;
+            }
+
+            write!(f, "{}", symbol)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn cache() -> &'static Mutex<HashMap<usize, Arc<[Symbol]>>> {
+    static CACHE:OnceLock<Mutex<HashMap<usize, Arc<[Symbol]>>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+///Resolves return address to symbols by debug info of the module, that contains it.
+pub fn resolve(address:usize) -> Frame {
+    if let Some( symbols )=lock(cache()).get(&address) {
+        return Frame { address, symbols:symbols.clone() };
+    }
+
+    let mut symbols=Vec::new();
+
+    backtrace::resolve(address as *mut _, |symbol| {
+        let name=match symbol.name() {
+            Some( name ) => format!("{:#}", name),
+            None => "<unknown>".to_string(),
+        };
+
+        symbols.push(Symbol {
+            name,
+            file:symbol.filename().map(Path::to_path_buf),
+            line:symbol.lineno(),
+            col:symbol.colno()
+        });
+    });
+
+    let symbols:Arc<[Symbol]>=symbols.into();
+    let mut cache=lock(cache());
+
+    if cache.len()>=CACHE_SIZE {
+        cache.clear();
+    }
+
+    cache.insert(address, symbols.clone());
+    Frame { address, symbols }
+}
+
+///Resolves all return addresses, like frames of `nes::stack::ErrorInfo`.
+pub fn resolve_all(addresses:&[usize]) -> Vec<Frame> {
+    addresses.iter().map(|address| resolve(*address)).collect()
+}
+
+///Removes cached symbols, for example, after unloading of dynamic library.
+pub fn clear_cache() {
+    lock(cache()).clear();
+}
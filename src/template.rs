@@ -0,0 +1,145 @@
+//!Message templates of variants for translation tools.
+//!
+//!`ErrorName::message_templates()` returns format strings of all variants with names of their fields, so tools can generate
+//!.pot or .ftl files for translators without parsing of source code.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!
+//!define_error!( CommonError,
+//!    IncorrectExtension(file_name:String, extension:String) => "file \"{}\" has incorrect extension \"{}\"",
+//!    #[nes(fluent = "too-many-files")]
+//!    TooManyFiles(count:usize) => "too many files: {1}"
+//!);
+//!
+//!fn main() {
+//!    let templates=CommonError::message_templates();
+//!
+//!    assert_eq!(templates[0].placeholders(), vec!["file_name", "extension"]);
+//!    assert_eq!(templates[1].placeholders(), vec!["count"]);
+//!
+//!    for template in templates {
+//!        println!("{}", template.to_fluent());
+//!    }
+//!}
+//! ```
+//!
+//!Output:
+//!
+//! ```text
+//!CommonError-IncorrectExtension = file "{ $file_name }" has incorrect extension "{ $extension }"
+//!too-many-files = too many files: { $count }
+//! ```
+
+///Message template of variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageTemplate {
+    pub error_name:&'static str,
+    pub variant_name:&'static str,
+    ///Key of Fluent message, that is set by `#[nes(fluent = "key")]`.
+    pub fluent_key:Option<&'static str>,
+    ///Format string of message, argument 0 is ErrorInfo, fields start from 1.
+    pub template:&'static str,
+    pub fields:&'static [&'static str]
+}
+
+enum Part {
+    Text(&'static str),
+    Placeholder(&'static str),
+}
+
+impl MessageTemplate {
+    ///Returns key of Fluent message, default key is `ErrorName-VariantName`.
+    pub fn key(&self) -> String {
+        match self.fluent_key {
+            Some( key ) => key.to_string(),
+            None => format!("{}-{}", self.error_name, self.variant_name),
+        }
+    }
+
+    ///Returns names of fields in order of their placeholders in template, ErrorInfo is named "error_info".
+    pub fn placeholders(&self) -> Vec<&'static str> {
+        self.parts().into_iter().filter_map(|part| match part {
+            Part::Placeholder(name) => Some(name),
+            Part::Text(_) => None,
+        }).collect()
+    }
+
+    ///Returns Fluent message `key = text { $field }`, format specs of placeholders are skipped.
+    pub fn to_fluent(&self) -> String {
+        let mut message=format!("{} = ", self.key());
+
+        for part in self.parts() {
+            match part {
+                Part::Placeholder(name) => message.push_str(&format!("{{ ${} }}", name)),
+                Part::Text(text) => {
+                    for c in text.chars() {
+                        match c {
+                            '{' => message.push_str("{\"{\"}"),
+                            '}' => message.push_str("{\"}\"}"),
+                            '\n' => message.push_str("\n    "),
+                            c => message.push(c),
+                        }
+                    }
+                },
+            }
+        }
+
+        message
+    }
+
+    //splits template into text and placeholders, "{{" and "}}" become text "{" and "}"
+    fn parts(&self) -> Vec<Part> {
+        let template=self.template;
+        let mut parts=Vec::new();
+        //argument 0 is ErrorInfo, that is written on its own line before message
+        let mut next_index=1;
+        let mut start=0;
+        let mut pos=0;
+        let bytes=template.as_bytes();
+
+        while pos<bytes.len() {
+            match bytes[pos] {
+                b'{' | b'}' if bytes.get(pos+1)==Some(&bytes[pos]) => {
+                    parts.push(Part::Text(&template[start..pos+1]));
+                    pos+=2;
+                    start=pos;
+                },
+                b'{' => {
+                    parts.push(Part::Text(&template[start..pos]));
+
+                    let end=match template[pos..].find('}') {
+                        Some( end ) => pos+end,
+                        None => break,
+                    };
+                    let argument=template[pos+1..end].split(':').next().unwrap_or("").trim();
+                    let index=if argument.is_empty() {
+                        next_index+=1;
+                        next_index-1
+                    }else{
+                        argument.parse::<usize>().unwrap_or(0)
+                    };
+
+                    parts.push(Part::Placeholder(match index {
+                        0 => "error_info",
+                        index => self.fields.get(index-1).cloned().unwrap_or("error_info"),
+                    }));
+
+                    pos=end+1;
+                    start=pos;
+                },
+                _ => pos+=1,
+            }
+        }
+
+        if start<template.len() {
+            parts.push(Part::Text(&template[start..]));
+        }
+
+        parts
+    }
+}
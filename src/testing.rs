@@ -0,0 +1,77 @@
+//!Generation of errors for property tests(feature "testing").
+//!
+//!Option `#[nes(arbitrary)]` of define_error!() implements `proptest::arbitrary::Arbitrary` for the error, so property tests
+//!may exercise error-handling branches and serialization round-trips with any variant. All fields must implement Arbitrary,
+//!ErrorInfo is dummy, it is created by `ErrorInfo::new` with file `<test>` and zero line and column.
+//!Recursive errors(with field `Box<Self>`) can not be generated this way.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!extern crate proptest;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use proptest::prelude::*;
+//!
+//!define_error!( #[nes(arbitrary)] RequestError,
+//!    NotFound(path:String) => "page {} is not found",
+//!    TooLarge(size:u64, limit:u64) => "request of {} bytes is larger than {}",
+//!    Timeout() => "timeout"
+//!);
+//!
+//!fn status(error:&RequestError) -> u16 {
+//!    match *error {
+//!        RequestError::NotFound(..) => 404,
+//!        RequestError::TooLarge(..) => 413,
+//!        RequestError::Timeout(..) => 504,
+//!    }
+//!}
+//!
+//!proptest! {
+//!    fn status_is_error(error in any::<RequestError>()) {
+//!        prop_assert!(status(&error)>=400);
+//!        prop_assert!(!error.to_string().is_empty());
+//!    }
+//!}
+//!
+//!fn main() { status_is_error(); }
+//! ```
+//!
+//!Unit tests of handlers may create errors without macros and real call sites by fixtures: `#[nes(fixture = fixture_no_arguments)]`
+//!generates function `fixture_no_arguments(fields...)`, that creates variant with dummy ErrorInfo.
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!
+//!define_error!( CommonError,
+//!    #[nes(fixture = fixture_no_arguments)]
+//!    NoArguments() => "no arguments",
+//!    #[nes(fixture = fixture_incorrect_extension)]
+//!    IncorrectExtension(file_name:String, extension:String) => "Expected extension \"{2}\" for file \"{1}\""
+//!);
+//!
+//!fn exit_code(error:&CommonError) -> i32 {
+//!    match *error {
+//!        CommonError::NoArguments(..) => 2,
+//!        CommonError::IncorrectExtension(..) => 3,
+//!    }
+//!}
+//!
+//!fn main() {
+//!    assert_eq!(exit_code(&CommonError::fixture_no_arguments()), 2);
+//!
+//!    let error=CommonError::fixture_incorrect_extension("main.c".to_string(), ".rs".to_string());
+//!    assert_eq!(exit_code(&error), 3);
+//!    assert_eq!(error.to_string(), "<test> 0:0\nExpected extension \".rs\" for file \"main.c\"");
+//!}
+//! ```
+
+use ErrorInfoTrait;
+
+///Returns dummy ErrorInfo of generated errors and fixtures.
+pub fn error_info<I:ErrorInfoTrait>() -> I {
+    I::new("<test>", 0, 0)
+}
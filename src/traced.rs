@@ -0,0 +1,139 @@
+//!Foreign errors with location of `?`.
+//!
+//!`?` does not capture location, so try!() is used for std errors, when location is needed. `Traced<E>` is created from E
+//!by From with `#[track_caller]`, that is called by `?`, so it stores ErrorInfo with location of `?` automatically.
+//!It may be returned from functions or used as field of variant, its location is shown in Display and in error trail.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait,Traced};
+//!
+//!define_error!( ConfigError,
+//!    ReadError(io_error:Traced<std::io::Error>) => "can not read config: {}"
+//!);
+//!
+//!impl_from_error!(Traced<std::io::Error> => ConfigError::ReadError);
+//!
+//!fn read(file:&str) -> Result<String, Traced<std::io::Error>> {
+//!    let text=std::fs::read_to_string(file)?; //location of this line is captured
+//!    Ok(text)
+//!}
+//!
+//!fn load(file:&str) -> result![String,ConfigError] {
+//!    ok!(read(file)?)
+//!}
+//!
+//!fn main() {
+//!    let error=read("no_file.rs").unwrap_err();
+//!    assert!(error.error_info().file().ends_with(".rs"));
+//!    assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+//!
+//!    println!("{}", load("no_file.rs").unwrap_err());
+//!}
+//! ```
+
+use std::any;
+use std::error::Error;
+use std::fmt;
+use std::ops::{Deref,DerefMut};
+
+use {caller_error_info,ErrorInfo,ErrorMetadata,ErrorTrail,Severity,TrailItem};
+
+///Foreign error with location, where it has been converted by `?`.
+pub struct Traced<E> {
+    error_info:ErrorInfo,
+    error:E
+}
+
+impl<E> Traced<E> {
+    #[track_caller]
+    pub fn new(error:E) -> Self {
+        Traced {
+            error_info:caller_error_info(),
+            error
+        }
+    }
+
+    pub fn error_info(&self) -> &ErrorInfo {
+        &self.error_info
+    }
+
+    pub fn into_inner(self) -> E {
+        self.error
+    }
+}
+
+impl<E:Error> From<E> for Traced<E> {
+    #[track_caller]
+    fn from(error:E) -> Self {
+        Traced::new(error)
+    }
+}
+
+impl<E> Deref for Traced<E> {
+    type Target = E;
+
+    fn deref(&self) -> &E {
+        &self.error
+    }
+}
+
+impl<E> DerefMut for Traced<E> {
+    fn deref_mut(&mut self) -> &mut E {
+        &mut self.error
+    }
+}
+
+impl<E:Clone> Clone for Traced<E> {
+    fn clone(&self) -> Self {
+        Traced {
+            error_info:self.error_info,
+            error:self.error.clone()
+        }
+    }
+}
+
+impl<E:fmt::Display> fmt::Display for Traced<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        ::report::fmt_limited(f, |f| write!(f, "{}\n{}", self.error_info, self.error))
+    }
+}
+
+impl<E:fmt::Debug> fmt::Debug for Traced<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        ::report::fmt_limited(f, |f| write!(f, "{}\n{:?}", self.error_info, self.error))
+    }
+}
+
+impl<E:Error> Error for Traced<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.error.source()
+    }
+}
+
+impl<E> ErrorTrail for Traced<E> {
+    fn push_error_trail(&self, trail:&mut Vec<TrailItem>) {
+        trail.push(TrailItem {
+            error_name:"Traced",
+            variant_name:any::type_name::<E>(),
+            error_info:self.error_info
+        });
+    }
+}
+
+impl<E:fmt::Display> ErrorMetadata for Traced<E> {
+    fn error_name(&self) -> &'static str { "Traced" }
+    fn variant_name(&self) -> &'static str { any::type_name::<E>() }
+    fn location(&self) -> ErrorInfo { self.error_info }
+    fn code(&self) -> Option<&'static str> { None }
+    fn help(&self) -> Option<&'static str> { None }
+    fn url(&self) -> Option<&'static str> { None }
+    fn severity(&self) -> Severity { Severity::Error }
+
+    fn fmt_message(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
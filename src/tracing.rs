@@ -0,0 +1,77 @@
+//!Capture of tracing span and distributed trace ids(feature "tracing").
+//!
+//!Standard ErrorInfo captures id of current tracing span, when error_info!() creates it, so errors in logs
+//!can be linked to spans. Span id is returned by `ErrorInfoTrait::span_id()`, it is written by Logfmt and serialized with location.
+//!
+//!tracing has no trace ids, they are given by OpenTelemetry layer, so trace id is captured only if provider is set
+//!by `set_trace_id_provider()`, for example, provider may return trace id of current OpenTelemetry context.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!extern crate tracing;
+//!extern crate tracing_subscriber;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!
+//!define_error!( HandlerError,
+//!    NotFound(path:String) => "page {} is not found"
+//!);
+//!
+//!fn handle(path:&str) -> result![HandlerError] {
+//!    let _span=tracing::info_span!("handle", path).entered();
+//!    err!(HandlerError::NotFound, path.to_string())
+//!}
+//!
+//!fn main() {
+//!    tracing_subscriber::fmt().init();
+//!    nes::tracing::set_trace_id_provider(|| Some(0x4bf92f3577b34da6a3ce929d0e0e4736));
+//!
+//!    let mut error=handle("/index.html").unwrap_err();
+//!    assert!(error.get_error_info().span_id().is_some());
+//!    assert_eq!(error.get_error_info().trace_id(), Some(0x4bf92f3577b34da6a3ce929d0e0e4736));
+//!}
+//! ```
+
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool,Ordering};
+
+use super::__private::tracing::dispatcher;
+
+///Function, that returns id of current distributed trace.
+pub type TraceIdProvider = fn() -> Option<u128>;
+
+static TRACE_ID_PROVIDER:RwLock<Option<TraceIdProvider>> = RwLock::new(None);
+//errors are created without lock, until provider is set
+static HAS_PROVIDER:AtomicBool = AtomicBool::new(false);
+
+///Sets function, that returns id of current distributed trace, ErrorInfo calls it, when error is created.
+pub fn set_trace_id_provider(provider:TraceIdProvider) {
+    match TRACE_ID_PROVIDER.write() {
+        Ok( mut guard ) => *guard=Some(provider),
+        Err( poisoned ) => *poisoned.into_inner()=Some(provider),
+    }
+
+    HAS_PROVIDER.store(true, Ordering::Release);
+}
+
+///Returns id of current tracing span.
+pub fn current_span_id() -> Option<u64> {
+    //unlike Span::current() it does not clone the span
+    dispatcher::get_default(|dispatch| dispatch.current_span().id().map(|id| id.into_u64()))
+}
+
+///Returns id of current distributed trace, if provider is set.
+pub fn current_trace_id() -> Option<u128> {
+    if !HAS_PROVIDER.load(Ordering::Acquire) {
+        return None;
+    }
+
+    let provider=match TRACE_ID_PROVIDER.read() {
+        Ok( guard ) => *guard,
+        Err( poisoned ) => *poisoned.into_inner(),
+    };
+
+    provider.and_then(|provider| provider())
+}
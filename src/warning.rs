@@ -0,0 +1,210 @@
+//!Warnings: problems, that do not stop the work, for "completed with warnings" results.
+//!
+//!Warnings are defined by define_error!() like errors and are located by the same ErrorInfo, but they are not returned by Err.
+//!Function pushes them into `Warnings` by `push_warning!(warnings, Warning::Variant, args)` and returns Ok, caller decides,
+//!what to do with them: show the list, report them or turn into the error. `warnings.report()` sends each warning to registered
+//!reporters with severity Warning, even if the variant has no `#[nes(severity = Warning)]`.
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait,Severity};
+//!use nes::reporter::{Reporter,ReportedError};
+//!use nes::warning::Warnings;
+//!
+//!define_error!( ImportWarning,
+//!    EmptyRow(row:usize) => "row {} is empty, it is skipped",
+//!    UnknownColumn(name:String) => "column {} is unknown, it is ignored"
+//!);
+//!
+//!fn import(rows:&[&str], warnings:&mut Warnings<ImportWarning>) -> result![usize,ImportWarning] {
+//!    let mut imported=0;
+//!
+//!    for (row, text) in rows.iter().enumerate() {
+//!        if text.is_empty() {
+//!            push_warning!(warnings, ImportWarning::EmptyRow, row+1);
+//!        }else{
+//!            imported+=1;
+//!        }
+//!    }
+//!
+//!    ok!(imported)
+//!}
+//!
+//!struct WarningReporter;
+//!
+//!impl Reporter for WarningReporter {
+//!    fn report(&self, error:&dyn ReportedError) {
+//!        assert_eq!(error.severity(), Severity::Warning);
+//!    }
+//!}
+//!
+//!fn main() {
+//!    nes::add_reporter(WarningReporter);
+//!
+//!    let mut warnings=Warnings::new();
+//!    assert_eq!(import(&["a", "", "b", ""], &mut warnings).unwrap(), 2);
+//!
+//!    let text=warnings.to_string();
+//!    assert!(text.starts_with("2 warnings:\n  [1] "));
+//!    assert!(text.ends_with("\n      row 4 is empty, it is skipped"));
+//!    warnings.report();
+//!}
+//! ```
+//!
+//!Output:
+//!
+//! ```text
+//!2 warnings:
+//!  [1] main/src/main.rs 25:12
+//!      row 2 is empty, it is skipped
+//!  [2] main/src/main.rs 25:12
+//!      row 4 is empty, it is skipped
+//! ```
+
+use std::any::{Any,TypeId};
+use std::fmt::{self,Write};
+use std::iter::FromIterator;
+use std::ops::Deref;
+
+use {ErrorInfo,ErrorMetadata,ErrorTrail,Severity,TrailItem};
+use aggregate::Indented;
+use context::ContextItem;
+#[cfg(feature = "reporters")]
+use reporter::{self,ReportedError};
+use section::Section;
+
+///Accumulator of warnings, functions push warnings into it and still return Ok.
+#[derive(Clone, PartialEq)]
+pub struct Warnings<W>(Vec<W>);
+
+impl<W> Warnings<W> {
+    pub fn new() -> Self {
+        Warnings(Vec::new())
+    }
+
+    pub fn push(&mut self, warning:W) {
+        self.0.push(warning)
+    }
+
+    ///Moves warnings of other accumulator(for example, of nested step) into this one.
+    pub fn append(&mut self, other:&mut Warnings<W>) {
+        self.0.append(&mut other.0)
+    }
+
+    pub fn into_vec(self) -> Vec<W> {
+        self.0
+    }
+}
+
+#[cfg(feature = "reporters")]
+impl<W:ReportedError> Warnings<W> {
+    ///Sends each warning to registered reporters, severity of the warning is Warning.
+    pub fn report(&self) {
+        for warning in self.0.iter() {
+            reporter::report(&AsWarning(warning));
+        }
+    }
+}
+
+impl<W> Default for Warnings<W> {
+    fn default() -> Self {
+        Warnings::new()
+    }
+}
+
+impl<W> Deref for Warnings<W> {
+    type Target = [W];
+
+    fn deref(&self) -> &[W] { &self.0 }
+}
+
+impl<W> Extend<W> for Warnings<W> {
+    fn extend<I:IntoIterator<Item = W>>(&mut self, iter:I) {
+        self.0.extend(iter)
+    }
+}
+
+impl<W> FromIterator<W> for Warnings<W> {
+    fn from_iter<I:IntoIterator<Item = W>>(iter:I) -> Self {
+        Warnings(iter.into_iter().collect())
+    }
+}
+
+impl<W> IntoIterator for Warnings<W> {
+    type Item = W;
+    type IntoIter = std::vec::IntoIter<W>;
+
+    fn into_iter(self) -> Self::IntoIter { self.0.into_iter() }
+}
+
+impl<'a, W> IntoIterator for &'a Warnings<W> {
+    type Item = &'a W;
+    type IntoIter = std::slice::Iter<'a, W>;
+
+    fn into_iter(self) -> Self::IntoIter { self.0.iter() }
+}
+
+impl<W:fmt::Display> fmt::Display for Warnings<W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0.len() {
+            0 => return write!(f, "no warnings"),
+            1 => write!(f, "1 warning:")?,
+            count => write!(f, "{} warnings:", count)?,
+        }
+
+        for (index, warning) in self.0.iter().enumerate() {
+            write!(f, "\n  [{}] ", index+1)?;
+            write!(Indented { f, indent:"      " }, "{}", warning)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<W:fmt::Debug> fmt::Debug for Warnings<W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.0.iter()).finish()
+    }
+}
+
+//warning for reporters, it is the same error with severity Warning
+#[cfg_attr(not(feature = "reporters"), allow(dead_code))]
+struct AsWarning<'a, W:'a>(&'a W);
+
+impl<'a, W:fmt::Display> fmt::Display for AsWarning<'a, W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<'a, W:ErrorTrail> ErrorTrail for AsWarning<'a, W> {
+    fn push_error_trail(&self, trail:&mut Vec<TrailItem>) {
+        self.0.push_error_trail(trail)
+    }
+}
+
+impl<'a, W:ErrorMetadata> ErrorMetadata for AsWarning<'a, W> {
+    fn error_name(&self) -> &'static str { self.0.error_name() }
+    fn variant_name(&self) -> &'static str { self.0.variant_name() }
+    fn location(&self) -> ErrorInfo { self.0.location() }
+    fn code(&self) -> Option<&'static str> { self.0.code() }
+    fn help(&self) -> Option<&'static str> { self.0.help() }
+    fn url(&self) -> Option<&'static str> { self.0.url() }
+
+    fn severity(&self) -> Severity {
+        match self.0.severity() {
+            Severity::Error => Severity::Warning,
+            severity => severity,
+        }
+    }
+
+    fn fmt_message(&self, f: &mut fmt::Formatter) -> fmt::Result { self.0.fmt_message(f) }
+    fn context(&self) -> &[ContextItem] { self.0.context() }
+    fn note(&self) -> Option<&'static str> { self.0.note() }
+    fn warning(&self) -> Option<&'static str> { self.0.warning() }
+    fn sections(&self) -> &[Section] { self.0.sections() }
+    fn typed_context(&self, type_id:TypeId) -> Option<&(dyn Any + Send + Sync)> { self.0.typed_context(type_id) }
+}
@@ -0,0 +1,96 @@
+//!Reporter for browsers(feature "wasm").
+//!
+//!Stderr of wasm32-unknown-unknown goes nowhere, so errors of Yew or Leptos apps are lost. `ConsoleReporter` writes reported errors
+//!to the console of the browser by `console.error`, the first argument is the full chain with line breaks, the second is the object
+//!with fields of the error, that may be expanded in devtools:
+//!
+//! ```text
+//!{ error: "LoadError", variant: "FetchFailed", file: "app/src/api.rs", line: 42, col: 9, severity: "error",
+//!  code: "E0102", message: "can not fetch /api/users", trail: [ { error, variant, file, line, col }, .. ] }
+//! ```
+//!
+//! # Example
+//!
+//! ```no_run
+//!#[macro_use]
+//!extern crate nes;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use nes::wasm::ConsoleReporter;
+//!
+//!define_error!( LoadError,
+//!    FetchFailed(url:String) => "can not fetch {}"
+//!);
+//!
+//!fn main() {
+//!    nes::add_reporter(ConsoleReporter);
+//!    nes::install_panic_hook();
+//!
+//!    let error:LoadError=create_err!(LoadError::FetchFailed, "/api/users".to_string());
+//!    error.report();
+//!}
+//! ```
+
+use super::__private::js_sys::{Array,Object,Reflect};
+use super::__private::wasm_bindgen::JsValue;
+use super::__private::wasm_bindgen::prelude::wasm_bindgen;
+
+use {ErrorInfo,ErrorInfoTrait,Severity,TrailItem};
+use reporter::{Reporter,ReportedError,Message};
+
+#[wasm_bindgen(wasm_bindgen = crate::__private::wasm_bindgen)]
+extern "C" {
+    #[wasm_bindgen(js_namespace = console, js_name = error)]
+    fn console_error(message:&str, error:&JsValue);
+}
+
+///Reporter, that writes errors to the console of the browser by `console.error`.
+pub struct ConsoleReporter;
+
+impl Reporter for ConsoleReporter {
+    fn report(&self, error:&dyn ReportedError) {
+        console_error(&error.to_string(), &to_object(error));
+    }
+}
+
+///Returns JS object with fields of the error and its trail, it is the second argument of `console.error`.
+pub fn to_object(error:&dyn ReportedError) -> JsValue {
+    let object=location_object(error.error_name(), error.variant_name(), &error.location());
+
+    let severity=match error.severity() {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Advice => "advice",
+    };
+
+    set(&object, "severity", &JsValue::from_str(severity));
+
+    if let Some( code )=error.code() {
+        set(&object, "code", &JsValue::from_str(code));
+    }
+
+    set(&object, "message", &JsValue::from_str(&Message(error).to_string()));
+
+    let trail=Array::new();
+
+    for TrailItem{ error_name, variant_name, ref error_info } in error.error_trail() {
+        trail.push(&location_object(error_name, variant_name, error_info));
+    }
+
+    set(&object, "trail", &trail);
+    object.into()
+}
+
+fn location_object(error_name:&str, variant_name:&str, error_info:&ErrorInfo) -> Object {
+    let object=Object::new();
+    set(&object, "error", &JsValue::from_str(error_name));
+    set(&object, "variant", &JsValue::from_str(variant_name));
+    set(&object, "file", &JsValue::from_str(error_info.file()));
+    set(&object, "line", &JsValue::from(error_info.line()));
+    set(&object, "col", &JsValue::from(error_info.col()));
+    object
+}
+
+fn set(object:&Object, key:&str, value:&JsValue) {
+    //set fails only for frozen objects
+    let _=Reflect::set(object, &JsValue::from_str(key), value);
+}
@@ -0,0 +1,226 @@
+//!Compact binary wire format of errors(feature "wire").
+//!
+//!Errors, that are defined with option `#[nes(wire)]`, implement WireFormat, so they can be shipped between services
+//!or from embedded devices and decoded back into the enum. Wrap error into `Wire` and encode it by any serde format,
+//!format is compact with bincode and postcard, because the error is written as tuples without names of fields:
+//!
+//! ```text
+//!(header:[b'N', b'E', b'S', WIRE_VERSION], (variant index:u32, file:&str, line:u32, col:u32, (fields...)))
+//! ```
+//!
+//!Decoding checks the header and returns error of format, if it is not nes error or version is unknown.
+//!All fields must implement Serialize and Deserialize, fields, that are nes errors(also in Box or Arc), must be defined with `#[nes(wire)]` too.
+//!Variant is encoded by index, so receiver must have the same order of variants.
+//!
+//!ErrorInfo stores file as `&'static str`, so decoded names of files are interned: each name is allocated once and never freed.
+//!Interner keeps at most `MAX_INTERNED_FILES` names of up to `MAX_FILE_LENGTH` bytes, so peer, that sends errors with
+//!random names, can not grow memory, other names are decoded as `REMOTE_FILE`("<remote>").
+//!
+//! # Example
+//!
+//! ```
+//!#[macro_use]
+//!extern crate nes;
+//!extern crate postcard;
+//!use nes::{ErrorInfo,ErrorInfoTrait};
+//!use nes::wire::Wire;
+//!
+//!define_error!( #[nes(wire)] SensorError,
+//!    OutOfRange(value:i32, max:i32) => "value {} is out of range 0..{}"
+//!);
+//!
+//!fn main() {
+//!    let error:SensorError=create_err!(SensorError::OutOfRange, 300, 255);
+//!    let bytes=postcard::to_allocvec(&Wire(error)).unwrap();
+//!
+//!    let decoded:Wire<SensorError>=postcard::from_bytes(&bytes).unwrap();
+//!    println!("{}",decoded.0);
+//!}
+//! ```
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use super::__private::Interner;
+use super::__private::serde::de::{self,Deserialize,DeserializeOwned,Deserializer,SeqAccess,Visitor};
+use super::__private::serde::ser::{Serialize,SerializeTuple,Serializer};
+
+///Version of wire format, it is written in the header.
+pub const WIRE_VERSION:u8 = 1;
+
+const MAGIC:[u8;3] = *b"NES";
+
+///This trait is implemented by define_error!() for errors with option `#[nes(wire)]`.
+///Methods write and read the body of error without header, use `Wire` to encode and decode errors.
+pub trait WireFormat: Sized {
+    fn serialize_wire<S:Serializer>(&self, serializer:S) -> Result<S::Ok, S::Error>;
+    fn deserialize_wire<'de, D:Deserializer<'de>>(deserializer:D) -> Result<Self, D::Error>;
+}
+
+impl<T:WireFormat> WireFormat for Box<T> {
+    fn serialize_wire<S:Serializer>(&self, serializer:S) -> Result<S::Ok, S::Error> {
+        (**self).serialize_wire(serializer)
+    }
+
+    fn deserialize_wire<'de, D:Deserializer<'de>>(deserializer:D) -> Result<Self, D::Error> {
+        T::deserialize_wire(deserializer).map(Box::new)
+    }
+}
+
+impl<T:WireFormat> WireFormat for Arc<T> {
+    fn serialize_wire<S:Serializer>(&self, serializer:S) -> Result<S::Ok, S::Error> {
+        (**self).serialize_wire(serializer)
+    }
+
+    fn deserialize_wire<'de, D:Deserializer<'de>>(deserializer:D) -> Result<Self, D::Error> {
+        T::deserialize_wire(deserializer).map(Arc::new)
+    }
+}
+
+///Error with header of wire format, it implements Serialize and Deserialize by WireFormat of error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Wire<E>(pub E);
+
+impl<E:WireFormat> Serialize for Wire<E> {
+    fn serialize<S:Serializer>(&self, serializer:S) -> Result<S::Ok, S::Error> {
+        let mut tuple=serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&[MAGIC[0], MAGIC[1], MAGIC[2], WIRE_VERSION])?;
+        tuple.serialize_element(&Body(&self.0))?;
+        tuple.end()
+    }
+}
+
+impl<'de, E:WireFormat> Deserialize<'de> for Wire<E> {
+    fn deserialize<D:Deserializer<'de>>(deserializer:D) -> Result<Self, D::Error> {
+        struct WireVisitor<E>(PhantomData<E>);
+
+        impl<'de, E:WireFormat> Visitor<'de> for WireVisitor<E> {
+            type Value = Wire<E>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "nes error")
+            }
+
+            fn visit_seq<A:SeqAccess<'de>>(self, mut seq:A) -> Result<Wire<E>, A::Error> {
+                let header:[u8;4]=seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+
+                if header[..3]!=MAGIC {
+                    return Err(de::Error::custom("it is not nes error"));
+                }
+
+                if header[3]!=WIRE_VERSION {
+                    return Err(de::Error::custom(format_args!("unsupported version {} of nes wire format", header[3])));
+                }
+
+                let body:OwnedBody<E>=seq.next_element()?.ok_or_else(|| de::Error::invalid_length(1, &self))?;
+
+                Ok(Wire(body.0))
+            }
+        }
+
+        deserializer.deserialize_tuple(2, WireVisitor(PhantomData))
+    }
+}
+
+///Max count of interned names of files.
+pub const MAX_INTERNED_FILES:usize = 4096;
+///Max length of interned name of file in bytes.
+pub const MAX_FILE_LENGTH:usize = 512;
+///Name of file, that is returned, when the interner is full or name is too long.
+pub const REMOTE_FILE:&str = "<remote>";
+
+///Returns name of file with static lifetime, each name is allocated once. If `MAX_INTERNED_FILES` names are interned
+///or name is longer than `MAX_FILE_LENGTH`, `REMOTE_FILE` is returned.
+pub fn intern_file(file:&str) -> &'static str {
+    static FILES:Interner = Interner::new(MAX_INTERNED_FILES, MAX_FILE_LENGTH);
+
+    FILES.intern(file).unwrap_or(REMOTE_FILE)
+}
+
+#[doc(hidden)]
+pub struct Body<'a, E:'a>(&'a E);
+
+impl<'a, E:WireFormat> Serialize for Body<'a, E> {
+    fn serialize<S:Serializer>(&self, serializer:S) -> Result<S::Ok, S::Error> {
+        self.0.serialize_wire(serializer)
+    }
+}
+
+#[doc(hidden)]
+pub struct OwnedBody<E>(E);
+
+impl<'de, E:WireFormat> Deserialize<'de> for OwnedBody<E> {
+    fn deserialize<D:Deserializer<'de>>(deserializer:D) -> Result<Self, D::Error> {
+        E::deserialize_wire(deserializer).map(OwnedBody)
+    }
+}
+
+//Fields, that are nes errors, are written by WireFormat, other fields by serde, it is selected by autoref.
+//Writing: (&WireField(field)).wire_value() returns value to serialize.
+//Reading: (&WireFieldType::<T>::new()).next_field(&mut seq) reads next element of sequence.
+#[doc(hidden)]
+pub struct WireField<'a, T:'a>(pub &'a T);
+
+#[doc(hidden)]
+pub trait WireViaNes<'a> {
+    type Value;
+    fn wire_value(&self) -> Self::Value;
+}
+
+impl<'a, T:WireFormat> WireViaNes<'a> for WireField<'a, T> {
+    type Value = Body<'a, T>;
+    fn wire_value(&self) -> Body<'a, T> {
+        Body(self.0)
+    }
+}
+
+#[doc(hidden)]
+pub trait WireViaSerde<'a> {
+    type Value;
+    fn wire_value(&self) -> Self::Value;
+}
+
+impl<'a, T:Serialize> WireViaSerde<'a> for &WireField<'a, T> {
+    type Value = &'a T;
+    fn wire_value(&self) -> &'a T {
+        self.0
+    }
+}
+
+#[doc(hidden)]
+pub struct WireFieldType<T>(PhantomData<T>);
+
+impl<T> WireFieldType<T> {
+    pub fn new() -> Self {
+        WireFieldType(PhantomData)
+    }
+}
+
+impl<T> Default for WireFieldType<T> {
+    fn default() -> Self {
+        WireFieldType::new()
+    }
+}
+
+#[doc(hidden)]
+pub trait WireTypeViaNes<T> {
+    fn next_field<'de, A:SeqAccess<'de>>(&self, seq:&mut A) -> Result<Option<T>, A::Error>;
+}
+
+impl<T:WireFormat> WireTypeViaNes<T> for WireFieldType<T> {
+    fn next_field<'de, A:SeqAccess<'de>>(&self, seq:&mut A) -> Result<Option<T>, A::Error> {
+        Ok(seq.next_element::<OwnedBody<T>>()?.map(|body| body.0))
+    }
+}
+
+#[doc(hidden)]
+pub trait WireTypeViaSerde<T> {
+    fn next_field<'de, A:SeqAccess<'de>>(&self, seq:&mut A) -> Result<Option<T>, A::Error>;
+}
+
+impl<T:DeserializeOwned> WireTypeViaSerde<T> for &WireFieldType<T> {
+    fn next_field<'de, A:SeqAccess<'de>>(&self, seq:&mut A) -> Result<Option<T>, A::Error> {
+        seq.next_element::<T>()
+    }
+}
@@ -0,0 +1,80 @@
+//!Errors with Copy, `&'static str` and `Cow<'static, str>` fields are created without heap allocation.
+
+#[macro_use]
+extern crate nes;
+use nes::{ErrorInfo,ErrorInfoTrait};
+
+use std::alloc::{GlobalAlloc,Layout,System};
+use std::borrow::Cow;
+use std::hint::black_box;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize,Ordering};
+
+//allocator, that counts allocations, so the test fails, if construction allocates
+struct CountingAllocator;
+
+static ALLOCATIONS:AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout:Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr:*mut u8, layout:Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR:CountingAllocator = CountingAllocator;
+
+//tests run in parallel threads, so allocations are counted by one test at a time
+static SERIAL:Mutex<()> = Mutex::new(());
+
+define_error!( BenchError,
+    Empty() => "empty",
+    Code(code:u32, retry:bool) => "code {}, retry {}",
+    Name(name:&'static str) => "name {}",
+    Text(text:Cow<'static, str>) => "text {}"
+);
+
+fn code(code:u32) -> result![u32,BenchError] {
+    err!(BenchError::Code, code, false)
+}
+
+fn assert_no_allocations<T, F:FnMut() -> T>(name:&str, mut construct:F) {
+    let _serial=SERIAL.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let allocations=ALLOCATIONS.load(Ordering::Relaxed);
+
+    for _ in 0..1000 {
+        black_box(construct());
+    }
+
+    assert_eq!(ALLOCATIONS.load(Ordering::Relaxed)-allocations, 0, "{} allocates", name);
+}
+
+#[test]
+fn create_err_without_fields() {
+    assert_no_allocations("create_err!() without fields", || -> BenchError { create_err!(BenchError::Empty) });
+}
+
+#[test]
+fn create_err_with_copy_fields() {
+    assert_no_allocations("create_err!() with Copy fields", || -> BenchError { create_err!(BenchError::Code, black_box(42), true) });
+}
+
+#[test]
+fn create_err_with_static_str() {
+    assert_no_allocations("create_err!() with &'static str", || -> BenchError { create_err!(BenchError::Name, "config") });
+}
+
+#[test]
+fn create_err_with_cow() {
+    assert_no_allocations("create_err!() with Cow", || -> BenchError { create_err!(BenchError::Text, "config") });
+}
+
+#[test]
+fn err() {
+    assert_no_allocations("err!()", || code(black_box(42)));
+}